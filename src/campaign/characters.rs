@@ -0,0 +1,1410 @@
+//! Player characters
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Ayla             Fighter 1   19/19 HP   1 Armor    3/8 XP ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ STR 16 (+1) Weak    DEX 15 (+1)         CON  9 (+0)        ┃
+//! ┃ INT  8 (-1)         WIS 12 (+0)         CHA 13 (+1)        ┃
+//! ┠────────────────────────────────────────────────────────────┨
+//! ┃ Damage d10                                            Good ┃
+//! ┠────────────────────────────────────────────────────────────┨
+//! ┃ • Bend Bars, Lift Gates                                    ┃
+//! ┃ • Armored                                                  ┃
+//! ┃ • Signature Weapon                                         ┃
+//! ┠────────────────────────────────────────────────────────────┨
+//! ┃ Gear                                                       ┃
+//! ┃ • Leather Armor (equipped)                                 ┃
+//! ┃ • Dungeon Rations                                          ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use colored::*;
+use data::card::{Card, Theme};
+use data::{Alignment, Class, Classes, Item, Items, Move, Moves, Spell, Treasure};
+use die::Variables;
+use regex::Regex;
+use serde_json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData, ErrorKind::InvalidInput};
+use std::io::{Read, Write};
+use std::ops::{Deref, DerefMut};
+
+/// The scores a new character assigns to its stats.
+pub const STANDARD_ARRAY: [u8; 6] = [16, 15, 13, 12, 9, 8];
+
+/// Highest level a character can reach.
+pub const MAX_LEVEL: u8 = 10;
+
+/// Wrapper around a `Vec<Character>`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Characters {
+    data: Vec<Character>,
+}
+
+/// A player character.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Character {
+    /// Name of the character.
+    name: String,
+    /// Name of the class, i.e. `Fighter`.
+    class: String,
+    /// Current level.
+    #[serde(default = "one")]
+    level: u8,
+    /// Experience points of the current level.
+    #[serde(default)]
+    xp: u8,
+    /// Score of each stat, from 3 to 18.
+    scores: BTreeMap<Stat, u8>,
+    /// Current HP.
+    hp: u8,
+    /// Maximum HP, the base HP of the class plus the CON score.
+    max_hp: u8,
+    /// Damage die of the class.
+    damage: String,
+    /// Name of the alignment, i.e. `Good`.
+    #[serde(default)]
+    alignment: Option<String>,
+    /// Names of the moves, starting with the ones of the class.
+    #[serde(default)]
+    moves: Vec<String>,
+    /// Names of the spells in the spellbook of a wizard.
+    #[serde(default)]
+    spellbook: Vec<String>,
+    /// Names of the prepared spells, which can be cast.
+    #[serde(default)]
+    prepared: Vec<String>,
+    /// Names of the cast spells which are still ongoing.
+    #[serde(default)]
+    ongoing: Vec<String>,
+    /// Penalty to cast a spell until spells are prepared again.
+    #[serde(default)]
+    spell_penalty: u8,
+    /// Debilities, each gives -1 to the modifier of its stat.
+    #[serde(default)]
+    debilities: BTreeSet<Debility>,
+    /// Items the character carries.
+    #[serde(default)]
+    gear: Vec<Gear>,
+    /// Bonds with the other characters.
+    #[serde(default)]
+    bonds: Vec<Bond>,
+    /// Coins to buy items.
+    #[serde(default)]
+    coins: u32,
+}
+
+/// A debility, giving -1 to the modifier of its stat.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Debility {
+    Weak,
+    Shaky,
+    Sick,
+    Stunned,
+    Confused,
+    Scarred,
+}
+
+/// An item a character carries, possibly worn or wielded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Gear {
+    /// Key of the item.
+    key: String,
+    /// Name of the item.
+    name: String,
+    /// Weight of the item.
+    #[serde(default)]
+    weight: u8,
+    /// Armor of the item.
+    #[serde(default)]
+    armor: u8,
+    /// Whether the armor adds to the one of other items, like the `+1` of a shield.
+    #[serde(default)]
+    adds_armor: bool,
+    /// Whether the item is worn or wielded, only then its armor protects.
+    #[serde(default)]
+    equipped: bool,
+}
+
+/// A bond of a character with another one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Bond {
+    /// Name of the other character.
+    with: String,
+    /// The bond, with the blank of the class template filled in.
+    text: String,
+}
+
+/// What a caster chooses on a 7-9 to cast a spell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastCost {
+    Attention,
+    Penalty,
+    Forget,
+}
+
+/// The stats of a character.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Stat {
+    Str,
+    Dex,
+    Con,
+    Int,
+    Wis,
+    Cha,
+}
+
+impl Stat {
+    /// All stats in the order of the character sheet.
+    pub const ALL: [Stat; 6] = [
+        Stat::Str,
+        Stat::Dex,
+        Stat::Con,
+        Stat::Int,
+        Stat::Wis,
+        Stat::Cha,
+    ];
+    /// Get the abbreviation, i.e. `STR`.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Stat::Str => "STR",
+            Stat::Dex => "DEX",
+            Stat::Con => "CON",
+            Stat::Int => "INT",
+            Stat::Wis => "WIS",
+            Stat::Cha => "CHA",
+        }
+    }
+    /// Get the stat with the given abbreviation, i.e. `STR`, ignoring case.
+    pub fn from_abbreviation(abbreviation: &str) -> Option<Stat> {
+        Stat::ALL
+            .iter()
+            .cloned()
+            .find(|stat| stat.abbreviation().eq_ignore_ascii_case(abbreviation))
+    }
+    /// Parse the scores of all stats in the order of [ALL](Stat::ALL),
+    /// separated by commas or whitespace. [None](Option::None) unless there are six.
+    pub fn parse_scores(s: &str) -> Option<[u8; 6]> {
+        let scores: Vec<u8> = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|score| !score.is_empty())
+            .map(|score| score.parse().ok())
+            .collect::<Option<_>>()?;
+        if scores.len() != 6 {
+            return None;
+        }
+        let mut array = [0; 6];
+        array.copy_from_slice(&scores);
+        Some(array)
+    }
+    /// Get the modifier of the given `score`.
+    pub fn modifier(score: u8) -> i32 {
+        match score {
+            0..=3 => -3,
+            4..=5 => -2,
+            6..=8 => -1,
+            9..=12 => 0,
+            13..=15 => 1,
+            16..=17 => 2,
+            _ => 3,
+        }
+    }
+}
+
+impl Debility {
+    /// All debilities in the order of their stats.
+    pub const ALL: [Debility; 6] = [
+        Debility::Weak,
+        Debility::Shaky,
+        Debility::Sick,
+        Debility::Stunned,
+        Debility::Confused,
+        Debility::Scarred,
+    ];
+    /// Get the name, i.e. `Weak`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Debility::Weak => "Weak",
+            Debility::Shaky => "Shaky",
+            Debility::Sick => "Sick",
+            Debility::Stunned => "Stunned",
+            Debility::Confused => "Confused",
+            Debility::Scarred => "Scarred",
+        }
+    }
+    /// Get the stat whose modifier the debility lowers.
+    pub fn stat(self) -> Stat {
+        match self {
+            Debility::Weak => Stat::Str,
+            Debility::Shaky => Stat::Dex,
+            Debility::Sick => Stat::Con,
+            Debility::Stunned => Stat::Int,
+            Debility::Confused => Stat::Wis,
+            Debility::Scarred => Stat::Cha,
+        }
+    }
+    /// Get the debility with the given name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Debility> {
+        Debility::ALL
+            .iter()
+            .cloned()
+            .find(|debility| debility.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl CastCost {
+    /// All costs in the order of the move.
+    pub const ALL: [CastCost; 3] = [CastCost::Attention, CastCost::Penalty, CastCost::Forget];
+    /// Get the description, i.e. `Forget the spell until spells are prepared again`.
+    pub fn description(self) -> &'static str {
+        match self {
+            CastCost::Attention => "Draw unwelcome attention or put yourself in a spot",
+            CastCost::Penalty => "Take -1 ongoing to cast a spell until spells are prepared again",
+            CastCost::Forget => "Forget the spell until spells are prepared again",
+        }
+    }
+}
+
+impl Gear {
+    /// Create the gear of the given `item`, not yet equipped.
+    pub fn new(item: &Item) -> Self {
+        Gear {
+            key: item.key().to_string(),
+            name: item.name().to_string(),
+            weight: item.weight(),
+            armor: item.armor(),
+            adds_armor: item.adds_armor(),
+            equipped: false,
+        }
+    }
+    /// Set whether the item is worn or wielded.
+    pub fn with_equipped(mut self, equipped: bool) -> Self {
+        self.equipped = equipped;
+        self
+    }
+    /// Get the key of the item.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the name of the item.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the weight of the item.
+    pub fn weight(&self) -> u8 {
+        self.weight
+    }
+    /// Get the armor of the item.
+    pub fn armor(&self) -> u8 {
+        self.armor
+    }
+    /// Whether the item is worn or wielded.
+    pub fn is_equipped(&self) -> bool {
+        self.equipped
+    }
+}
+
+impl Bond {
+    /// Create a bond with the character named `with` from a class `template`,
+    /// i.e. `__________ trusted me with a secret.`, the blanks are filled with the name.
+    pub fn new(template: &str, with: &str) -> Self {
+        let re = Regex::new("_{2,}").unwrap();
+        Bond {
+            with: with.to_string(),
+            text: re.replace_all(template, with).into_owned(),
+        }
+    }
+    /// Get the name of the other character.
+    pub fn with(&self) -> &str {
+        &self.with
+    }
+    /// Get the bond.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Characters {
+    /// Create an empty set of characters.
+    pub fn new() -> Self {
+        Characters::default()
+    }
+    /// Parse the data given through the reader into `Characters`.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|data| Characters { data })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Write the characters as JSON to the given writer,
+    /// which can be read again with [parse](Characters::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.data).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Add the given `character`, replacing the one with the same name.
+    pub fn insert(&mut self, character: Character) {
+        match self.data.iter().position(|c| c.name == character.name) {
+            Some(index) => self.data[index] = character,
+            None => self.data.push(character),
+        }
+    }
+    /// Remove the first character whose name matches the given `regex` and return it.
+    pub fn remove(&mut self, re: &str) -> Option<Character> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let index = self.data.iter().position(|c| re.is_match(&c.name))?;
+        Some(self.data.remove(index))
+    }
+    /// Find the first character whose name matches the given `regex`.
+    pub fn find(&self, re: &str) -> Option<&Character> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter().find(|c| re.is_match(&c.name))
+    }
+    /// Find the first character whose name matches the given `regex` to change it.
+    pub fn find_mut(&mut self, re: &str) -> Option<&mut Character> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter_mut().find(|c| re.is_match(&c.name))
+    }
+    /// Find the character with exactly the given `name`.
+    pub fn get(&self, name: &str) -> Option<&Character> {
+        self.data.iter().find(|c| c.name == name)
+    }
+    /// Find the character with exactly the given `name` to change it.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Character> {
+        self.data.iter_mut().find(|c| c.name == name)
+    }
+    /// Get the names of all characters but the one named `name`,
+    /// the ones it can write bonds with.
+    pub fn others(&self, name: &str) -> Vec<String> {
+        self.data
+            .iter()
+            .map(|c| c.name.clone())
+            .filter(|other| other != name)
+            .collect()
+    }
+    /// List all characters with their class and level.
+    pub fn list(&self) {
+        println!(">> {}", "Characters".bold());
+        self.data.iter().for_each(|c| {
+            println!("   {} ({} {})", c.name, c.class, c.level);
+        });
+    }
+    /// Create the card showing the bonds between the character named `name`
+    /// and everyone else, with the bonus each one rolls to aid or interfere with the other.
+    pub fn web(&self, name: &str) -> Option<Card> {
+        let theme = Theme::current();
+        let character = self.get(name)?;
+        let mut others: Vec<&str> = self
+            .data
+            .iter()
+            .map(|c| c.name.as_str())
+            .filter(|other| *other != name)
+            .collect();
+        for bond in character.bonds.iter() {
+            if !others.contains(&bond.with.as_str()) {
+                others.push(&bond.with);
+            }
+        }
+        let card = Card::new()
+            .with_width(60)
+            .line(&theme.name.paint(&format!("Bonds of {}", name)))
+            .heavy_line();
+        Some(others.iter().enumerate().fold(card, |card, (i, other)| {
+            let theirs: Vec<&Bond> = self
+                .get(other)
+                .map(|c| c.bonds.iter().filter(|bond| bond.with == name).collect())
+                .unwrap_or_default();
+            let bonds: Vec<String> = character
+                .bonds
+                .iter()
+                .filter(|bond| bond.with == *other)
+                .map(|bond| format!("{}: {}", name, bond.text))
+                .chain(
+                    theirs
+                        .iter()
+                        .map(|bond| format!("{}: {}", other, bond.text)),
+                )
+                .collect();
+            let has_bonds = !bonds.is_empty();
+            card.light_line_if(i > 0)
+                .line(&format!(
+                    "{}{{}}{} aids {:+}  {} aids {:+}",
+                    other,
+                    name,
+                    character.bond(other),
+                    other,
+                    theirs.len()
+                ))
+                .list_if(bonds, has_bonds)
+        }))
+    }
+    /// Create the board showing the HP, armor, XP, load, debilities and ongoing spells
+    /// of all characters. Characters below half HP or over their load are highlighted.
+    pub fn party(&self, classes: &Classes) -> Card {
+        let theme = Theme::current();
+        let highlight = |s: String, pred: bool| if pred { theme.hp.paint(&s) } else { s };
+        self.data.iter().enumerate().fold(
+            Card::new()
+                .with_width(72)
+                .line(&theme.name.paint("Party"))
+                .heavy_line(),
+            |card, (i, c)| {
+                let max_load = c.find_class(classes).map(|class| c.max_load(class));
+                let load = match max_load {
+                    Some(max_load) => format!(" {}/{} Load ", c.load(), max_load),
+                    None => format!(" {} Load ", c.load()),
+                };
+                let row = format!(
+                    "{} {}{{}}{}  {} Armor  {}/{} XP {}",
+                    theme.name.paint(&c.name),
+                    theme.class.paint(&format!(" {} {} ", c.class, c.level)),
+                    highlight(format!(" {}/{} HP ", c.hp, c.max_hp), c.is_wounded()),
+                    c.armor(),
+                    c.xp,
+                    c.xp_needed(),
+                    highlight(load, max_load.is_some_and(|max| c.load() > max))
+                );
+                let status: Vec<String> = c
+                    .debilities
+                    .iter()
+                    .map(|debility| debility.name().to_string())
+                    .chain(c.ongoing.iter().map(|spell| format!("{} (ongoing)", spell)))
+                    .collect();
+                card.light_line_if(i > 0)
+                    .line(&row)
+                    .line_if(&format!("  {}", status.join(", ")), !status.is_empty())
+            },
+        )
+    }
+}
+
+impl Character {
+    /// Create a new level 1 character of the given `class`
+    /// with the `scores` of the stats in the order of [Stat::ALL](Stat::ALL).
+    pub fn new(name: &str, class: &Class, scores: [u8; 6]) -> io::Result<Self> {
+        if name.is_empty() {
+            return Err(Error::new(InvalidInput, "A character needs a name"));
+        }
+        if scores.iter().any(|score| *score < 3 || *score > 18) {
+            return Err(Error::new(
+                InvalidInput,
+                "Scores have to be between 3 and 18",
+            ));
+        }
+        let scores: BTreeMap<Stat, u8> = Stat::ALL
+            .iter()
+            .cloned()
+            .zip(scores.iter().cloned())
+            .collect();
+        let max_hp = class.base_hp() + scores[&Stat::Con];
+        let spellbook = if class
+            .starting_moves()
+            .iter()
+            .any(|mv| mv.name() == "Spellbook")
+        {
+            class
+                .spells()
+                .iter()
+                .filter(|spell| spell.level() == 0)
+                .map(|spell| spell.name().to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok(Character {
+            name: name.to_string(),
+            class: class.name().to_string(),
+            level: 1,
+            xp: 0,
+            scores,
+            hp: max_hp,
+            max_hp,
+            damage: class.damage().to_string(),
+            alignment: None,
+            moves: class
+                .starting_moves()
+                .iter()
+                .map(|mv| mv.name().to_string())
+                .collect(),
+            spellbook,
+            prepared: Vec::new(),
+            ongoing: Vec::new(),
+            spell_penalty: 0,
+            debilities: BTreeSet::new(),
+            gear: Vec::new(),
+            bonds: Vec::new(),
+            coins: 0,
+        })
+    }
+    /// Set the name of the alignment, i.e. `Good`.
+    pub fn with_alignment(mut self, alignment: &str) -> Self {
+        self.alignment = Some(alignment.to_string());
+        self
+    }
+    /// Get the name of the character.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the name of the class.
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+    /// Get the current level.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+    /// Get the experience points of the current level.
+    pub fn xp(&self) -> u8 {
+        self.xp
+    }
+    /// Get the experience points needed for the next level, level + 7.
+    pub fn xp_needed(&self) -> u8 {
+        self.level + 7
+    }
+    /// Mark `n` experience points, negative ones take them back.
+    /// Returns the experience points of the current level.
+    pub fn mark_xp(&mut self, n: i32) -> u8 {
+        self.xp = (i32::from(self.xp) + n).max(0).min(i32::from(u8::MAX)) as u8;
+        self.xp
+    }
+    /// Whether the character has enough experience points for the next level.
+    pub fn can_level_up(&self) -> bool {
+        self.level < MAX_LEVEL && self.xp >= self.xp_needed()
+    }
+    /// Get the name of the alignment.
+    pub fn alignment(&self) -> Option<&str> {
+        self.alignment.as_deref()
+    }
+    /// Find the alignment of the character in its `class`.
+    pub fn find_alignment<'a>(&self, class: &'a Class) -> Option<&'a Alignment> {
+        let name = self.alignment()?;
+        class
+            .alignments()
+            .values()
+            .find(|alignment| alignment.name() == name)
+    }
+    /// Get the names of the moves.
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+    /// Whether the character has the move with the given `name`.
+    pub fn knows(&self, name: &str) -> bool {
+        self.moves.iter().any(|mv| mv == name)
+    }
+    /// Find the class of the character in `classes`.
+    pub fn find_class<'a>(&self, classes: &'a Classes) -> Option<&'a Class> {
+        classes.iter().find(|class| class.name() == self.class)
+    }
+    /// Get the moves of `class` the character can choose on the next level.
+    ///
+    /// Levels 2-5 choose from the first advanced moves,
+    /// levels 6-10 from both the first and the second.
+    /// The moves are looked up in `moves`, where `requires` and `replaces` are resolved.
+    /// Moves the character knows, whose requirement it misses
+    /// or which a known move replaces are left out.
+    pub fn advanced_moves<'a>(&self, class: &'a Class, moves: &'a Moves) -> Vec<&'a Move> {
+        let mut choices: Vec<&Move> = class.advanced_moves_1().iter().collect();
+        if self.level >= 5 {
+            choices.extend(class.advanced_moves_2());
+        }
+        let choices: Vec<&Move> = choices
+            .into_iter()
+            .map(|mv| moves.get(mv.key()).unwrap_or(mv))
+            .collect();
+        let replaced: Vec<&str> = choices
+            .iter()
+            .filter(|mv| self.knows(mv.name()))
+            .map(|mv| mv.replaces())
+            .collect();
+        choices
+            .into_iter()
+            .filter(|mv| !self.knows(mv.name()) && !replaced.contains(&mv.name()))
+            .filter(|mv| mv.requires().is_empty() || self.knows(mv.requires()))
+            .collect()
+    }
+    /// Get the names of the spells in the spellbook.
+    pub fn spellbook(&self) -> &[String] {
+        &self.spellbook
+    }
+    /// Get the names of the prepared spells.
+    pub fn prepared(&self) -> &[String] {
+        &self.prepared
+    }
+    /// Get the names of the ongoing spells.
+    pub fn ongoing(&self) -> &[String] {
+        &self.ongoing
+    }
+    /// Get the penalty to cast a spell, one for each ongoing spell
+    /// and the one taken on a 7-9 until spells are prepared again.
+    pub fn spell_penalty(&self) -> u8 {
+        self.ongoing.len() as u8 + self.spell_penalty
+    }
+    /// Get the spells of `class` the character can prepare,
+    /// the ones in the spellbook of a wizard or all up to the level of a cleric.
+    pub fn available_spells<'a>(&self, class: &'a Class) -> Vec<&'a Spell> {
+        class
+            .spells()
+            .into_iter()
+            .filter(|spell| spell.level() <= self.level)
+            .filter(|spell| {
+                !self.knows("Spellbook") || self.spellbook.iter().any(|s| s == spell.name())
+            })
+            .collect()
+    }
+    /// Get the spells of `class` the character can write into the spellbook,
+    /// the ones up to its level which aren't in it yet, but no cantrips.
+    pub fn learnable_spells<'a>(&self, class: &'a Class) -> Vec<&'a Spell> {
+        class
+            .spells()
+            .into_iter()
+            .filter(|spell| spell.level() > 0 && spell.level() <= self.level)
+            .filter(|spell| !self.spellbook.iter().any(|s| s == spell.name()))
+            .collect()
+    }
+    /// Write `spell` into the spellbook, it may not be of a higher level than the character.
+    pub fn learn_spell(&mut self, spell: &Spell) -> io::Result<()> {
+        if spell.level() > self.level {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} is of level {}", spell.name(), spell.level()),
+            ));
+        }
+        if self.spellbook.iter().any(|s| s == spell.name()) {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} is already in the spellbook", spell.name()),
+            ));
+        }
+        self.spellbook.push(spell.name().to_string());
+        Ok(())
+    }
+    /// Write all `spells` into the spellbook, or none if one of them fails.
+    pub fn learn_spells(&mut self, spells: &[&Spell]) -> io::Result<()> {
+        let len = self.spellbook.len();
+        for spell in spells {
+            if let Err(e) = self.learn_spell(spell) {
+                self.spellbook.truncate(len);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Prepare the `spells` of `class` whose levels add up to at most level + 1,
+    /// together with the cantrips or rotes.
+    /// The spells prepared before are lost, ongoing spells end and the penalty is gone.
+    pub fn prepare(&mut self, class: &Class, spells: &[&Spell]) -> io::Result<()> {
+        let available = self.available_spells(class);
+        if let Some(spell) = spells
+            .iter()
+            .find(|spell| !available.iter().any(|s| s.name() == spell.name()))
+        {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} can't prepare {}", self.name, spell.name()),
+            ));
+        }
+        let levels: u32 = spells.iter().map(|spell| u32::from(spell.level())).sum();
+        if levels > u32::from(self.level) + 1 {
+            return Err(Error::new(
+                InvalidInput,
+                format!(
+                    "The levels of the spells add up to {}, more than {}",
+                    levels,
+                    self.level + 1
+                ),
+            ));
+        }
+        self.prepared = available
+            .iter()
+            .filter(|spell| spell.level() == 0)
+            .chain(spells.iter().filter(|spell| spell.level() > 0))
+            .map(|spell| spell.name().to_string())
+            .collect();
+        self.ongoing.clear();
+        self.spell_penalty = 0;
+        Ok(())
+    }
+    /// Whether the spell with the given `name` is prepared.
+    pub fn is_prepared(&self, name: &str) -> bool {
+        self.prepared.iter().any(|spell| spell == name)
+    }
+    /// Find the first prepared spell of `class` whose name matches the given `regex`.
+    pub fn find_prepared<'a>(&self, class: &'a Class, re: &str) -> Option<&'a Spell> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        class
+            .spells()
+            .into_iter()
+            .find(|spell| re.is_match(spell.name()) && self.is_prepared(spell.name()))
+    }
+    /// Get the modifier to cast a spell, the one of the casting stat of `class`
+    /// minus the penalty. [None](Option::None) if the class casts no spells.
+    pub fn cast_modifier(&self, class: &Class) -> Option<i32> {
+        let stat = Stat::from_abbreviation(class.casting_stat()?)?;
+        Some(self.modifier(stat) - i32::from(self.spell_penalty()))
+    }
+    /// Keep track of `spell` while it is ongoing.
+    pub fn start_ongoing(&mut self, spell: &Spell) {
+        if spell.is_ongoing() && !self.ongoing.iter().any(|s| s == spell.name()) {
+            self.ongoing.push(spell.name().to_string());
+        }
+    }
+    /// End the first ongoing spell whose name matches the given `regex` and return its name.
+    pub fn end_ongoing(&mut self, re: &str) -> Option<String> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let index = self.ongoing.iter().position(|spell| re.is_match(spell))?;
+        Some(self.ongoing.remove(index))
+    }
+    /// Forget the spell with the given `name` until spells are prepared again.
+    pub fn forget(&mut self, name: &str) {
+        self.prepared.retain(|spell| spell != name);
+    }
+    /// Take -1 ongoing to cast a spell until spells are prepared again.
+    pub fn take_spell_penalty(&mut self) {
+        self.spell_penalty += 1;
+    }
+    /// Cast the prepared `spell`, paying the `cost` chosen on a 7-9,
+    /// and keep track of it while it is ongoing.
+    pub fn cast(&mut self, spell: &Spell, cost: Option<CastCost>) {
+        match cost {
+            Some(CastCost::Penalty) => self.take_spell_penalty(),
+            Some(CastCost::Forget) => self.forget(spell.name()),
+            Some(CastCost::Attention) | None => (),
+        }
+        self.start_ongoing(spell);
+    }
+    /// Gain a level, raising the score of `stat` by one and learning the move `mv`,
+    /// which replaces the move named in its `replaces`.
+    ///
+    /// Raising CON raises the maximum HP, too.
+    pub fn level_up(&mut self, stat: Stat, mv: &Move) -> io::Result<()> {
+        if !self.can_level_up() {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} needs {} XP to level up", self.name, self.xp_needed()),
+            ));
+        }
+        if self.score(stat) >= 18 {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} is already 18", stat.abbreviation()),
+            ));
+        }
+        if self.knows(mv.name()) {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} already knows {}", self.name, mv.name()),
+            ));
+        }
+        if !mv.requires().is_empty() && !self.knows(mv.requires()) {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} requires {}", mv.name(), mv.requires()),
+            ));
+        }
+        self.xp -= self.xp_needed();
+        self.level += 1;
+        *self.scores.entry(stat).or_insert(10) += 1;
+        if stat == Stat::Con {
+            self.max_hp += 1;
+            self.hp += 1;
+        }
+        let replaces = mv.replaces();
+        self.moves.retain(|known| known != replaces);
+        self.moves.push(mv.name().to_string());
+        Ok(())
+    }
+    /// Get the stats whose score a level up can raise, the ones below 18.
+    pub fn raisable_stats(&self) -> Vec<Stat> {
+        Stat::ALL
+            .iter()
+            .cloned()
+            .filter(|stat| self.score(*stat) < 18)
+            .collect()
+    }
+    /// Get the score of the given `stat`.
+    pub fn score(&self, stat: Stat) -> u8 {
+        self.scores.get(&stat).cloned().unwrap_or(10)
+    }
+    /// Get the modifier of the given `stat`, -1 if the character suffers its debility.
+    pub fn modifier(&self, stat: Stat) -> i32 {
+        let debilitated = self
+            .debilities
+            .iter()
+            .any(|debility| debility.stat() == stat);
+        Stat::modifier(self.score(stat)) - if debilitated { 1 } else { 0 }
+    }
+    /// Get the debilities.
+    pub fn debilities(&self) -> &BTreeSet<Debility> {
+        &self.debilities
+    }
+    /// Suffer the `debility`, returns false if the character already does.
+    pub fn add_debility(&mut self, debility: Debility) -> bool {
+        self.debilities.insert(debility)
+    }
+    /// Recover from the `debility`, returns false if the character didn't suffer it.
+    pub fn remove_debility(&mut self, debility: Debility) -> bool {
+        self.debilities.remove(&debility)
+    }
+    /// Get the bonds with the other characters.
+    pub fn bonds(&self) -> &[Bond] {
+        &self.bonds
+    }
+    /// Write a new bond.
+    pub fn add_bond(&mut self, bond: Bond) {
+        self.bonds.push(bond);
+    }
+    /// Resolve the bond at `index`, removing it from the bonds.
+    pub fn resolve_bond(&mut self, index: usize) -> Option<Bond> {
+        if index < self.bonds.len() {
+            Some(self.bonds.remove(index))
+        } else {
+            None
+        }
+    }
+    /// Resolve the bonds at the `indices`, each one once, and return them.
+    pub fn resolve_bonds(&mut self, indices: &[usize]) -> Vec<Bond> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        // Remove from the back, so the indices stay valid
+        let mut resolved: Vec<Bond> = indices
+            .iter()
+            .rev()
+            .filter_map(|index| self.resolve_bond(*index))
+            .collect();
+        resolved.reverse();
+        resolved
+    }
+    /// Get the number of bonds with the character named `name`,
+    /// the bonus to aid or interfere with them.
+    pub fn bond(&self, name: &str) -> usize {
+        self.bonds.iter().filter(|bond| bond.with == name).count()
+    }
+    /// Get the items the character carries.
+    pub fn gear(&self) -> &[Gear] {
+        &self.gear
+    }
+    /// Carry the `gear`.
+    pub fn add_gear(&mut self, gear: Gear) {
+        self.gear.push(gear);
+    }
+    /// Wear or wield the first carried item whose name matches the given `regex`.
+    pub fn equip(&mut self, re: &str) -> Option<&Gear> {
+        self.set_equipped(re, true)
+    }
+    /// Take off the first carried item whose name matches the given `regex`.
+    pub fn unequip(&mut self, re: &str) -> Option<&Gear> {
+        self.set_equipped(re, false)
+    }
+    /// Set whether the first carried item whose name matches the given `regex` is equipped.
+    fn set_equipped(&mut self, re: &str, equipped: bool) -> Option<&Gear> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let gear = self.gear.iter_mut().find(|gear| re.is_match(&gear.name))?;
+        gear.equipped = equipped;
+        Some(gear)
+    }
+    /// Drop the first item whose name matches the given `regex` and return it.
+    pub fn remove_gear(&mut self, re: &str) -> Option<Gear> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let index = self.gear.iter().position(|gear| re.is_match(&gear.name))?;
+        Some(self.gear.remove(index))
+    }
+    /// Get the coins.
+    pub fn coins(&self) -> u32 {
+        self.coins
+    }
+    /// Gain `n` coins, or lose them if negative, and return the coins.
+    pub fn add_coins(&mut self, n: i32) -> u32 {
+        self.coins = (i64::from(self.coins) + i64::from(n)).max(0) as u32;
+        self.coins
+    }
+    /// Pay `coins`, fails if the character has less.
+    pub fn pay(&mut self, coins: u32) -> io::Result<()> {
+        if coins > self.coins {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} has only {} coins", self.name, self.coins),
+            ));
+        }
+        self.coins -= coins;
+        Ok(())
+    }
+    /// Pay `price` for the `item` and carry it. Returns the coins left.
+    pub fn buy(&mut self, item: &Item, price: u32) -> io::Result<u32> {
+        self.pay(price)?;
+        self.add_gear(Gear::new(item));
+        Ok(self.coins)
+    }
+    /// Take the coins and the items of the `treasure`, which are looked up in `items`.
+    /// Returns the items taken.
+    pub fn take_treasure<'a>(&mut self, treasure: &Treasure, items: &'a Items) -> Vec<&'a Item> {
+        let taken: Vec<&Item> = treasure
+            .items()
+            .iter()
+            .filter_map(|key| items.get(key))
+            .collect();
+        for item in taken.iter() {
+            self.add_gear(Gear::new(item));
+        }
+        self.add_coins(treasure.coins().min(i32::MAX as u32) as i32);
+        taken
+    }
+    /// Get the armor of the equipped items.
+    /// Armor doesn't stack, the best one counts, but shields add theirs.
+    pub fn armor(&self) -> u8 {
+        let equipped = self.gear.iter().filter(|gear| gear.equipped);
+        let (adds, base): (Vec<&Gear>, Vec<&Gear>) = equipped.partition(|gear| gear.adds_armor);
+        let base = base.iter().map(|gear| gear.armor).max().unwrap_or(0);
+        adds.iter()
+            .fold(base, |armor, gear| armor.saturating_add(gear.armor))
+    }
+    /// Get the weight of all carried items.
+    pub fn load(&self) -> u8 {
+        self.gear
+            .iter()
+            .fold(0, |load, gear| load.saturating_add(gear.weight))
+    }
+    /// Get the load the character can carry, the one of the `class` plus STR.
+    pub fn max_load(&self, class: &Class) -> u8 {
+        let max_load = i32::from(class.load()) + self.modifier(Stat::Str);
+        max_load.max(0) as u8
+    }
+    /// Take `damage`, reduced by the armor unless it is ignored.
+    /// Returns the damage dealt.
+    pub fn take_damage(&mut self, damage: u8, ignore_armor: bool) -> u8 {
+        let damage = if ignore_armor {
+            damage
+        } else {
+            damage.saturating_sub(self.armor())
+        };
+        let dealt = damage.min(self.hp);
+        self.hp -= dealt;
+        dealt
+    }
+    /// Heal `hp`, at most up to the maximum HP. Returns the HP healed.
+    pub fn heal(&mut self, hp: u8) -> u8 {
+        let healed = hp.min(self.max_hp - self.hp);
+        self.hp += healed;
+        healed
+    }
+    /// Whether the character has less than half of the maximum HP left.
+    pub fn is_wounded(&self) -> bool {
+        u16::from(self.hp) * 2 < u16::from(self.max_hp)
+    }
+    /// Whether the character has no HP left and takes the Last Breath.
+    pub fn is_dying(&self) -> bool {
+        self.hp == 0
+    }
+    /// Get the current HP.
+    pub fn hp(&self) -> u8 {
+        self.hp
+    }
+    /// Get the maximum HP.
+    pub fn max_hp(&self) -> u8 {
+        self.max_hp
+    }
+    /// Get the damage die.
+    pub fn damage(&self) -> &str {
+        &self.damage
+    }
+    /// Get the variables for rolls, the modifier of each stat, i.e. `STR` → `2`,
+    /// and the `LEVEL`.
+    pub fn variables(&self) -> Variables {
+        let mut vars: Variables = Stat::ALL
+            .iter()
+            .map(|stat| (stat.abbreviation().to_string(), self.modifier(*stat)))
+            .collect();
+        vars.insert(String::from("LEVEL"), i32::from(self.level));
+        vars
+    }
+    /// Create the card showing the character sheet.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let title = format!(
+            "{} {{}}{} {} {}  {}/{} XP",
+            theme.name.paint(&self.name),
+            theme
+                .class
+                .paint(&format!(" {} {} ", self.class, self.level)),
+            theme.hp.paint(&format!(" {}/{} HP ", self.hp, self.max_hp)),
+            theme.armor.paint(&format!(" {} Armor ", self.armor())),
+            self.xp,
+            self.xp_needed()
+        );
+        let stats: Vec<String> = Stat::ALL
+            .iter()
+            .map(|stat| {
+                let debility = self
+                    .debilities
+                    .iter()
+                    .find(|debility| debility.stat() == *stat)
+                    .map(|debility| debility.name())
+                    .unwrap_or_default();
+                format!(
+                    "{} {:>2} ({:+}) {}",
+                    stat.abbreviation(),
+                    self.score(*stat),
+                    self.modifier(*stat),
+                    debility
+                )
+            })
+            .collect();
+        let gear: Vec<String> = self
+            .gear
+            .iter()
+            .map(|gear| {
+                if gear.equipped {
+                    format!("{} (equipped)", gear.name)
+                } else {
+                    gear.name.clone()
+                }
+            })
+            .collect();
+        let has_items = !gear.is_empty();
+        let has_gear = has_items || self.coins > 0;
+        let bonds: Vec<String> = self.bonds.iter().map(|bond| bond.text.clone()).collect();
+        let has_bonds = !bonds.is_empty();
+        let spells: Vec<String> = self
+            .prepared
+            .iter()
+            .filter(|spell| !self.ongoing.contains(spell))
+            .cloned()
+            .chain(
+                self.ongoing
+                    .iter()
+                    .map(|spell| format!("{} (ongoing)", spell)),
+            )
+            .collect();
+        let has_spells = !spells.is_empty();
+        let penalty = match self.spell_penalty() {
+            0 => String::new(),
+            penalty => format!("-{} to cast", penalty),
+        };
+        let row = |stats: &[String]| format!("{:<19} {:<19} {}", stats[0], stats[1], stats[2]);
+        Card::new()
+            .with_width(60)
+            .line(&title)
+            .heavy_line()
+            .line(&row(&stats[..3]))
+            .line(&row(&stats[3..]))
+            .light_line()
+            .line(&format!(
+                "Damage {}{{}}{}",
+                self.damage,
+                self.alignment().unwrap_or_default()
+            ))
+            .light_line_if(!self.moves.is_empty())
+            .list_if(self.moves.clone(), !self.moves.is_empty())
+            .light_line_if(has_spells)
+            .line_if(&format!("Spells {{}}{}", penalty), has_spells)
+            .list_if(spells, has_spells)
+            .light_line_if(has_gear)
+            .line_if(&format!("Gear {{}}{} coins", self.coins), has_gear)
+            .list_if(gear, has_items)
+            .light_line_if(has_bonds)
+            .line_if("Bonds", has_bonds)
+            .list_if(bonds, has_bonds)
+    }
+}
+
+impl fmt::Display for Character {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for Characters {
+    type Target = Vec<Character>;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for Characters {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+/// Simple helper function for Serde to return `1`.
+fn one() -> u8 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+    use data::{Classes, Hoard, Items, Monsters};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::fs::File;
+
+    fn classes() -> Classes {
+        Classes::parse(File::open("data/classes.json").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn character() {
+        let classes = classes();
+        let fighter = classes.find("fighter").unwrap();
+        assert!(Character::new("Ayla", fighter, [16, 15, 9, 8, 12, 2]).is_err());
+        assert_eq!(
+            Stat::parse_scores("16, 15 9 8,12  13"),
+            Some([16, 15, 9, 8, 12, 13])
+        );
+        assert!(Stat::parse_scores("16 15 9 8 12").is_none());
+        assert!(Stat::parse_scores("16 15 9 8 12 x").is_none());
+        let ayla = Character::new("Ayla", fighter, [16, 15, 9, 8, 12, 13]).unwrap();
+        assert!(ayla.find_alignment(fighter).is_none());
+        let ayla = ayla.with_alignment("Good");
+        let good = ayla.find_alignment(fighter).unwrap();
+        assert_eq!(good.name(), "Good");
+        assert_eq!(ayla.max_hp(), fighter.base_hp() + 9);
+        assert_eq!(ayla.modifier(Stat::Str), 2);
+        assert_eq!(ayla.modifier(Stat::Int), -1);
+        let vars = ayla.variables();
+        assert_eq!(vars["STR"], 2);
+        assert_eq!(vars["CHA"], 1);
+        assert_eq!(vars["LEVEL"], 1);
+        let card = strip_colors(&ayla.to_string());
+        assert!(card.contains("STR 16 (+2)         DEX 15 (+1)         CON  9 (+0)"));
+        // Survives saving and loading
+        let mut characters = Characters::new();
+        characters.insert(ayla);
+        let mut json = Vec::new();
+        characters.save(&mut json).unwrap();
+        assert_eq!(Characters::parse(&json[..]).unwrap(), characters);
+        assert_eq!(characters.find("ay").unwrap().class(), "Fighter");
+    }
+
+    #[test]
+    fn damage() {
+        let classes = classes();
+        let items = Items::parse(File::open("data/items.json").unwrap()).unwrap();
+        let fighter = classes.find("fighter").unwrap();
+        let mut ayla = Character::new("Ayla", fighter, [16, 15, 9, 8, 12, 13]).unwrap();
+        ayla.add_gear(Gear::new(items.get("leather_armor").unwrap()));
+        ayla.add_gear(Gear::new(items.get("scale_mail").unwrap()));
+        ayla.add_gear(Gear::new(items.get("shield").unwrap()));
+        assert_eq!(ayla.armor(), 0);
+        // The best armor counts, shields add theirs
+        ayla.equip("leather").unwrap();
+        ayla.equip("scale").unwrap();
+        ayla.equip("shield").unwrap();
+        assert_eq!(ayla.armor(), 3);
+        ayla.unequip("scale").unwrap();
+        assert_eq!(ayla.armor(), 2);
+        let max_hp = ayla.max_hp();
+        assert_eq!(ayla.take_damage(5, false), 3);
+        assert_eq!(ayla.take_damage(5, true), 5);
+        assert_eq!(ayla.heal(100), 8);
+        assert_eq!(ayla.hp(), max_hp);
+        assert_eq!(ayla.take_damage(100, true), max_hp);
+        assert!(ayla.is_dying());
+        // Debilities lower the modifiers used by rolls
+        assert!(ayla.add_debility(Debility::from_name("weak").unwrap()));
+        assert!(!ayla.add_debility(Debility::Weak));
+        assert_eq!(ayla.variables()["STR"], 1);
+        let card = strip_colors(&ayla.to_string());
+        assert!(card.contains("STR 16 (+1) Weak"));
+        assert!(card.contains("Leather Armor (equipped)"));
+        assert!(ayla.remove_debility(Debility::Weak));
+        assert_eq!(ayla.modifier(Stat::Str), 2);
+    }
+
+    #[test]
+    fn party() {
+        let classes = classes();
+        let items = Items::parse(File::open("data/items.json").unwrap()).unwrap();
+        let fighter = classes.find("fighter").unwrap();
+        let mut ayla = Character::new("Ayla", fighter, [16, 15, 9, 8, 12, 13]).unwrap();
+        ayla.add_gear(Gear::new(items.get("scale_mail").unwrap()).with_equipped(true));
+        ayla.add_gear(Gear::new(items.get("shield").unwrap()).with_equipped(true));
+        assert_eq!(ayla.load(), 5);
+        assert_eq!(ayla.max_load(fighter), fighter.load() + 2);
+        ayla.take_damage(12, true);
+        assert!(ayla.is_wounded());
+        ayla.add_debility(Debility::Sick);
+        let mut characters = Characters::new();
+        characters.insert(ayla);
+        let card = strip_colors(&characters.party(&classes).to_string());
+        assert!(card.contains("7/19 HP   3 Armor  0/8 XP  5/14 Load"));
+        assert!(card.contains("  Sick "));
+        // Neither the armor nor the load overflow
+        let mut hoarder = Character::new("Hoarder", fighter, [16, 15, 9, 8, 12, 13]).unwrap();
+        for _ in 0..300 {
+            hoarder.add_gear(Gear::new(items.get("shield").unwrap()).with_equipped(true));
+        }
+        assert_eq!(hoarder.armor(), u8::MAX);
+        assert_eq!(hoarder.load(), u8::MAX);
+    }
+
+    #[test]
+    fn bonds() {
+        let classes = classes();
+        let bard = classes.find("bard").unwrap();
+        let mut ayla = Character::new("Ayla", bard, [16, 15, 9, 8, 12, 13]).unwrap();
+        let mut bob = Character::new("Bob", bard, [16, 15, 9, 8, 12, 13]).unwrap();
+        ayla.add_bond(Bond::new(&bard.bonds()[4], "Bob"));
+        ayla.add_bond(Bond::new("I owe __________ my life.", "Bob"));
+        bob.add_bond(Bond::new(
+            "__________ is often the butt of my jokes.",
+            "Ayla",
+        ));
+        assert_eq!(ayla.bonds()[0].text(), "Bob trusted me with a secret.");
+        assert_eq!(ayla.bond("Bob"), 2);
+        assert_eq!(ayla.bond("Carl"), 0);
+        let mut characters = Characters::new();
+        characters.insert(ayla);
+        characters.insert(bob);
+        assert_eq!(characters.others("Ayla"), &["Bob"]);
+        let card = strip_colors(&characters.web("Ayla").unwrap().to_string());
+        assert!(card.contains("Ayla aids +2  Bob aids +1"));
+        assert!(card.contains("Bob: Ayla is often the butt of my jokes."));
+        assert!(characters.web("Carl").is_none());
+        let ayla = characters.get_mut("Ayla").unwrap();
+        assert_eq!(ayla.resolve_bond(1).unwrap().text(), "I owe Bob my life.");
+        assert!(ayla.resolve_bond(1).is_none());
+        assert_eq!(ayla.bond("Bob"), 1);
+        // Each bond is resolved once, whatever the order of the indices
+        ayla.add_bond(Bond::new("I owe __________ my life.", "Bob"));
+        ayla.add_bond(Bond::new("__________ sings badly.", "Bob"));
+        let resolved = ayla.resolve_bonds(&[2, 0, 2, 5]);
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[1].text(), "Bob sings badly.");
+        assert_eq!(ayla.bonds()[0].text(), "I owe Bob my life.");
+    }
+
+    #[test]
+    fn spells() {
+        let classes = classes();
+        let wizard = classes.find("wizard").unwrap();
+        let spell = |name: &str| {
+            wizard
+                .spells()
+                .into_iter()
+                .find(|spell| spell.name() == name)
+                .unwrap()
+        };
+        assert_eq!(wizard.casting_stat(), Some("INT"));
+        assert_eq!(classes.find("cleric").unwrap().casting_stat(), Some("WIS"));
+        // A spell that can't be read and spells without a casting stat are invalid
+        let mage = r#"[{"name": "Mage", "casting_stat": "INT", "spells": {"key": "spells", "missile": {"name": "Missile"}}}]"#;
+        let e = Classes::parse(mage.as_bytes()).unwrap_err();
+        assert_eq!(e.kind(), InvalidData);
+        let mage = r#"[{"name": "Mage", "spells": {"missile": {"key": "missile", "name": "Missile", "level": 1}}}]"#;
+        let e = Classes::parse(mage.as_bytes()).unwrap_err();
+        assert_eq!(e.kind(), InvalidData);
+        assert_eq!(spell("Light").level(), 0);
+        let mut merlin = Character::new("Merlin", wizard, [8, 12, 13, 16, 15, 9]).unwrap();
+        // Cantrips are in the spellbook from the start
+        assert!(merlin.spellbook().contains(&String::from("Light")));
+        assert!(merlin.learn_spell(spell("Dispel Magic")).is_err());
+        assert!(merlin
+            .learnable_spells(wizard)
+            .iter()
+            .all(|spell| spell.level() == 1));
+        // All spells are written or none
+        let cantrips = merlin.spellbook().len();
+        let missile = spell("Magic Missile");
+        assert!(merlin.learn_spells(&[missile, missile]).is_err());
+        assert_eq!(merlin.spellbook().len(), cantrips);
+        merlin
+            .learn_spells(&[missile, spell("Charm Person"), spell("Alarm")])
+            .unwrap();
+        assert!(merlin
+            .learnable_spells(wizard)
+            .iter()
+            .all(|spell| spell.name() != "Magic Missile"));
+        // At most level + 1 levels of spells from the spellbook
+        let charm = spell("Charm Person");
+        assert!(merlin
+            .prepare(wizard, &[missile, charm, spell("Alarm")])
+            .is_err());
+        assert!(merlin.prepare(wizard, &[spell("Telepathy")]).is_err());
+        merlin.prepare(wizard, &[missile, charm]).unwrap();
+        assert!(merlin.is_prepared("Light"));
+        assert_eq!(merlin.cast_modifier(wizard), Some(2));
+        // Ongoing spells and a 7-9 give -1 to cast
+        assert_eq!(
+            merlin.find_prepared(wizard, "charm").unwrap().name(),
+            "Charm Person"
+        );
+        merlin.cast(charm, None);
+        merlin.cast(missile, Some(CastCost::Penalty));
+        assert_eq!(merlin.cast_modifier(wizard), Some(0));
+        merlin.cast(missile, Some(CastCost::Forget));
+        assert!(merlin.find_prepared(wizard, "missile").is_none());
+        assert_eq!(merlin.end_ongoing("charm").unwrap(), "Charm Person");
+        assert_eq!(merlin.spell_penalty(), 1);
+        // Preparing again resets everything
+        merlin.prepare(wizard, &[missile]).unwrap();
+        assert_eq!(merlin.spell_penalty(), 0);
+        let fighter = classes.find("fighter").unwrap();
+        let ayla = Character::new("Ayla", fighter, STANDARD_ARRAY).unwrap();
+        assert!(ayla.cast_modifier(fighter).is_none());
+    }
+
+    #[test]
+    fn level_up() {
+        let classes = classes();
+        let moves = Moves::parse(File::open("data/moves.json").unwrap()).unwrap();
+        let fighter = classes.find("fighter").unwrap();
+        let mut ayla = Character::new("Ayla", fighter, [16, 15, 9, 8, 12, 13]).unwrap();
+        assert!(ayla.knows("Signature Weapon"));
+        let merciless = moves.get("merciless").unwrap();
+        assert!(ayla.level_up(Stat::Con, merciless).is_err());
+        assert_eq!(ayla.mark_xp(9), 9);
+        assert!(ayla.can_level_up());
+        // Only moves of levels 2-5 are offered
+        let choices = ayla.advanced_moves(fighter, &moves);
+        assert!(choices.iter().any(|mv| mv.name() == "Merciless"));
+        assert!(!choices.iter().any(|mv| mv.name() == "Bloodthirsty"));
+        assert_eq!(ayla.raisable_stats().len(), 6);
+        ayla.level_up(Stat::Con, merciless).unwrap();
+        assert_eq!(ayla.level(), 2);
+        assert_eq!(ayla.xp(), 1);
+        assert_eq!(ayla.score(Stat::Con), 10);
+        assert_eq!(ayla.max_hp(), fighter.base_hp() + 10);
+        // Replaced moves are lost, required ones are needed
+        ayla.level = 5;
+        ayla.mark_xp(20);
+        let choices = ayla.advanced_moves(fighter, &moves);
+        assert!(!choices.iter().any(|mv| mv.name() == "Merciless"));
+        assert!(!choices.iter().any(|mv| mv.name() == "Evil Eye"));
+        let bloodthirsty = moves.get("bloodthirsty").unwrap();
+        assert!(choices.iter().any(|mv| mv.name() == "Bloodthirsty"));
+        ayla.level_up(Stat::Str, bloodthirsty).unwrap();
+        assert!(ayla.knows("Bloodthirsty"));
+        assert!(!ayla.knows("Merciless"));
+        assert!(ayla
+            .level_up(Stat::Str, moves.get("evil_eye").unwrap())
+            .is_err());
+        assert!(ayla
+            .advanced_moves(fighter, &moves)
+            .iter()
+            .all(|mv| mv.name() != "Merciless"));
+        ayla.scores.insert(Stat::Str, 18);
+        assert!(!ayla.raisable_stats().contains(&Stat::Str));
+    }
+
+    #[test]
+    fn treasure() {
+        let classes = classes();
+        let items = Items::parse(File::open("data/items.json").unwrap()).unwrap();
+        let monsters = Monsters::parse(File::open("data/monsters.json").unwrap()).unwrap();
+        let goblin = monsters.find("goblin").unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let treasure = Treasure::roll(goblin, Hoard::of(goblin), &items, &mut rng);
+        let fighter = classes.find("fighter").unwrap();
+        let mut ayla = Character::new("Ayla", fighter, STANDARD_ARRAY).unwrap();
+        let taken = ayla.take_treasure(&treasure, &items);
+        assert_eq!(taken.len(), 1);
+        assert_eq!(ayla.gear()[0].name(), taken[0].name());
+        assert_eq!(ayla.coins(), treasure.coins());
+    }
+}
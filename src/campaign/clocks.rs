@@ -0,0 +1,198 @@
+//! Segmented countdown clocks
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Clocks                                 ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ ritual                 ■■■■□□□□    4/8 ┃
+//! ┃ guards                     ■■■■    4/4 ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use colored::*;
+use data::card::{Card, Theme};
+use regex::Regex;
+use serde_json;
+use std::fmt;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData, ErrorKind::InvalidInput};
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+/// Most segments allowed on a clock.
+const MAX_SEGMENTS: u8 = 12;
+
+/// Wrapper around a `Vec<Clock>`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Clocks {
+    data: Vec<Clock>,
+}
+
+/// A clock counting down to something, i.e. `the ritual completes`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Clock {
+    /// Name of the clock.
+    name: String,
+    /// Number of segments.
+    segments: u8,
+    /// Number of filled segments.
+    #[serde(default)]
+    filled: u8,
+}
+
+impl Clocks {
+    /// Create an empty set of clocks.
+    pub fn new() -> Self {
+        Clocks::default()
+    }
+    /// Parse the data given through the reader into `Clocks`.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|data| Clocks { data })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Write the clocks as JSON to the given writer,
+    /// which can be read again with [parse](Clocks::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.data).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Add the given `clock`, replacing the one with the same name.
+    pub fn insert(&mut self, clock: Clock) {
+        match self.data.iter().position(|c| c.name == clock.name) {
+            Some(index) => self.data[index] = clock,
+            None => self.data.push(clock),
+        }
+    }
+    /// Remove the first clock whose name matches the given `regex` and return it.
+    pub fn remove(&mut self, re: &str) -> Option<Clock> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let index = self
+            .data
+            .iter()
+            .position(|clock| re.is_match(&clock.name))?;
+        Some(self.data.remove(index))
+    }
+    /// Find the first clock whose name matches the given `regex`.
+    pub fn find(&self, re: &str) -> Option<&Clock> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter().find(|clock| re.is_match(&clock.name))
+    }
+    /// Fill `n` more segments of the first clock whose name matches the given `regex`.
+    ///
+    /// Returns the clock and whether it just filled up.
+    pub fn tick(&mut self, re: &str, n: u8) -> Option<(&Clock, bool)> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let clock = self
+            .data
+            .iter_mut()
+            .find(|clock| re.is_match(&clock.name))?;
+        let filled = clock.tick(n);
+        Some((clock, filled))
+    }
+    /// Create the card showing all clocks.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let width = self.data.iter().map(|c| c.segments).max().unwrap_or(0) as usize;
+        let lines = self.data.iter().map(|clock| {
+            let dial = format!("{:>width$}", clock.dial(), width = width);
+            let count = format!("{:>2}/{}", clock.filled, clock.segments);
+            if clock.is_full() {
+                format!("{} {{}}{}   {}", clock.name.bold(), dial, count.bold())
+            } else {
+                format!("{} {{}}{}   {}", clock.name, dial, count)
+            }
+        });
+        lines.fold(
+            Card::new().line(&theme.name.paint("Clocks")).heavy_line(),
+            |card, line| card.line(&line),
+        )
+    }
+}
+
+impl Clock {
+    /// Create a new empty clock with the given number of `segments`.
+    pub fn new(name: &str, segments: u8) -> io::Result<Self> {
+        if segments == 0 || segments > MAX_SEGMENTS {
+            return Err(Error::new(
+                InvalidInput,
+                format!("A clock needs 1 to {} segments", MAX_SEGMENTS),
+            ));
+        }
+        Ok(Clock {
+            name: name.to_string(),
+            segments,
+            filled: 0,
+        })
+    }
+    /// Get the name of the clock.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the number of segments.
+    pub fn segments(&self) -> u8 {
+        self.segments
+    }
+    /// Get the number of filled segments.
+    pub fn filled(&self) -> u8 {
+        self.filled
+    }
+    /// Whether all segments are filled.
+    pub fn is_full(&self) -> bool {
+        self.filled >= self.segments
+    }
+    /// Fill `n` more segments, at most all of them.
+    /// Returns whether the clock just filled up.
+    pub fn tick(&mut self, n: u8) -> bool {
+        let was_full = self.is_full();
+        self.filled = self.filled.saturating_add(n).min(self.segments);
+        !was_full && self.is_full()
+    }
+    /// Get the dial of segments, `■` for filled and `□` for empty ones.
+    pub fn dial(&self) -> String {
+        let empty = self.segments - self.filled;
+        "■".repeat(self.filled as usize) + &"□".repeat(empty as usize)
+    }
+}
+
+impl fmt::Display for Clocks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for Clocks {
+    type Target = Vec<Clock>;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+
+    #[test]
+    fn tick() {
+        let mut clocks = Clocks::new();
+        assert!(Clock::new("never", 0).is_err());
+        clocks.insert(Clock::new("ritual", 6).unwrap());
+        clocks.insert(Clock::new("guards", 4).unwrap());
+        assert!(!clocks.tick("rit", 2).unwrap().1);
+        assert_eq!(clocks.find("ritual").unwrap().dial(), "■■□□□□");
+        // Fires once when it fills up
+        assert!(clocks.tick("guards", 9).unwrap().1);
+        assert!(!clocks.tick("guards", 1).unwrap().1);
+        assert_eq!(clocks.find("guards").unwrap().filled(), 4);
+        assert!(clocks.tick("nothing", 1).is_none());
+        let card = strip_colors(&clocks.to_string());
+        assert!(card.contains("ritual                   ■■□□□□    2/6"));
+        assert!(card.contains("guards                     ■■■■    4/4"));
+        // Survives saving and loading
+        let mut json = Vec::new();
+        clocks.save(&mut json).unwrap();
+        assert_eq!(Clocks::parse(&json[..]).unwrap(), clocks);
+        assert_eq!(clocks.remove("rit").unwrap().name(), "ritual");
+        assert_eq!(clocks.len(), 1);
+    }
+}
@@ -0,0 +1,194 @@
+//! The monsters of the current fight
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Encounter                              ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ Goblin                  3/3 HP 1 Armor ┃
+//! ┃ Goblin 2                1/3 HP 1 Armor ┃
+//! ┃ G̶o̶b̶l̶i̶n̶ 3̶                0/3 HP 1 Armor ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use data::card::helper::*;
+use data::card::{Card, Theme};
+use data::Monster;
+use regex::Regex;
+use serde_json;
+use std::fmt;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData};
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+/// The monsters fighting the party.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Encounter {
+    foes: Vec<Foe>,
+}
+
+/// A monster in an encounter.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Foe {
+    /// Name of the foe, numbered if there are several of the same monster.
+    name: String,
+    /// Key of the monster.
+    monster: String,
+    /// Current HP.
+    hp: u8,
+    /// HP of the monster.
+    max_hp: u8,
+    /// Armor of the monster.
+    armor: u8,
+}
+
+impl Encounter {
+    /// Create an encounter without foes.
+    pub fn new() -> Self {
+        Encounter::default()
+    }
+    /// Parse the data given through the reader into an `Encounter`.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|foes| Encounter { foes })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Write the foes as JSON to the given writer,
+    /// which can be read again with [parse](Encounter::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.foes).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Add the given `monster` as a foe and return it.
+    /// Several foes of the same monster are numbered, i.e. `Goblin 2`.
+    pub fn add(&mut self, monster: &Monster) -> &Foe {
+        let count = self
+            .foes
+            .iter()
+            .filter(|foe| foe.monster == monster.key())
+            .count();
+        let name = if count == 0 {
+            monster.name().to_string()
+        } else {
+            format!("{} {}", monster.name(), count + 1)
+        };
+        self.foes.push(Foe {
+            name,
+            monster: monster.key().to_string(),
+            hp: monster.hp(),
+            max_hp: monster.hp(),
+            armor: monster.armor(),
+        });
+        &self.foes[self.foes.len() - 1]
+    }
+    /// Deal `damage` to the first foe still standing whose name matches the given `regex`.
+    /// Its armor is subtracted from the damage.
+    pub fn damage(&mut self, re: &str, damage: u8) -> Option<&Foe> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let foe = self
+            .foes
+            .iter_mut()
+            .find(|foe| foe.hp > 0 && re.is_match(&foe.name))?;
+        foe.hp = foe.hp.saturating_sub(damage.saturating_sub(foe.armor));
+        Some(foe)
+    }
+    /// Remove the first foe whose name matches the given `regex` and return it.
+    pub fn remove(&mut self, re: &str) -> Option<Foe> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let index = self.foes.iter().position(|foe| re.is_match(&foe.name))?;
+        Some(self.foes.remove(index))
+    }
+    /// Remove all foes.
+    pub fn clear(&mut self) {
+        self.foes.clear();
+    }
+    /// Create the card showing all foes, the defeated ones struck through.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        self.foes.iter().fold(
+            Card::new()
+                .line(&theme.name.paint("Encounter"))
+                .heavy_line(),
+            |card, foe| {
+                let name = if foe.is_defeated() {
+                    strikethrough(&foe.name)
+                } else {
+                    foe.name.clone()
+                };
+                card.line(&format!(
+                    "{} {{}}{}/{} HP {} Armor",
+                    name, foe.hp, foe.max_hp, foe.armor
+                ))
+            },
+        )
+    }
+}
+
+impl Foe {
+    /// Get the name of the foe.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the key of the monster.
+    pub fn monster(&self) -> &str {
+        &self.monster
+    }
+    /// Get the current HP.
+    pub fn hp(&self) -> u8 {
+        self.hp
+    }
+    /// Get the HP of the monster.
+    pub fn max_hp(&self) -> u8 {
+        self.max_hp
+    }
+    /// Get the armor of the monster.
+    pub fn armor(&self) -> u8 {
+        self.armor
+    }
+    /// Whether the foe has no HP left.
+    pub fn is_defeated(&self) -> bool {
+        self.hp == 0
+    }
+}
+
+impl fmt::Display for Encounter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for Encounter {
+    type Target = Vec<Foe>;
+    fn deref(&self) -> &Self::Target {
+        &self.foes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::Monsters;
+    use std::fs::File;
+
+    #[test]
+    fn encounter() {
+        let monsters = Monsters::parse(File::open("data/monsters.json").unwrap()).unwrap();
+        let goblin = monsters.find("^goblin$").unwrap();
+        let mut encounter = Encounter::new();
+        encounter.add(goblin);
+        assert_eq!(encounter.add(goblin).name(), "Goblin 2");
+        // Armor is subtracted, only foes still standing are hit
+        let hp = goblin.hp();
+        let hit = goblin.armor() + 1;
+        assert_eq!(encounter.damage("goblin", hit).unwrap().hp(), hp - 1);
+        assert_eq!(encounter.damage("goblin 2", 100).unwrap().hp(), 0);
+        assert!(encounter.damage("goblin 2", 1).is_none());
+        assert!(encounter.to_string().contains(&strikethrough("Goblin 2")));
+        // Survives saving and loading
+        let mut json = Vec::new();
+        encounter.save(&mut json).unwrap();
+        assert_eq!(Encounter::parse(&json[..]).unwrap(), encounter);
+        assert_eq!(encounter.remove("goblin 2").unwrap().name(), "Goblin 2");
+        encounter.clear();
+        assert!(encounter.is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+//! Fronts, their dangers and grim portents
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ The Cult of the Black Sun                                  ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ • [x] Will the cult find the last seal?                    ┃
+//! ┃ • [ ] Who betrays the baron?                               ┃
+//! ┠────────────────────────────────────────────────────────────┨
+//! ┃ The Eclipse Cult                    Ambitious Organization ┃
+//! ┃ Impulse: To summon the sun eater                           ┃
+//! ┃ • A̶ ̶p̶r̶i̶e̶s̶t̶ ̶v̶a̶n̶i̶s̶h̶e̶s̶                                       ┃
+//! ┃ • The seals are stolen                                     ┃
+//! ┃ Impending doom: The sun goes dark forever                  ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use colored::*;
+use data::card::helper::*;
+use data::card::{Card, Theme};
+use regex::Regex;
+use serde_json;
+use std::fmt;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData};
+use std::io::{Read, Write};
+use std::ops::{Deref, DerefMut};
+
+/// Wrapper around a `Vec<Front>`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Fronts {
+    data: Vec<Front>,
+}
+
+/// A front, dangers that share a theme and threaten the world.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Front {
+    /// Name of the front.
+    name: String,
+    /// The dangers of the front.
+    #[serde(default)]
+    dangers: Vec<Danger>,
+    /// Questions about the front the GM wants to see answered in play.
+    #[serde(default)]
+    stakes: Vec<Stake>,
+}
+
+/// A danger of a front, advancing towards its impending doom.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Danger {
+    /// Name of the danger.
+    name: String,
+    /// Kind of the danger, i.e. `Ambitious Organization`.
+    #[serde(default)]
+    kind: String,
+    /// What drives the danger.
+    #[serde(default)]
+    impulse: String,
+    /// The grim portents, in the order they are expected to happen.
+    #[serde(default)]
+    portents: Vec<Portent>,
+    /// What happens when all grim portents came to pass.
+    #[serde(default)]
+    doom: String,
+}
+
+/// A grim portent of a danger.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Portent {
+    /// What happens.
+    text: String,
+    /// Whether it already happened.
+    #[serde(default)]
+    happened: bool,
+}
+
+/// A stakes question of a front.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Stake {
+    /// The question.
+    question: String,
+    /// Whether it has been answered in play.
+    #[serde(default)]
+    checked: bool,
+}
+
+impl Fronts {
+    /// Create an empty set of fronts.
+    pub fn new() -> Self {
+        Fronts::default()
+    }
+    /// Parse the data given through the reader into `Fronts`.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|data| Fronts { data })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Write the fronts as JSON to the given writer,
+    /// which can be read again with [parse](Fronts::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.data).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Add the given `front`, replacing the one with the same name.
+    pub fn insert(&mut self, front: Front) {
+        match self.data.iter().position(|f| f.name == front.name) {
+            Some(index) => self.data[index] = front,
+            None => self.data.push(front),
+        }
+    }
+    /// Find the first front whose name matches the given `regex`.
+    pub fn find(&self, re: &str) -> Option<&Front> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter().find(|front| re.is_match(&front.name))
+    }
+    /// Find the first front whose name matches the given `regex` to change it.
+    pub fn find_mut(&mut self, re: &str) -> Option<&mut Front> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter_mut().find(|front| re.is_match(&front.name))
+    }
+    /// Get all fronts whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&Front> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data
+            .iter()
+            .filter(|front| re.is_match(&front.name))
+            .collect()
+    }
+    /// Let the next grim portent matching the given `regex` happen.
+    ///
+    /// The first portent that did not happen yet and whose text matches is used,
+    /// otherwise the next portent of the first danger whose name matches.
+    /// Returns the danger of the portent.
+    pub fn advance(&mut self, re: &str) -> Option<&Danger> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let dangers = || self.data.iter().flat_map(|front| front.dangers.iter());
+        let by_portent = dangers().enumerate().find_map(|(d, danger)| {
+            danger
+                .portents
+                .iter()
+                .position(|p| !p.happened && re.is_match(&p.text))
+                .map(|p| (d, p))
+        });
+        let by_danger = || {
+            dangers().enumerate().find_map(|(d, danger)| {
+                if re.is_match(&danger.name) {
+                    danger
+                        .portents
+                        .iter()
+                        .position(|p| !p.happened)
+                        .map(|p| (d, p))
+                } else {
+                    None
+                }
+            })
+        };
+        let (d, p) = by_portent.or_else(by_danger)?;
+        let danger = self
+            .data
+            .iter_mut()
+            .flat_map(|front| front.dangers.iter_mut())
+            .nth(d)?;
+        danger.portents[p].happened = true;
+        Some(danger)
+    }
+    /// Check off the first open stakes question matching the given `regex`
+    /// and return it.
+    pub fn check(&mut self, re: &str) -> Option<&Stake> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let stake = self
+            .data
+            .iter_mut()
+            .flat_map(|front| front.stakes.iter_mut())
+            .find(|stake| !stake.checked && re.is_match(&stake.question))?;
+        stake.checked = true;
+        Some(stake)
+    }
+    /// List all fronts whose name match the given `regex`.
+    pub fn list(&self, re: &str) {
+        println!(">> {}", "Fronts".bold());
+        self.filter(re)
+            .iter()
+            .for_each(|front| println!("   {}", front.name));
+    }
+}
+
+impl Front {
+    /// Create a new front without dangers and stakes.
+    pub fn new(name: &str) -> Self {
+        Front {
+            name: name.to_string(),
+            dangers: Vec::new(),
+            stakes: Vec::new(),
+        }
+    }
+    /// Get the name of the front.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the dangers of the front.
+    pub fn dangers(&self) -> &[Danger] {
+        &self.dangers
+    }
+    /// Get the stakes questions of the front.
+    pub fn stakes(&self) -> &[Stake] {
+        &self.stakes
+    }
+    /// Add the given `danger`.
+    pub fn add_danger(&mut self, danger: Danger) {
+        self.dangers.push(danger);
+    }
+    /// Add the stakes question `question`.
+    pub fn add_stake(&mut self, question: &str) {
+        self.stakes.push(Stake {
+            question: question.to_string(),
+            checked: false,
+        });
+    }
+    /// Create the card showing this front.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let stakes = self
+            .stakes
+            .iter()
+            .map(|stake| {
+                let check = if stake.checked { "[x]" } else { "[ ]" };
+                format!("{} {}", check, stake.question)
+            })
+            .collect();
+        let mut card = Card::new()
+            .with_width(60)
+            .line(&theme.name.paint(&self.name))
+            .heavy_line()
+            .list(stakes);
+        for danger in &self.dangers {
+            let portents = danger
+                .portents
+                .iter()
+                .map(|portent| {
+                    if portent.happened {
+                        strikethrough(&portent.text)
+                    } else {
+                        portent.text.clone()
+                    }
+                })
+                .collect();
+            card = card
+                .light_line()
+                .line(&format!(
+                    "{} {{}} {}",
+                    danger.name.bold(),
+                    danger.kind.italic()
+                ))
+                .text_if(
+                    &format!("Impulse: {}", danger.impulse),
+                    !danger.impulse.is_empty(),
+                )
+                .list(portents)
+                .text_if(
+                    &format!("Impending doom: {}", danger.doom),
+                    !danger.doom.is_empty(),
+                );
+        }
+        card
+    }
+}
+
+impl Danger {
+    /// Create a new danger of the given `kind` driven by `impulse`.
+    pub fn new(name: &str, kind: &str, impulse: &str) -> Self {
+        Danger {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            impulse: impulse.to_string(),
+            portents: Vec::new(),
+            doom: String::new(),
+        }
+    }
+    /// Add the grim portent `text`, after all others.
+    pub fn portent(mut self, text: &str) -> Self {
+        self.portents.push(Portent {
+            text: text.to_string(),
+            happened: false,
+        });
+        self
+    }
+    /// Set the impending doom.
+    pub fn doom(mut self, doom: &str) -> Self {
+        self.doom = doom.to_string();
+        self
+    }
+    /// Get the name of the danger.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the kind of the danger.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+    /// Get the impulse of the danger.
+    pub fn impulse(&self) -> &str {
+        &self.impulse
+    }
+    /// Get the grim portents of the danger.
+    pub fn portents(&self) -> &[Portent] {
+        &self.portents
+    }
+    /// Get the impending doom of the danger.
+    pub fn impending_doom(&self) -> &str {
+        &self.doom
+    }
+    /// Get the last grim portent that happened.
+    pub fn last_happened(&self) -> Option<&Portent> {
+        self.portents.iter().rev().find(|portent| portent.happened)
+    }
+    /// Whether all grim portents happened and the impending doom comes to pass.
+    pub fn is_doomed(&self) -> bool {
+        !self.portents.is_empty() && self.portents.iter().all(|portent| portent.happened)
+    }
+}
+
+impl Portent {
+    /// Get what happens.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Whether it already happened.
+    pub fn happened(&self) -> bool {
+        self.happened
+    }
+}
+
+impl Stake {
+    /// Get the question.
+    pub fn question(&self) -> &str {
+        &self.question
+    }
+    /// Whether it has been answered in play.
+    pub fn checked(&self) -> bool {
+        self.checked
+    }
+}
+
+impl fmt::Display for Front {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for Fronts {
+    type Target = Vec<Front>;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for Fronts {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance() {
+        let mut front = Front::new("The Cult of the Black Sun");
+        front.add_danger(
+            Danger::new("The Eclipse Cult", "Ambitious Organization", "To summon")
+                .portent("A priest vanishes")
+                .portent("The seals are stolen")
+                .doom("The sun goes dark"),
+        );
+        front.add_stake("Will the cult find the last seal?");
+        let mut fronts = Fronts::new();
+        fronts.insert(front);
+        // By portent, then by danger
+        let danger = fronts.advance("seals").unwrap();
+        assert_eq!(
+            danger.last_happened().unwrap().text(),
+            "The seals are stolen"
+        );
+        assert!(!danger.is_doomed());
+        assert!(fronts.advance("seals").is_none());
+        assert!(fronts.advance("eclipse").unwrap().is_doomed());
+        assert!(fronts.advance("eclipse").is_none());
+        assert!(fronts.check("seal").unwrap().checked());
+        assert!(fronts.check("seal").is_none());
+        // Happened portents are struck through
+        let card = fronts.find("black sun").unwrap().to_string();
+        assert!(card.contains(&strikethrough("A priest vanishes")));
+        assert!(strip_colors(&card).contains("[x] Will the cult"));
+        // Survives saving and loading
+        let mut json = Vec::new();
+        fronts.save(&mut json).unwrap();
+        assert_eq!(Fronts::parse(&json[..]).unwrap(), fronts);
+    }
+}
@@ -0,0 +1,214 @@
+//! Session notes
+//!
+//! Notes may link to other entities with `[[category:regex]]`,
+//! i.e. `met [[monster:goblin]] at the inn`.
+//!
+//! ```text
+//! >> Session 2
+//!    3  20:14:03  Met goblin at the inn
+//!    4  20:31:47  The baron owes Ayla 40 coins
+//! ```
+
+use chrono::{DateTime, Local};
+use colored::*;
+use regex::Regex;
+use serde_json;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData};
+use std::io::{Read, Write};
+use std::ops::Deref;
+use std::path::Path;
+
+/// All notes of a campaign, the latest last.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Journal {
+    notes: Vec<Note>,
+}
+
+/// A note taken during a session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Note {
+    /// Number of the session.
+    session: u32,
+    /// When the note was taken.
+    time: DateTime<Local>,
+    /// The note, possibly containing links.
+    text: String,
+}
+
+/// A link to another entity, `[[category:regex]]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// Category of the entity, i.e. `monster`.
+    pub category: String,
+    /// Regex matching the entity, i.e. `goblin`.
+    pub query: String,
+}
+
+impl Journal {
+    /// Create an empty journal.
+    pub fn new() -> Self {
+        Journal::default()
+    }
+    /// Parse a journal given as JSON array of notes through the reader.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|notes| Journal { notes })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Write the journal as JSON array of notes to the given writer,
+    /// which can be read again with [parse](Journal::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.notes).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Take the note `text` in `session`, just now, and return it.
+    pub fn add(&mut self, session: u32, text: &str) -> &Note {
+        self.notes.push(Note {
+            session,
+            time: Local::now(),
+            text: text.to_string(),
+        });
+        &self.notes[self.notes.len() - 1]
+    }
+    /// Get all notes matching the given `regex` with their number, starting at one.
+    pub fn search(&self, re: &str) -> Vec<(usize, &Note)> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.notes
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| re.is_match(&note.text))
+            .map(|(i, note)| (i + 1, note))
+            .collect()
+    }
+    /// Get all notes of `session`.
+    pub fn session(&self, session: u32) -> Vec<&Note> {
+        self.notes
+            .iter()
+            .filter(|note| note.session == session)
+            .collect()
+    }
+    /// Print the notes matching the given `regex`, grouped by session.
+    pub fn list(&self, re: &str) {
+        let mut session = None;
+        for (i, note) in self.search(re) {
+            if session != Some(note.session) {
+                session = Some(note.session);
+                println!(">> {}", format!("Session {}", note.session).bold());
+            }
+            println!("{:>4}  {}", i, note);
+        }
+    }
+    /// Write the notes of `session` as Markdown to the file at `path`.
+    pub fn export<P: AsRef<Path>>(&self, session: u32, path: P) -> io::Result<()> {
+        fs::write(path, self.to_markdown(session))
+    }
+    /// Get the notes of `session` as Markdown.
+    pub fn to_markdown(&self, session: u32) -> String {
+        let notes = self.session(session);
+        let mut md = format!("# Session {}", session);
+        if let Some(first) = notes.first() {
+            md += &format!(" ({})", first.time.format("%Y-%m-%d"));
+        }
+        md += "\n\n";
+        for note in notes {
+            md += &format!(
+                "- **{}** {}\n",
+                note.time.format("%H:%M"),
+                note.replace_links(|link| format!("*{}*", link.query))
+            );
+        }
+        md
+    }
+}
+
+impl Note {
+    /// Get the number of the session the note was taken in.
+    pub fn session(&self) -> u32 {
+        self.session
+    }
+    /// Get when the note was taken.
+    pub fn time(&self) -> &DateTime<Local> {
+        &self.time
+    }
+    /// Get the note with its links.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Get all links of the note.
+    pub fn links(&self) -> Vec<Link> {
+        link_regex()
+            .captures_iter(&self.text)
+            .map(|caps| Link {
+                category: caps[1].to_lowercase(),
+                query: caps[2].trim().to_string(),
+            })
+            .collect()
+    }
+    /// Get the note with every link replaced by `f`.
+    pub fn replace_links<F: Fn(&Link) -> String>(&self, f: F) -> String {
+        link_regex()
+            .replace_all(&self.text, |caps: &::regex::Captures| {
+                f(&Link {
+                    category: caps[1].to_lowercase(),
+                    query: caps[2].trim().to_string(),
+                })
+            })
+            .to_string()
+    }
+}
+
+/// The regex of links, capturing the category and the query.
+fn link_regex() -> Regex {
+    Regex::new(r"\[\[(\w+):([^\]]+)\]\]").unwrap()
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let text = self.replace_links(|link| link.query.underline().to_string());
+        write!(f, "{}  {}", self.time.format("%H:%M:%S"), text)
+    }
+}
+
+impl Deref for Journal {
+    type Target = Vec<Note>;
+    fn deref(&self) -> &Self::Target {
+        &self.notes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal() {
+        let mut journal = Journal::new();
+        journal.add(1, "Arrived in Stonebridge");
+        journal.add(2, "Met [[monster:goblin]] at the [[item:bag]] shop");
+        assert_eq!(
+            journal[1].links(),
+            vec![
+                Link {
+                    category: String::from("monster"),
+                    query: String::from("goblin"),
+                },
+                Link {
+                    category: String::from("item"),
+                    query: String::from("bag"),
+                },
+            ]
+        );
+        let found = journal.search("goblin");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 2);
+        let md = journal.to_markdown(2);
+        assert!(md.starts_with("# Session 2 ("));
+        assert!(md.ends_with(" Met *goblin* at the *bag* shop\n"));
+        // Survives saving and loading
+        let mut json = Vec::new();
+        journal.save(&mut json).unwrap();
+        assert_eq!(Journal::parse(&json[..]).unwrap(), journal);
+    }
+}
@@ -0,0 +1,436 @@
+//! Campaign state
+//!
+//! Everything the GM tracks between the sessions of a campaign,
+//! like the [characters](characters), the [encounter](encounter) at hand,
+//! the [fronts](fronts) threatening the world, the [clocks](clocks)
+//! counting down to what happens next, the [journal](journal) of the sessions,
+//! the [steadings](steadings) of the world and the [shop](shop) of the one the party is in.
+//!
+//! A campaign is a directory holding one file for each of them:
+//!
+//! ```text
+//! campaign.toml     session, active character, shop and data overlay
+//! characters.json
+//! encounter.json
+//! fronts.json
+//! clocks.json
+//! journal.json
+//! rolls.json
+//! steadings.json
+//! tables/           random tables of the campaign, see [Tables](::data::Tables)
+//! ```
+//!
+//! The data overlay of `campaign.toml` names files with additional monsters,
+//! moves, tags, items or classes, relative to the campaign directory:
+//!
+//! ```toml
+//! session = 3
+//! active = "Ayla"
+//!
+//! [overlay]
+//! monsters = "homebrew/monsters.json"
+//! ```
+
+pub mod characters;
+pub mod clocks;
+pub mod encounter;
+pub mod fronts;
+pub mod journal;
+pub mod shop;
+pub mod steadings;
+
+pub use self::characters::{
+    Bond, CastCost, Character, Characters, Debility, Gear, Stat, MAX_LEVEL,
+};
+pub use self::clocks::{Clock, Clocks};
+pub use self::encounter::{Encounter, Foe};
+pub use self::fronts::{Danger, Front, Fronts, Portent, Stake};
+pub use self::journal::{Journal, Link, Note};
+pub use self::shop::{Settlement, Shop};
+pub use self::steadings::{Defenses, Population, Prosperity, Steading, Steadings};
+
+use data::card::Card;
+use data::{Classes, Data, Items, Monsters, Moves, Tags};
+use die::{RollLog, Variables};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Error, ErrorKind::InvalidData, Write};
+use std::path::{Path, PathBuf};
+use toml;
+
+/// The state of a campaign, stored in its directory.
+#[derive(Debug, Clone)]
+pub struct Campaign {
+    /// Directory of the campaign.
+    dir: PathBuf,
+    /// Contents of `campaign.toml`.
+    config: Config,
+    /// The player characters.
+    pub characters: Characters,
+    /// The monsters of the current fight.
+    pub encounter: Encounter,
+    /// The fronts of the campaign.
+    pub fronts: Fronts,
+    /// The countdown clocks.
+    pub clocks: Clocks,
+    /// The notes of all sessions.
+    pub journal: Journal,
+    /// All rolls of the campaign.
+    pub rolls: RollLog,
+    /// The villages, towns, keeps and cities.
+    pub steadings: Steadings,
+}
+
+/// Contents of `campaign.toml`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Config {
+    /// Number of the current session.
+    #[serde(default = "one")]
+    session: u32,
+    /// Name of the character whose stats are used as variables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active: Option<String>,
+    /// Additional data of the campaign.
+    #[serde(default, skip_serializing_if = "Overlay::is_empty")]
+    overlay: Overlay,
+    /// The settlement and the prices of the shop.
+    #[serde(default, skip_serializing_if = "Shop::is_empty")]
+    shop: Shop,
+}
+
+/// Files with additional data, relative to the campaign directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Overlay {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    monsters: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    moves: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    items: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    classes: Option<PathBuf>,
+}
+
+impl Campaign {
+    /// Open the campaign in the directory `dir`.
+    /// Starts a new campaign if there is none yet.
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let config = match read(&dir.join("campaign.toml"))? {
+            Some(content) => toml::from_str(&content).map_err(|e| Error::new(InvalidData, e))?,
+            None => Config::default(),
+        };
+        let characters = match open(&dir.join("characters.json"))? {
+            Some(file) => Characters::parse(file)?,
+            None => Characters::new(),
+        };
+        let encounter = match open(&dir.join("encounter.json"))? {
+            Some(file) => Encounter::parse(file)?,
+            None => Encounter::new(),
+        };
+        let fronts = match open(&dir.join("fronts.json"))? {
+            Some(file) => Fronts::parse(file)?,
+            None => Fronts::new(),
+        };
+        let clocks = match open(&dir.join("clocks.json"))? {
+            Some(file) => Clocks::parse(file)?,
+            None => Clocks::new(),
+        };
+        let journal = match open(&dir.join("journal.json"))? {
+            Some(file) => Journal::parse(file)?,
+            None => Journal::new(),
+        };
+        let rolls = match open(&dir.join("rolls.json"))? {
+            Some(file) => RollLog::parse(file)?,
+            None => RollLog::new(),
+        };
+        let steadings = match open(&dir.join("steadings.json"))? {
+            Some(file) => Steadings::parse(file)?,
+            None => Steadings::new(),
+        };
+        Ok(Campaign {
+            dir,
+            config,
+            characters,
+            encounter,
+            fronts,
+            clocks,
+            journal,
+            rolls,
+            steadings,
+        })
+    }
+    /// Save the whole campaign to its directory, creating it if necessary.
+    ///
+    /// Every file is written next to the old one first and then replaces it,
+    /// so an interrupted save never leaves a broken campaign behind.
+    pub fn save(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let config = toml::to_string(&self.config).map_err(|e| Error::new(InvalidData, e))?;
+        write(&self.dir.join("campaign.toml"), |w| {
+            w.write_all(config.as_bytes())
+        })?;
+        write(&self.dir.join("characters.json"), |w| {
+            self.characters.save(w)
+        })?;
+        write(&self.dir.join("encounter.json"), |w| self.encounter.save(w))?;
+        write(&self.dir.join("fronts.json"), |w| self.fronts.save(w))?;
+        write(&self.dir.join("clocks.json"), |w| self.clocks.save(w))?;
+        write(&self.dir.join("journal.json"), |w| self.journal.save(w))?;
+        write(&self.dir.join("rolls.json"), |w| self.rolls.save(w))?;
+        write(&self.dir.join("steadings.json"), |w| self.steadings.save(w))
+    }
+    /// Get the directory of the campaign.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    /// Get the number of the current session.
+    pub fn session(&self) -> u32 {
+        self.config.session
+    }
+    /// End the current session and return the number of the next one.
+    pub fn next_session(&mut self) -> u32 {
+        self.config.session += 1;
+        self.config.session
+    }
+    /// Get the files with additional data.
+    pub fn overlay(&self) -> &Overlay {
+        &self.config.overlay
+    }
+    /// Get the shop of the steading the party is in,
+    /// selling by the current Prosperity of the steading.
+    pub fn shop(&self) -> Shop {
+        let prosperity = self
+            .config
+            .shop
+            .steading()
+            .and_then(|name| self.steadings.get(name))
+            .map(|steading| steading.prosperity());
+        self.config.shop.clone().with_prosperity(prosperity)
+    }
+    /// Shop in the settlement or the steading matching `at`, without one everything is for sale.
+    /// Returns false if neither matches.
+    pub fn shop_at(&mut self, at: Option<&str>) -> bool {
+        let (settlement, steading) = match at {
+            Some(at) => match Settlement::from_name(at) {
+                Some(settlement) => (Some(settlement), None),
+                None => match self.steadings.find(at) {
+                    Some(steading) => (Some(steading.kind()), Some(steading.name().to_string())),
+                    None => return false,
+                },
+            },
+            None => (None, None),
+        };
+        self.config.shop.set_settlement(settlement);
+        self.config.shop.set_steading(steading.as_deref());
+        true
+    }
+    /// Get the shop to change its settlement or prices.
+    pub fn shop_mut(&mut self) -> &mut Shop {
+        &mut self.config.shop
+    }
+    /// Get the character whose stats are used as variables.
+    pub fn active(&self) -> Option<&Character> {
+        let name = self.config.active.as_ref()?;
+        self.characters.get(name)
+    }
+    /// Get the character whose stats are used as variables to change it.
+    pub fn active_mut(&mut self) -> Option<&mut Character> {
+        let name = self.config.active.clone()?;
+        self.characters.get_mut(&name)
+    }
+    /// Find the first character whose name matches the given `regex` to change it,
+    /// without one the active character.
+    pub fn character_mut(&mut self, re: Option<&str>) -> Option<&mut Character> {
+        match re {
+            Some(re) => self.characters.find_mut(re),
+            None => self.active_mut(),
+        }
+    }
+    /// Make the first character whose name matches the given `regex` the active one.
+    pub fn play(&mut self, re: &str) -> Option<&Character> {
+        let name = self.characters.find(re)?.name().to_string();
+        self.set_active(&name)
+    }
+    /// Make the character with exactly the given `name` the active one.
+    pub fn set_active(&mut self, name: &str) -> Option<&Character> {
+        self.characters.get(name)?;
+        self.config.active = Some(name.to_string());
+        self.active()
+    }
+    /// Get the card of the entity the `link` points to.
+    /// Besides the categories of `data`, links may point to characters, fronts and steadings.
+    pub fn card(&self, link: &Link, data: &Data) -> Option<Card> {
+        let query = link.query.as_str();
+        match link.category.as_str() {
+            "monster" => data.monsters.find(query).map(|monster| monster.card()),
+            "move" => data.moves.find(query).map(|mv| mv.card()),
+            "item" => data.items.find(query).map(|item| item.card()),
+            "tag" => data.tags.find(query).map(|tag| tag.card()),
+            "class" => data.classes.find(query).map(|class| class.card()),
+            "character" => self.characters.find(query).map(|c| c.card()),
+            "front" => self.fronts.find(query).map(|front| front.card()),
+            "steading" => self.steadings.find(query).map(|steading| steading.card()),
+            _ => None,
+        }
+    }
+    /// Get the variables of the active character, none if no character is active.
+    pub fn variables(&self) -> Variables {
+        self.active()
+            .map(|character| character.variables())
+            .unwrap_or_default()
+    }
+}
+
+impl Overlay {
+    /// Whether there is no additional data.
+    pub fn is_empty(&self) -> bool {
+        *self == Overlay::default()
+    }
+    /// Add the additional data to `data`, the files are relative to `dir`.
+    /// Entries with the key of an existing one replace it.
+    pub fn apply(&self, dir: &Path, data: &mut Data) -> io::Result<()> {
+        if let Some(ref path) = self.monsters {
+            let mut monsters = Monsters::parse(File::open(dir.join(path))?)?;
+            merge(&mut data.monsters, monsters.drain(..), |m| {
+                m.key().to_string()
+            });
+        }
+        if let Some(ref path) = self.moves {
+            let mut moves = Moves::parse(File::open(dir.join(path))?)?;
+            merge(&mut data.moves, moves.drain(..), |m| m.key().to_string());
+        }
+        if let Some(ref path) = self.tags {
+            let mut tags = Tags::parse(File::open(dir.join(path))?)?;
+            merge(&mut data.tags, tags.drain(..), |t| t.key().to_string());
+        }
+        if let Some(ref path) = self.items {
+            let mut items = Items::parse(File::open(dir.join(path))?)?;
+            merge(&mut data.items, items.drain(..), |i| i.key().to_string());
+        }
+        if let Some(ref path) = self.classes {
+            let mut classes = Classes::parse(File::open(dir.join(path))?)?;
+            merge(&mut data.classes, classes.drain(..), |c| {
+                c.key().to_string()
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session: 1,
+            active: None,
+            overlay: Overlay::default(),
+            shop: Shop::default(),
+        }
+    }
+}
+
+/// Add the `entries` to `data`, replacing the ones with the same key.
+fn merge<T, I, K>(data: &mut Vec<T>, entries: I, key: K)
+where
+    I: Iterator<Item = T>,
+    K: Fn(&T) -> String,
+{
+    for entry in entries {
+        match data.iter().position(|e| key(e) == key(&entry)) {
+            Some(index) => data[index] = entry,
+            None => data.push(entry),
+        }
+    }
+}
+
+/// Open the file at `path`, [None](Option::None) if it does not exist.
+fn open(path: &Path) -> io::Result<Option<File>> {
+    if path.exists() {
+        File::open(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Read the file at `path`, [None](Option::None) if it does not exist.
+fn read(path: &Path) -> io::Result<Option<String>> {
+    if path.exists() {
+        fs::read_to_string(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Write the file at `path` with `f` by replacing it with a new one.
+fn write<F>(path: &Path, f: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let tmp = path.with_extension("tmp");
+    {
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        f(&mut writer)?;
+        writer.flush()?;
+    }
+    fs::rename(tmp, path)
+}
+
+/// Simple helper function for Serde to return `1`.
+fn one() -> u32 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::DataPaths;
+    use std::env;
+
+    #[test]
+    fn campaign() {
+        let dir = env::temp_dir().join(format!("console-hero-campaign-{}", std::process::id()));
+        let mut campaign = Campaign::open(&dir).unwrap();
+        assert_eq!(campaign.session(), 1);
+        assert!(campaign.variables().is_empty());
+        let classes = Classes::parse(File::open("data/classes.json").unwrap()).unwrap();
+        let fighter = classes.find("fighter").unwrap();
+        let ayla = Character::new("Ayla", fighter, [16, 15, 13, 12, 9, 8]).unwrap();
+        campaign.characters.insert(ayla);
+        assert_eq!(campaign.play("ay").unwrap().name(), "Ayla");
+        assert_eq!(campaign.variables()["STR"], 2);
+        campaign.clocks.insert(Clock::new("ritual", 6).unwrap());
+        campaign
+            .steadings
+            .insert(Steading::new("Oakvale", Settlement::Village));
+        assert!(!campaign.shop_at(Some("riverton")));
+        assert!(campaign.shop_at(Some("oak")));
+        assert_eq!(campaign.shop().steading(), Some("Oakvale"));
+        assert!(campaign.shop_at(Some("keep")));
+        assert_eq!(campaign.shop().settlement(), Some(Settlement::Keep));
+        assert_eq!(campaign.shop().steading(), None);
+        assert_eq!(campaign.next_session(), 2);
+        campaign.save().unwrap();
+        // Resumes where it stopped
+        let campaign = Campaign::open(&dir).unwrap();
+        assert_eq!(campaign.active().unwrap().name(), "Ayla");
+        assert_eq!(campaign.session(), 2);
+        assert_eq!(campaign.clocks.len(), 1);
+        assert_eq!(campaign.shop().settlement(), Some(Settlement::Keep));
+        assert!(campaign.overlay().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn overlay() {
+        let mut data = Data::from(&DataPaths::default()).unwrap();
+        let monsters = data.monsters.len();
+        let overlay: Overlay = toml::from_str("monsters = \"data/monsters.json\"").unwrap();
+        overlay.apply(Path::new("."), &mut data).unwrap();
+        assert_eq!(data.monsters.len(), monsters);
+        assert!(toml::from_str::<Overlay>("spells = \"spells.json\"").is_err());
+    }
+}
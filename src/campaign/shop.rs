@@ -0,0 +1,288 @@
+//! Shops of the steadings
+//!
+//! What a shop sells depends on the steading the party is in,
+//! by its kind or, for the [steadings](super::steadings) of the campaign, its Prosperity.
+//! The prices can be changed for the campaign:
+//!
+//! ```toml
+//! [shop]
+//! settlement = "village"
+//! steading = "Oakvale"
+//!
+//! [shop.prices]
+//! healing_potion = 80
+//! ```
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Shop                                               Village ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ Chainmail                                         10 coins ┃
+//! ┃ Healing Potion                           Supply   80 coins ┃
+//! ┃ Leather Armor                                     10 coins ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::steadings::Prosperity;
+use super::{Character, Gear};
+use data::card::{Card, Theme};
+use data::{Item, Items};
+use std::collections::BTreeMap;
+
+/// The kinds of steadings, each one sells different items.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Settlement {
+    Village,
+    Town,
+    Keep,
+    City,
+}
+
+/// The settlement the party shops in and the prices of the campaign.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Shop {
+    /// The settlement, without one everything is for sale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    settlement: Option<Settlement>,
+    /// Name of the steading of the campaign the party shops in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    steading: Option<String>,
+    /// Prosperity of the steading, deciding what is readily available.
+    #[serde(skip)]
+    prosperity: Option<Prosperity>,
+    /// Prices replacing the ones of the items, by the key of the item.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    prices: BTreeMap<String, u32>,
+}
+
+impl Settlement {
+    /// All settlements.
+    pub const ALL: [Settlement; 4] = [
+        Settlement::Village,
+        Settlement::Town,
+        Settlement::Keep,
+        Settlement::City,
+    ];
+    /// Get the name of the settlement, i.e. `Village`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Settlement::Village => "Village",
+            Settlement::Town => "Town",
+            Settlement::Keep => "Keep",
+            Settlement::City => "City",
+        }
+    }
+    /// Get the settlement with the given `name`, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Settlement> {
+        Settlement::ALL
+            .iter()
+            .cloned()
+            .find(|settlement| settlement.name().eq_ignore_ascii_case(name))
+    }
+    /// Whether the `item` costing `price` is readily available here.
+    /// Villages sell only the basics, towns most mundane items, keeps the basics
+    /// and weapons and armor of any price and cities everything.
+    pub fn sells(self, item: &Item, price: u32) -> bool {
+        match self {
+            Settlement::Village => price <= 20,
+            Settlement::Town => price <= 60,
+            Settlement::Keep => price <= 20 || item.is_weapon() || item.armor() > 0,
+            Settlement::City => true,
+        }
+    }
+}
+
+impl Shop {
+    /// Whether the shop sells everything at the prices of the items.
+    pub fn is_empty(&self) -> bool {
+        *self == Shop::default()
+    }
+    /// Get the settlement the party shops in.
+    pub fn settlement(&self) -> Option<Settlement> {
+        self.settlement
+    }
+    /// Shop in the `settlement`, without one everything is for sale.
+    pub fn set_settlement(&mut self, settlement: Option<Settlement>) {
+        self.settlement = settlement;
+    }
+    /// Get the name of the steading the party shops in.
+    pub fn steading(&self) -> Option<&str> {
+        self.steading.as_deref()
+    }
+    /// Shop in the steading with the given `name`, set its kind as settlement too.
+    pub fn set_steading(&mut self, name: Option<&str>) {
+        self.steading = name.map(|name| name.to_string());
+    }
+    /// Set the Prosperity of the steading, it decides what is readily available
+    /// instead of the settlement.
+    pub fn with_prosperity(mut self, prosperity: Option<Prosperity>) -> Self {
+        self.prosperity = prosperity;
+        self
+    }
+    /// Get the price of the `item` in coins, [None](Option::None) if it is not for sale.
+    pub fn price(&self, item: &Item) -> Option<u32> {
+        self.prices
+            .get(item.key())
+            .cloned()
+            .or_else(|| item.coins())
+    }
+    /// Set the price of the item with the given `key`,
+    /// without one the price of the item counts again.
+    pub fn set_price(&mut self, key: &str, price: Option<u32>) {
+        match price {
+            Some(price) => self.prices.insert(key.to_string(), price),
+            None => self.prices.remove(key),
+        };
+    }
+    /// Get the coins a character gets for selling the `item`, half its price.
+    pub fn sell_price(&self, item: &Item) -> u32 {
+        self.price(item).unwrap_or(0) / 2
+    }
+    /// Sell the first item of the `character` whose name matches the given `regex`
+    /// for the [sell price](Shop::sell_price) of the item in `items`.
+    /// Returns the gear sold and the coins got for it.
+    pub fn sell(&self, character: &mut Character, items: &Items, re: &str) -> Option<(Gear, u32)> {
+        let gear = character.remove_gear(re)?;
+        let price = items
+            .get(gear.key())
+            .map(|item| self.sell_price(item))
+            .unwrap_or(0);
+        character.add_coins(price.min(i32::MAX as u32) as i32);
+        Some((gear, price))
+    }
+    /// Whether the `item` is readily available, other items need to Supply.
+    /// Keeps sell weapons and armor of any price, whatever their Prosperity.
+    pub fn sells(&self, item: &Item) -> bool {
+        let keep = self.settlement == Some(Settlement::Keep);
+        match (self.prosperity, self.settlement, self.price(item)) {
+            (_, _, None) => false,
+            (Some(_), _, Some(_)) if keep && (item.is_weapon() || item.armor() > 0) => true,
+            (Some(prosperity), _, Some(price)) => prosperity.sells(price),
+            (None, Some(settlement), Some(price)) => settlement.sells(item, price),
+            (None, None, Some(_)) => true,
+        }
+    }
+    /// Get the price after rolling `total` to Supply the `item`,
+    /// [None](Option::None) if it can't be found.
+    ///
+    /// Haggling for an available item lowers its price by a quarter on a 10+
+    /// and raises it by half on a miss. Something special is found at a fair price
+    /// on a 10+, costs half more on a 7-9 and isn't found on a miss.
+    pub fn supply(&self, item: &Item, total: i32) -> Option<u32> {
+        let price = self.price(item)?;
+        match (self.sells(item), total) {
+            (true, total) if total >= 10 => Some(price - price / 4),
+            (true, total) if total >= 7 => Some(price),
+            (true, _) => Some(price + price / 2),
+            (false, total) if total >= 10 => Some(price),
+            (false, total) if total >= 7 => Some(price + price / 2),
+            (false, _) => None,
+        }
+    }
+    /// Create the card listing the `items` for sale with their prices,
+    /// the ones which aren't readily available are marked to Supply.
+    pub fn card(&self, items: &[&Item]) -> Card {
+        let theme = Theme::current();
+        let settlement = self
+            .settlement
+            .map(|settlement| settlement.name())
+            .unwrap_or("Market");
+        let settlement = match (&self.steading, self.prosperity) {
+            (Some(steading), Some(prosperity)) => {
+                format!("{}, {} {}", steading, prosperity.name(), settlement)
+            }
+            (Some(steading), None) => format!("{}, {}", steading, settlement),
+            (None, _) => settlement.to_string(),
+        };
+        let title = format!(
+            "{}{{}}{}",
+            theme.name.paint("Shop"),
+            theme.class.paint(&format!(" {} ", settlement))
+        );
+        items.iter().fold(
+            Card::new().with_width(60).line(&title).heavy_line(),
+            |card, item| match self.price(item) {
+                Some(price) => {
+                    let supply = if self.sells(item) {
+                        String::new()
+                    } else {
+                        theme.requires.paint(" Supply ")
+                    };
+                    card.line(&format!(
+                        "{}{{}}{}  {:>4} coins",
+                        item.name(),
+                        supply,
+                        price
+                    ))
+                }
+                None => card,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+    use data::Classes;
+    use std::fs::File;
+    use toml;
+
+    #[test]
+    fn shop() {
+        let items = Items::parse(File::open("data/items.json").unwrap()).unwrap();
+        let potion = items.get("healing_potion").unwrap();
+        let plate = items.get("plate").unwrap();
+        let rations = items.get("dungeon_rations").unwrap();
+        let mut shop = Shop::default();
+        assert!(shop.sells(potion));
+        assert!(!shop.sells(items.get("vorpal_sword").unwrap()));
+        shop.set_settlement(Settlement::from_name("village"));
+        assert!(shop.sells(rations));
+        assert!(!shop.sells(potion));
+        shop.set_settlement(Some(Settlement::Keep));
+        assert!(shop.sells(plate));
+        assert!(!shop.sells(potion));
+        // Haggling and supplying something special
+        assert_eq!(shop.supply(plate, 10), Some(263));
+        assert_eq!(shop.supply(plate, 6), Some(525));
+        assert_eq!(shop.supply(potion, 12), Some(50));
+        assert_eq!(shop.supply(potion, 8), Some(75));
+        assert_eq!(shop.supply(potion, 6), None);
+        shop.set_price("healing_potion", Some(80));
+        assert_eq!(shop.price(potion), Some(80));
+        assert_eq!(shop.sell_price(potion), 40);
+        let classes = Classes::parse(File::open("data/classes.json").unwrap()).unwrap();
+        let fighter = classes.find("fighter").unwrap();
+        let mut ayla = Character::new("Ayla", fighter, [16, 15, 13, 12, 9, 8]).unwrap();
+        ayla.add_coins(100);
+        assert!(ayla.buy(plate, 350).is_err());
+        assert_eq!(ayla.buy(potion, 80).unwrap(), 20);
+        assert!(shop.sell(&mut ayla, &items, "plate").is_none());
+        let (gear, coins) = shop.sell(&mut ayla, &items, "potion").unwrap();
+        assert_eq!((gear.name(), coins), ("Healing Potion", 40));
+        assert_eq!(ayla.coins(), 60);
+        let card = strip_colors(&shop.card(&[potion, rations]).to_string());
+        assert!(card.contains("Healing Potion"));
+        assert!(card.contains("Supply     80 coins"));
+        // The Prosperity of a steading decides instead of the settlement
+        shop.set_steading(Some("Blackwall"));
+        assert!(shop
+            .clone()
+            .with_prosperity(Some(Prosperity::Wealthy))
+            .sells(potion));
+        let dirt = shop.clone().with_prosperity(Some(Prosperity::Dirt));
+        assert!(!dirt.sells(rations));
+        assert!(dirt.sells(plate));
+        assert!(strip_colors(&dirt.card(&[]).to_string()).contains("Blackwall, Dirt Keep"));
+        shop.set_steading(None);
+        // Survives saving and loading
+        let toml = toml::to_string(&shop).unwrap();
+        assert_eq!(toml::from_str::<Shop>(&toml).unwrap(), shop);
+        shop.set_price("healing_potion", None);
+        shop.set_settlement(None);
+        assert!(shop.is_empty());
+    }
+}
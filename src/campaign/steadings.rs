@@ -0,0 +1,488 @@
+//! Steadings of the campaign world
+//!
+//! Villages, towns, keeps and cities with their Prosperity, Population,
+//! Defenses and tags, changing as the campaign goes on:
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Stonebridge                                          Town  ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ Prosperity    Moderate                                     ┃
+//! ┃ Population    Growing                                      ┃
+//! ┃ Defenses      Watch                                        ┃
+//! ┠────────────────────────────────────────────────────────────┨
+//! ┃ Trade (Riverton), Religion                                 ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::shop::Settlement;
+use data::card::{Card, Theme};
+use die::Expr;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::Regex;
+use serde_json;
+use std::fmt;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData, ErrorKind::InvalidInput};
+use std::io::{Read, Write};
+use std::ops::Deref;
+
+/// The tags of steadings with the question for their parameter, if they take one.
+pub const TAGS: [(&str, Option<&str>); 20] = [
+    ("Safe", None),
+    ("Religion", None),
+    ("Exotic", None),
+    ("Resource", Some("Which resource?")),
+    ("Need", Some("What is needed?")),
+    ("Oath", Some("Sworn to which steading?")),
+    ("Trade", Some("Trading with which steading?")),
+    ("Market", None),
+    ("Enmity", Some("Enemy of which steading?")),
+    ("History", Some("What happened here?")),
+    ("Arcane", None),
+    ("Divine", None),
+    ("Guild", Some("Which guild?")),
+    ("Personage", Some("Who lives here?")),
+    ("Dwarven", None),
+    ("Elven", None),
+    ("Craft", Some("Which craft?")),
+    ("Lawless", None),
+    ("Blight", Some("What blights it?")),
+    ("Power", Some("What kind of power?")),
+];
+
+/// Tags without parameter a rolled steading may get.
+const FLAVORS: [&str; 8] = [
+    "Safe", "Religion", "Exotic", "Arcane", "Divine", "Dwarven", "Elven", "Lawless",
+];
+
+/// Resources of rolled villages.
+const RESOURCES: [&str; 8] = [
+    "timber", "ore", "fish", "grain", "stone", "salt", "wool", "herbs",
+];
+
+/// Guilds of rolled cities.
+const GUILDS: [&str; 6] = [
+    "merchants",
+    "thieves",
+    "masons",
+    "smiths",
+    "wizards",
+    "mercenaries",
+];
+
+/// Define a stat of steadings with its values from the lowest to the highest.
+macro_rules! stat {
+    ($(#[$meta:meta])* $stat:ident { $($value:ident => $name:expr),* $(,)* }) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        #[serde(rename_all = "lowercase")]
+        pub enum $stat {
+            $($value),*
+        }
+
+        impl $stat {
+            /// All values, from the lowest to the highest.
+            pub const ALL: &'static [$stat] = &[$($stat::$value),*];
+            /// Get the name of the value.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $($stat::$value => $name),*
+                }
+            }
+            /// Get the value with the given `name`, ignoring the case.
+            pub fn from_name(name: &str) -> Option<$stat> {
+                $stat::ALL
+                    .iter()
+                    .cloned()
+                    .find(|value| value.name().eq_ignore_ascii_case(name))
+            }
+            /// Get the value `n` steps higher, lower for a negative `n`,
+            /// staying between the lowest and the highest.
+            pub fn shift(self, n: i32) -> $stat {
+                let last = $stat::ALL.len() as i32 - 1;
+                let index = $stat::ALL.iter().position(|value| *value == self).unwrap() as i32;
+                $stat::ALL[(index + n).max(0).min(last) as usize]
+            }
+        }
+    };
+}
+
+stat!(
+    /// How wealthy a steading is, deciding what is for sale.
+    Prosperity {
+        Dirt => "Dirt",
+        Poor => "Poor",
+        Moderate => "Moderate",
+        Wealthy => "Wealthy",
+        Rich => "Rich",
+    }
+);
+
+stat!(
+    /// How many people live in a steading compared to its size.
+    Population {
+        Exodus => "Exodus",
+        Shrinking => "Shrinking",
+        Steady => "Steady",
+        Growing => "Growing",
+        Booming => "Booming",
+    }
+);
+
+stat!(
+    /// Who defends a steading.
+    Defenses {
+        None => "None",
+        Militia => "Militia",
+        Watch => "Watch",
+        Guard => "Guard",
+        Garrison => "Garrison",
+        Battalion => "Battalion",
+        Legion => "Legion",
+    }
+);
+
+/// Wrapper around a `Vec<Steading>`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Steadings {
+    data: Vec<Steading>,
+}
+
+/// A village, town, keep or city.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Steading {
+    /// Name of the steading.
+    name: String,
+    /// Whether it is a village, town, keep or city.
+    kind: Settlement,
+    /// How wealthy it is.
+    prosperity: Prosperity,
+    /// How many people live here.
+    population: Population,
+    /// Who defends it.
+    defenses: Defenses,
+    /// Tags like `Market` or `Trade (Riverton)`.
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl Prosperity {
+    /// Whether an item costing `price` is readily available.
+    /// Dirt has nothing for sale, poor steadings only the basics,
+    /// moderate ones most mundane items and wealthy and rich ones everything.
+    pub fn sells(self, price: u32) -> bool {
+        match self {
+            Prosperity::Dirt => false,
+            Prosperity::Poor => price <= 20,
+            Prosperity::Moderate => price <= 60,
+            Prosperity::Wealthy | Prosperity::Rich => true,
+        }
+    }
+}
+
+impl Steadings {
+    /// Create an empty set of steadings.
+    pub fn new() -> Self {
+        Steadings::default()
+    }
+    /// Parse the data given through the reader into `Steadings`.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|data| Steadings { data })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Write the steadings as JSON to the given writer,
+    /// which can be read again with [parse](Steadings::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.data).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Add the given `steading`, replacing the one with the same name.
+    pub fn insert(&mut self, steading: Steading) {
+        match self.data.iter().position(|s| s.name == steading.name) {
+            Some(index) => self.data[index] = steading,
+            None => self.data.push(steading),
+        }
+    }
+    /// Remove the first steading whose name matches the given `regex` and return it.
+    pub fn remove(&mut self, re: &str) -> Option<Steading> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let index = self
+            .data
+            .iter()
+            .position(|steading| re.is_match(&steading.name))?;
+        Some(self.data.remove(index))
+    }
+    /// Get the steading with exactly the given `name`.
+    pub fn get(&self, name: &str) -> Option<&Steading> {
+        self.data.iter().find(|steading| steading.name == name)
+    }
+    /// Find the first steading whose name matches the given `regex`.
+    pub fn find(&self, re: &str) -> Option<&Steading> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data
+            .iter()
+            .find(|steading| re.is_match(&steading.name))
+    }
+    /// Find the first steading whose name matches the given `regex` to change it.
+    pub fn find_mut(&mut self, re: &str) -> Option<&mut Steading> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data
+            .iter_mut()
+            .find(|steading| re.is_match(&steading.name))
+    }
+    /// Get the names of all steadings.
+    pub fn names(&self) -> Vec<String> {
+        self.data
+            .iter()
+            .map(|steading| steading.name.clone())
+            .collect()
+    }
+}
+
+impl Steading {
+    /// Create the steading `name` with the usual stats of its `kind` and no tags.
+    pub fn new(name: &str, kind: Settlement) -> Self {
+        let (prosperity, population, defenses) = match kind {
+            Settlement::Village => (Prosperity::Poor, Population::Steady, Defenses::Militia),
+            Settlement::Town => (Prosperity::Moderate, Population::Steady, Defenses::Watch),
+            Settlement::Keep => (Prosperity::Poor, Population::Shrinking, Defenses::Guard),
+            Settlement::City => (Prosperity::Moderate, Population::Steady, Defenses::Guard),
+        };
+        Steading {
+            name: name.to_string(),
+            kind,
+            prosperity,
+            population,
+            defenses,
+            tags: Vec::new(),
+        }
+    }
+    /// Roll the steading `name` of the given `kind`.
+    ///
+    /// It starts with the usual stats and tags of its kind, a 2d6 each lowers its Prosperity
+    /// and Population on a 6- and raises them on a 10+, and it gets one more tag.
+    /// Oaths and trade are with one of the `others` steadings, if there are any.
+    /// Everything is rolled with the random number generator `rng`.
+    pub fn roll<R: Rng + ?Sized>(
+        name: &str,
+        kind: Settlement,
+        others: &[String],
+        rng: &mut R,
+    ) -> Self {
+        let mut steading = Steading::new(name, kind);
+        let two_d6: Expr = "2d6".parse().expect("2d6 is a valid expression");
+        let mut shift = || match two_d6.roll(rng).map(|roll| roll.total()).unwrap_or(7) {
+            total if total <= 6 => -1,
+            total if total >= 10 => 1,
+            _ => 0,
+        };
+        steading.prosperity = steading.prosperity.shift(shift());
+        steading.population = steading.population.shift(shift());
+        for tag in Steading::usual_tags(kind) {
+            let parameter = match *tag {
+                "Resource" => pick(rng, &RESOURCES).map(|resource| resource.to_string()),
+                "Need" => Some(String::from("supplies")),
+                "Oath" | "Trade" => pick(rng, others).cloned(),
+                "Guild" => pick(rng, &GUILDS).map(|guild| guild.to_string()),
+                _ => None,
+            };
+            let takes_parameter = TAGS.iter().any(|(name, q)| name == tag && q.is_some());
+            if !takes_parameter || parameter.is_some() {
+                let _ = steading.add_tag(tag, parameter.as_deref());
+            }
+        }
+        if let Some(flavor) = pick(rng, &FLAVORS) {
+            let _ = steading.add_tag(flavor, None);
+        }
+        steading
+    }
+    /// Get the tags steadings of the given `kind` usually have.
+    pub fn usual_tags(kind: Settlement) -> &'static [&'static str] {
+        match kind {
+            Settlement::Village => &["Resource", "Oath"],
+            Settlement::Town => &["Trade"],
+            Settlement::Keep => &["Need", "Trade", "Oath"],
+            Settlement::City => &["Market", "Guild"],
+        }
+    }
+    /// Get the name of the steading.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get whether it is a village, town, keep or city.
+    pub fn kind(&self) -> Settlement {
+        self.kind
+    }
+    /// Get how wealthy the steading is.
+    pub fn prosperity(&self) -> Prosperity {
+        self.prosperity
+    }
+    /// Get how many people live here.
+    pub fn population(&self) -> Population {
+        self.population
+    }
+    /// Get who defends the steading.
+    pub fn defenses(&self) -> Defenses {
+        self.defenses
+    }
+    /// Get the tags of the steading.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    /// Set the stats of the steading.
+    pub fn with_stats(
+        mut self,
+        prosperity: Prosperity,
+        population: Population,
+        defenses: Defenses,
+    ) -> Self {
+        self.prosperity = prosperity;
+        self.population = population;
+        self.defenses = defenses;
+        self
+    }
+    /// Raise the Prosperity by `n` steps, lower it for a negative `n`.
+    pub fn shift_prosperity(&mut self, n: i32) -> Prosperity {
+        self.prosperity = self.prosperity.shift(n);
+        self.prosperity
+    }
+    /// Raise the Population by `n` steps, lower it for a negative `n`.
+    pub fn shift_population(&mut self, n: i32) -> Population {
+        self.population = self.population.shift(n);
+        self.population
+    }
+    /// Raise the Defenses by `n` steps, lower them for a negative `n`.
+    pub fn shift_defenses(&mut self, n: i32) -> Defenses {
+        self.defenses = self.defenses.shift(n);
+        self.defenses
+    }
+    /// Add the `tag` with its optional `parameter`, i.e. `Trade (Riverton)`.
+    /// Returns the tag, an error if there is no such tag or it has already been added.
+    pub fn add_tag(&mut self, tag: &str, parameter: Option<&str>) -> io::Result<String> {
+        let name = TAGS
+            .iter()
+            .map(|(name, _)| name)
+            .find(|name| name.eq_ignore_ascii_case(tag))
+            .ok_or_else(|| Error::new(InvalidInput, format!("Unknown steading tag {}", tag)))?;
+        let tag = match parameter {
+            Some(parameter) => format!("{} ({})", name, parameter),
+            None => name.to_string(),
+        };
+        if self.tags.contains(&tag) {
+            return Err(Error::new(
+                InvalidInput,
+                format!("{} is already tagged {}", self.name, tag),
+            ));
+        }
+        self.tags.push(tag.clone());
+        Ok(tag)
+    }
+    /// Remove the first tag matching the given `regex` and return it.
+    pub fn remove_tag(&mut self, re: &str) -> Option<String> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        let index = self.tags.iter().position(|tag| re.is_match(tag))?;
+        Some(self.tags.remove(index))
+    }
+    /// Create the card showing the steading.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let title = format!(
+            "{}{{}}{}",
+            theme.name.paint(&self.name),
+            theme.class.paint(&format!(" {} ", self.kind.name()))
+        );
+        Card::new()
+            .with_width(60)
+            .line(&title)
+            .heavy_line()
+            .line(&format!("Prosperity    {}", self.prosperity.name()))
+            .line(&format!("Population    {}", self.population.name()))
+            .line(&format!("Defenses      {}", self.defenses.name()))
+            .light_line_if(!self.tags.is_empty())
+            .text_if(&self.tags.join(", "), !self.tags.is_empty())
+    }
+}
+
+impl fmt::Display for Steadings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for steading in &self.data {
+            write!(f, "{}", steading)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Steading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for Steadings {
+    type Target = Vec<Steading>;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+/// Pick a random element of `slice`.
+fn pick<'a, T, R: Rng + ?Sized>(rng: &mut R, slice: &'a [T]) -> Option<&'a T> {
+    slice.choose(rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn steadings() {
+        assert_eq!(Prosperity::Poor.shift(-3), Prosperity::Dirt);
+        assert_eq!(
+            Defenses::from_name("watch").unwrap().shift(1),
+            Defenses::Guard
+        );
+        let mut steadings = Steadings::new();
+        let mut town = Steading::new("Stonebridge", Settlement::Town);
+        assert_eq!(town.prosperity(), Prosperity::Moderate);
+        assert_eq!(
+            town.add_tag("trade", Some("Riverton")).unwrap(),
+            "Trade (Riverton)"
+        );
+        assert!(town.add_tag("Trade", Some("Riverton")).is_err());
+        assert!(town.add_tag("Haunted", None).is_err());
+        assert_eq!(town.shift_prosperity(-1), Prosperity::Poor);
+        steadings.insert(town);
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let keep = Steading::roll("Blackwall", Settlement::Keep, &steadings.names(), &mut rng);
+        assert!(keep.tags().contains(&String::from("Need (supplies)")));
+        assert!(keep.tags().contains(&String::from("Oath (Stonebridge)")));
+        assert!(keep.prosperity() <= Prosperity::Moderate);
+        steadings.insert(keep);
+        let village = Steading::roll("Oakvale", Settlement::Village, &[], &mut rng);
+        assert!(village.tags().iter().all(|tag| !tag.starts_with("Oath")));
+        // The same seed gives the same steading
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let city = Steading::roll("Highgate", Settlement::City, &[], &mut rng);
+        assert_eq!(city.prosperity(), Prosperity::Moderate);
+        assert_eq!(city.population(), Population::Growing);
+        assert_eq!(city.tags(), &["Market", "Guild (masons)", "Dwarven"]);
+        let card = strip_colors(&steadings.find("stone").unwrap().to_string());
+        assert!(card.contains("Prosperity    Poor"));
+        assert!(card.contains("Trade (Riverton)"));
+        assert_eq!(
+            steadings.find_mut("stone").unwrap().remove_tag("trade"),
+            Some(String::from("Trade (Riverton)"))
+        );
+        // Survives saving and loading
+        let mut json = Vec::new();
+        steadings.save(&mut json).unwrap();
+        assert_eq!(Steadings::parse(json.as_slice()).unwrap(), steadings);
+        assert!(steadings.remove("black").is_some());
+        assert_eq!(steadings.len(), 1);
+    }
+}
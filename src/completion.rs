@@ -4,6 +4,8 @@ use rustyline::completion::Completer;
 
 pub struct HeroCompleter<'a> {
     data: &'a Data,
+    /// Names of the roll macros.
+    macros: Vec<String>,
 }
 
 macro_rules! comp {
@@ -20,14 +22,71 @@ macro_rules! comp {
 
 impl<'a> HeroCompleter<'a> {
     pub fn new(data: &'a Data) -> Self {
-        HeroCompleter { data }
+        HeroCompleter {
+            data,
+            macros: Vec::new(),
+        }
+    }
+    /// Complete the given macro names after `roll` and `macro remove`.
+    pub fn with_macros(mut self, macros: Vec<String>) -> Self {
+        self.macros = macros;
+        self
+    }
+    /// Get all macro names starting with `part`.
+    fn macro_names(&self, part: &str) -> Vec<String> {
+        self.macros
+            .iter()
+            .filter(|name| name.starts_with(part))
+            .cloned()
+            .collect()
     }
 }
 
 impl<'a> Completer for HeroCompleter<'a> {
     fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
         let top_level = [
-            "help", "info", "quit", "item", "monster", "move", "tag", "list",
+            "help",
+            "info",
+            "quit",
+            "item",
+            "monster",
+            "move",
+            "gm",
+            "tag",
+            "class",
+            "roll",
+            "reroll",
+            "rolls",
+            "note",
+            "notes",
+            "seed",
+            "macro",
+            "odds",
+            "front",
+            "advance",
+            "clock",
+            "campaign",
+            "character",
+            "party",
+            "bonds",
+            "damage",
+            "heal",
+            "debility",
+            "steading",
+            "shop",
+            "buy",
+            "sell",
+            "coins",
+            "xp",
+            "end",
+            "level",
+            "spell",
+            "cast",
+            "encounter",
+            "loot",
+            "table",
+            "npc",
+            "list",
         ];
         let matches: Vec<String> = top_level
             .iter()
@@ -42,11 +101,176 @@ impl<'a> Completer for HeroCompleter<'a> {
             self.data.monsters.complete(line, pos)
         } else if line.starts_with("move ") {
             self.data.moves.complete(line, pos)
+        } else if line.starts_with("gm ") {
+            let (_, mut names) = self.data.gm_moves.complete(line, pos)?;
+            if "suggest".starts_with(line.trim_start_matches("gm ")) {
+                names.insert(0, String::from("suggest"));
+            }
+            comp!(3; names)
         } else if line.starts_with("tag ") {
             self.data.tags.complete(line, pos)
+        } else if line.starts_with("class ") {
+            self.data.classes.complete(line, pos)
+        } else if line.starts_with("roll ") {
+            let part = line.trim_start_matches("roll ");
+            comp!(5; self.macro_names(part))
+        } else if line.starts_with("macro remove ") {
+            let part = line.trim_start_matches("macro remove ");
+            comp!(13; self.macro_names(part))
+        } else if line.starts_with("macro ") {
+            let sec_level = ["set", "list", "remove"];
+            let line = line.trim_start_matches("macro ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(6; matches)
+        } else if line.starts_with("front ") {
+            let sec_level = ["new", "danger", "stake", "check", "list"];
+            let line = line.trim_start_matches("front ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(6; matches)
+        } else if line.starts_with("clock ") {
+            let sec_level = ["new", "tick", "remove"];
+            let line = line.trim_start_matches("clock ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(6; matches)
+        } else if line.starts_with("campaign ") {
+            let line = line.trim_start_matches("campaign ");
+            let matches = if "open".starts_with(line) {
+                vec!["open".to_string()]
+            } else {
+                vec![]
+            };
+            comp!(9; matches)
+        } else if line.starts_with("debility add ") || line.starts_with("debility remove ") {
+            let start = line.find(' ').unwrap() + 1;
+            let start = start + line[start..].find(' ').unwrap() + 1;
+            let part = &line[start..];
+            let matches = ["weak", "shaky", "sick", "stunned", "confused", "scarred"]
+                .iter()
+                .filter(|com| com.starts_with(part))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(start; matches)
+        } else if line.starts_with("steading new ") {
+            let part = line.trim_start_matches("steading new ");
+            let matches = ["village", "town", "keep", "city"]
+                .iter()
+                .filter(|com| com.starts_with(part))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(13; matches)
+        } else if line.starts_with("steading ") {
+            let sec_level = [
+                "new",
+                "show",
+                "prosperity",
+                "population",
+                "defenses",
+                "tag",
+                "untag",
+                "remove",
+            ];
+            let line = line.trim_start_matches("steading ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(9; matches)
+        } else if line.starts_with("shop at ") {
+            let part = line.trim_start_matches("shop at ");
+            let matches = ["village", "town", "keep", "city"]
+                .iter()
+                .filter(|com| com.starts_with(part))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(8; matches)
+        } else if line.starts_with("debility ") {
+            let sec_level = ["add", "remove"];
+            let line = line.trim_start_matches("debility ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(9; matches)
+        } else if line.starts_with("spell ") {
+            let sec_level = ["show", "learn", "prepare", "end"];
+            let line = line.trim_start_matches("spell ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(6; matches)
+        } else if line.starts_with("end ") {
+            let line = line.trim_start_matches("end ");
+            let matches = if "session".starts_with(line) {
+                vec!["session".to_string()]
+            } else {
+                vec![]
+            };
+            comp!(4; matches)
+        } else if line.starts_with("level ") {
+            let line = line.trim_start_matches("level ");
+            let matches = if "up".starts_with(line) {
+                vec!["up".to_string()]
+            } else {
+                vec![]
+            };
+            comp!(6; matches)
+        } else if line.starts_with("character ") {
+            let sec_level = ["new", "play", "show", "remove", "list", "equip", "unequip"];
+            let line = line.trim_start_matches("character ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(10; matches)
+        } else if line.starts_with("encounter add ") {
+            let part = line.trim_start_matches("encounter add ");
+            let (_, names) = self
+                .data
+                .monsters
+                .complete(&format!("monster {}", part), 8 + part.len())?;
+            comp!(14; names)
+        } else if line.starts_with("encounter ") {
+            let sec_level = ["add", "hit", "remove", "clear"];
+            let line = line.trim_start_matches("encounter ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(10; matches)
+        } else if line.starts_with("table roll ") || line.starts_with("table show ") {
+            self.data.tables.complete(line, pos)
+        } else if line.starts_with("table ") {
+            let sec_level = ["roll", "show"];
+            let line = line.trim_start_matches("table ");
+            let matches = sec_level
+                .iter()
+                .filter(|com| com.starts_with(line))
+                .map(|com| com.to_string())
+                .collect();
+            comp!(6; matches)
         } else if line.starts_with("list ") {
-            let sec_level = ["monsters", "moves", "items", "tags"];
-            let line = line.trim_left_matches("list ");
+            let sec_level = [
+                "monsters", "moves", "gm", "items", "tags", "classes", "tables",
+            ];
+            let line = line.trim_start_matches("list ");
             let matches = sec_level
                 .iter()
                 .filter(|com| com.starts_with(line))
@@ -0,0 +1,39 @@
+//! User configuration
+//!
+//! Everything the user can configure lives in the directory returned by [dir](dir),
+//! i.e. `~/.config/console-hero` on Linux.
+
+use dirs;
+use std::path::PathBuf;
+
+/// Get the configuration directory.
+/// Returns [None](Option::None) if no home directory could be found.
+pub fn dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("console-hero"))
+}
+
+/// Get the path of the theme file, `theme.toml` in the configuration directory.
+pub fn theme_file() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("theme.toml"))
+}
+
+/// Get the path of the roll macros, `macros.toml` in the configuration directory.
+pub fn macros_file() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("macros.toml"))
+}
+
+/// Get the directory of the campaign used when none is given, `campaign` in the
+/// configuration directory.
+pub fn campaign_dir() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("campaign"))
+}
+
+/// Get the path of the homebrew monsters, `monsters.json` in the configuration directory.
+pub fn monsters_file() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("monsters.json"))
+}
+
+/// Get the directory of the random tables, `tables` in the configuration directory.
+pub fn tables_dir() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("tables"))
+}
@@ -1,5 +1,6 @@
 /// Border types to be used.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "lowercase")]
 pub enum Border {
     /// The light border.
     /// ```text
@@ -15,6 +16,20 @@ pub enum Border {
     /// ┗━━━┛
     /// ```
     Heavy,
+    /// The light border with rounded corners.
+    /// ```text
+    /// ╭───╮
+    /// │   │
+    /// ╰───╯
+    /// ```
+    Rounded,
+    /// A border for terminals without box drawing characters.
+    /// ```text
+    /// +---+
+    /// |   |
+    /// +---+
+    /// ```
+    Ascii,
 }
 
 impl Border {
@@ -22,7 +37,8 @@ impl Border {
     pub fn line(&self, width: usize) -> String {
         match *self {
             Border::Heavy => "━".repeat(width),
-            Border::Light => "─".repeat(width),
+            Border::Light | Border::Rounded => "─".repeat(width),
+            Border::Ascii => "-".repeat(width),
         }
     }
     /// Get the first line with the given `width`.
@@ -31,14 +47,42 @@ impl Border {
         match *self {
             Border::Heavy => format!(" ┏{}┓", line),
             Border::Light => format!(" ┌{}┐", line),
+            Border::Rounded => format!(" ╭{}╮", line),
+            Border::Ascii => format!(" +{}+", line),
         }
     }
     /// Get the end line with the given `width`.
     pub fn end(&self, width: usize) -> String {
         let line = self.line(width);
-        match self {
+        match *self {
             Border::Heavy => format!(" ┗{}┛\n", line),
             Border::Light => format!(" └{}┘\n", line),
+            Border::Rounded => format!(" ╰{}╯\n", line),
+            Border::Ascii => format!(" +{}+\n", line),
+        }
+    }
+    /// Get the left and right border of a content line.
+    pub fn side(&self) -> &'static str {
+        match *self {
+            Border::Heavy => " ┃ ",
+            Border::Light | Border::Rounded => " │ ",
+            Border::Ascii => " | ",
+        }
+    }
+    /// Get a light separator of the given `width`, joined with the sides.
+    pub fn light_separator(&self, width: usize) -> String {
+        match *self {
+            Border::Heavy => format!(" ┠{}┨", "─".repeat(width)),
+            Border::Light | Border::Rounded => format!(" ├{}┤", "─".repeat(width)),
+            Border::Ascii => format!(" +{}+", "-".repeat(width)),
+        }
+    }
+    /// Get a heavy separator of the given `width`, joined with the sides.
+    pub fn heavy_separator(&self, width: usize) -> String {
+        match *self {
+            Border::Heavy => format!(" ┣{}┫", "━".repeat(width)),
+            Border::Light | Border::Rounded => format!(" ┝{}┥", "━".repeat(width)),
+            Border::Ascii => format!(" +{}+", "=".repeat(width)),
         }
     }
 }
@@ -5,8 +5,21 @@ use unicode_width::UnicodeWidthStr as UW;
 
 /// Calculate the width of a string containing escape codes for coloring.
 pub fn terminal_string_width(s: &str) -> usize {
+    strip_colors(s).width()
+}
+
+/// Remove all escape codes for coloring from the given string `s`.
+pub fn strip_colors(s: &str) -> String {
     let re = Regex::new(r"\x1B\[.*?m").unwrap();
-    re.replace_all(s, "").width()
+    re.replace_all(s, "").to_string()
+}
+
+/// Escape the given string `s` for use in HTML.
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 /// Wraps the given String by word wrapping at the given
@@ -26,7 +39,7 @@ pub fn wrap(text: &str, width: usize, border: &str) -> String {
                 s += &desc;
                 s
             })
-            .trim_right_matches("\n")
+            .trim_end_matches("\n")
             .to_string()
     }
 }
@@ -57,7 +70,7 @@ pub fn listify<I: Iterator<Item = String>>(
             s += &item;
             s
         })
-        .trim_right_matches("\n")
+        .trim_end_matches("\n")
         .to_string();
     if ret == String::new() {
         wrap("", width, border)
@@ -75,6 +88,18 @@ pub fn capitalize(s: &str) -> String {
     }
 }
 
+/// Strikes through the given String `s` with combining characters.
+/// Unlike an escape code, this keeps the width of `s` and works without colors.
+pub fn strikethrough(s: &str) -> String {
+    s.chars().fold(String::new(), |mut ret, c| {
+        ret.push(c);
+        if !c.is_whitespace() {
+            ret.push('\u{336}');
+        }
+        ret
+    })
+}
+
 /// Expands the given string `text` at `{}` to match the given `width`.
 /// If the `text` is already wider than `width` do nothing.
 /// Multiple `{}` will be ignored and removed, only the first one is used for expansion.
@@ -101,7 +126,7 @@ pub fn expand(text: &str, width: usize) -> String {
 /// Concatenates the given `Vec<String>` to one String, seperated by `sep`.
 pub fn concat<I: Iterator<Item = String>>(items: I, sep: &str) -> String {
     items.fold(String::new(), |mut s, item| {
-        if s.len() > 0 {
+        if !s.is_empty() {
             s += sep;
         }
         s += &item;
@@ -117,6 +142,8 @@ mod tests {
 
     #[test]
     fn terminal_string_width_test() {
+        // Colors are disabled when not writing to a terminal
+        ::colored::control::set_override(true);
         let x = String::from("Hello World");
         let x_red = format!("{}", x.red());
         let x_red_black = format!("{}", x_red.on_black());
@@ -136,6 +163,12 @@ mod tests {
         assert_eq!(terminal_string_width(""), 0);
     }
 
+    #[test]
+    fn escape_html_test() {
+        assert_eq!(escape_html("<b>\"A\" & B</b>"), "&lt;b&gt;&quot;A&quot; &amp; B&lt;/b&gt;");
+        assert_eq!(strip_colors(&format!("{}", "Red".red())), "Red");
+    }
+
     #[test]
     fn wrap_test() {
         assert_eq!(
@@ -169,6 +202,13 @@ mod tests {
         assert_eq!(capitalize(""), "");
     }
 
+    #[test]
+    fn strikethrough_test() {
+        assert_eq!(strikethrough("a b"), "a\u{336} b\u{336}");
+        assert_eq!(terminal_string_width(&strikethrough("Hello World")), 11);
+        assert_eq!(strikethrough(""), "");
+    }
+
     #[test]
     fn expand_test() {
         assert_eq!(expand("Hello{}World", 10), String::from("HelloWorld"),);
@@ -209,7 +249,7 @@ mod tests {
             listify(a1.drain(..).map(ts), '-', 6, "r"),
             ts("r- Einsr\nr- Zweir\nr- Dreir"),
         );
-        assert_eq!(listify(a2.drain(..).map(ts), '-', 5, "("), ts(&"(     ("),);
+        assert_eq!(listify(a2.drain(..).map(ts), '-', 5, "("), ts("(     ("),);
         assert_eq!(
             listify(a3.drain(..).map(ts), '#', 32, "+===+"),
             ts("+===+# Aliquam erat volutpat.  Nunc  +===+
@@ -13,10 +13,12 @@
 
 mod border;
 pub mod helper;
+pub mod theme;
 
+pub use self::border::Border;
 pub use self::helper::*;
+pub use self::theme::Theme;
 
-use self::border::Border;
 use std::fmt;
 
 /// A terminal card.
@@ -25,6 +27,7 @@ use std::fmt;
 pub struct Card {
     elements: Vec<Element>,
     border: Border,
+    bullet: char,
     width: usize,
 }
 
@@ -47,11 +50,14 @@ enum Element {
 
 impl Card {
     /// Create a new Card.
-    /// Uses the [heavy](Border::Heavy) border and a width of `40`.
+    /// Uses the border and bullet of the [current theme](Theme::current)
+    /// and a width of `40`.
     pub fn new() -> Self {
+        let theme = Theme::current();
         Card {
             elements: Vec::new(),
-            border: Border::Heavy,
+            border: theme.border,
+            bullet: theme.bullet,
             width: 40,
         }
     }
@@ -60,15 +66,22 @@ impl Card {
         self.width = width;
         self
     }
-    /// Use the light border for the card.
-    #[allow(dead_code)]
-    pub fn with_light_border(mut self) -> Self {
-        self.border = Border::Light;
+    /// Use the given `border` for the card.
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = border;
         self
     }
+    /// Use the light border for the card.
+    pub fn with_light_border(self) -> Self {
+        self.with_border(Border::Light)
+    }
     /// Use the heavy border for the card.
-    pub fn with_heavy_border(mut self) -> Self {
-        self.border = Border::Heavy;
+    pub fn with_heavy_border(self) -> Self {
+        self.with_border(Border::Heavy)
+    }
+    /// Use the given `bullet` for lists.
+    pub fn with_bullet(mut self, bullet: char) -> Self {
+        self.bullet = bullet;
         self
     }
     /// Add a light line.
@@ -120,24 +133,59 @@ impl Card {
         self.elements.push(Element::List(list));
         self
     }
+    /// Add a list of items if `pred` is `true`.
+    pub fn list_if(self, list: Vec<String>, pred: bool) -> Self {
+        if pred {
+            self.list(list)
+        } else {
+            self
+        }
+    }
+    /// Render the card as HTML.
+    ///
+    /// Colors are removed, lines are split at `{}` into a left and a right part.
+    /// The card is a `<div class="card">` and can be styled with CSS.
+    pub fn to_html(&self) -> String {
+        let html = |s: &str| escape_html(&strip_colors(s));
+        let els = self.elements.iter().map(|el| match el {
+            Element::LightLine => String::from("<hr class=\"light\">"),
+            Element::HeavyLine => String::from("<hr class=\"heavy\">"),
+            Element::Text(s) => format!("<p>{}</p>", html(s)),
+            Element::Line(l) => {
+                let mut parts = l.splitn(2, "{}");
+                let left = parts.next().unwrap_or_default();
+                let right = parts.next().unwrap_or_default().replace("{}", "");
+                format!(
+                    "<div class=\"line\"><span>{}</span><span>{}</span></div>",
+                    html(left),
+                    html(&right)
+                )
+            }
+            Element::List(v) => {
+                let items = v.iter().map(|item| format!("<li>{}</li>", html(item)));
+                format!("<ul>{}</ul>", concat(items, ""))
+            }
+        });
+        format!("<div class=\"card\">{}</div>", concat(els, "\n"))
+    }
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Card::new()
+    }
 }
 
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        let border = match self.border {
-            Border::Heavy => " ┃ ",
-            Border::Light => " ┃ ",
-        };
+        let border = self.border.side();
         let mut first = vec![self.border.head(self.width)];
-        let els = self.elements.iter().map(|ref el| match el {
-            Element::LightLine => format!(" ┠{}┨", Border::Light.line(self.width)),
-            Element::HeavyLine => format!(" ┣{}┫", Border::Heavy.line(self.width)),
-            Element::Text(s) => format!("{}", wrap(s, self.width - 2, border)),
+        let els = self.elements.iter().map(|el| match el {
+            Element::LightLine => self.border.light_separator(self.width),
+            Element::HeavyLine => self.border.heavy_separator(self.width),
+            Element::Text(s) => wrap(s, self.width - 2, border),
             Element::Line(l) => format!("{0}{1}{0}", border, expand(l, self.width - 2)),
-            Element::List(v) => format!(
-                "{}",
-                listify(v.iter().map(|v| v.clone()), '•', self.width - 2, border,)
-            ),
+            Element::List(v) => listify(v.iter().cloned(), self.bullet, self.width - 2, border),
         });
         let last = vec![self.border.end(self.width)];
         first.extend(els);
@@ -162,6 +210,9 @@ mod tests {
         assert_eq!(x.border, Border::Heavy);
         let x = Card::new().with_light_border();
         assert_eq!(x.border, Border::Light);
+        let x = Card::new().with_border(Border::Ascii).with_bullet('*');
+        assert_eq!(x.border, Border::Ascii);
+        assert_eq!(x.bullet, '*');
         let x = Card::new().list(vec![
             String::from("1"),
             String::from("2"),
@@ -177,6 +228,13 @@ mod tests {
         );
         let x = Card::new().text("Hello World!");
         assert_eq!(x.elements[0], Element::Text(String::from("Hello World!")));
+        let x = Card::new().line("<A>{}B").light_line().list(vec![String::from("C")]);
+        assert_eq!(
+            x.to_html(),
+            "<div class=\"card\"><div class=\"line\"><span>&lt;A&gt;</span><span>B</span></div>
+<hr class=\"light\">
+<ul><li>C</li></ul></div>"
+        );
         let x = Card::new().heavy_line().text("In between!").light_line();
         assert_eq!(
             x.elements,
@@ -0,0 +1,253 @@
+//! Card themes
+//!
+//! A theme defines the border, the bullet used in lists and the colors
+//! of the badges shown on the cards. Some themes are built in, see
+//! [Theme::builtin](Theme::builtin). A custom theme can be written in TOML,
+//! based on one of the built-in ones:
+//!
+//! ```toml
+//! base = "rounded"
+//! bullet = "-"
+//!
+//! [hp]
+//! fg = "black"
+//! bg = "bright red"
+//! bold = true
+//! ```
+
+use super::border::Border;
+use colored::{Color, Colorize};
+use serde::{Deserialize, Deserializer};
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::{Error, ErrorKind::InvalidData, ErrorKind::NotFound};
+use std::path::Path;
+use std::sync::RwLock;
+use toml;
+
+/// The theme used by all cards created with [Card::new](super::Card::new).
+static CURRENT: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// Names of the built-in themes.
+pub const BUILTIN: [&str; 4] = ["classic", "light", "rounded", "ascii"];
+
+/// A card theme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Border of the cards.
+    pub border: Border,
+    /// Bullet point used for lists.
+    pub bullet: char,
+    /// Style of names and titles.
+    pub name: Style,
+    /// Style of the HP badge.
+    pub hp: Style,
+    /// Style of the armor badge.
+    pub armor: Style,
+    /// Style of the class badges.
+    pub class: Style,
+    /// Style of the requires badge.
+    pub requires: Style,
+    /// Style of the replaces badge.
+    pub replaces: Style,
+    /// Style of the instinct badge.
+    pub instinct: Style,
+}
+
+/// Colors and emphasis of a badge.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Style {
+    /// Foreground color.
+    #[serde(default, deserialize_with = "color")]
+    pub fg: Option<Color>,
+    /// Background color.
+    #[serde(default, deserialize_with = "color")]
+    pub bg: Option<Color>,
+    /// Whether to use a bold font.
+    #[serde(default)]
+    pub bold: bool,
+}
+
+/// A theme file, every field overwrites the one of the `base` theme.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    border: Option<Border>,
+    bullet: Option<char>,
+    name: Option<Style>,
+    hp: Option<Style>,
+    armor: Option<Style>,
+    class: Option<Style>,
+    requires: Option<Style>,
+    replaces: Option<Style>,
+    instinct: Option<Style>,
+}
+
+impl Theme {
+    /// The classic theme with the heavy border.
+    pub fn classic() -> Self {
+        Theme {
+            border: Border::Heavy,
+            bullet: '•',
+            name: Style::new(Some(Color::Yellow), None, true),
+            hp: Style::new(None, Some(Color::Red), false),
+            armor: Style::new(None, Some(Color::Blue), false),
+            class: Style::new(Some(Color::Black), Some(Color::BrightWhite), false),
+            requires: Style::new(Some(Color::Black), Some(Color::Red), false),
+            replaces: Style::new(Some(Color::Black), Some(Color::BrightWhite), false),
+            instinct: Style::new(Some(Color::Black), Some(Color::BrightWhite), false),
+        }
+    }
+    /// Get the built-in theme with the given `name`.
+    /// See [BUILTIN](BUILTIN) for all names.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let classic = Theme::classic();
+        match name {
+            "classic" => Some(classic),
+            "light" => Some(Theme {
+                border: Border::Light,
+                ..classic
+            }),
+            "rounded" => Some(Theme {
+                border: Border::Rounded,
+                bullet: '◦',
+                ..classic
+            }),
+            "ascii" => Some(Theme {
+                border: Border::Ascii,
+                bullet: '*',
+                ..classic
+            }),
+            _ => None,
+        }
+    }
+    /// Parse a theme given in TOML through the reader.
+    pub fn parse<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        let file: ThemeFile = toml::from_str(&content).map_err(|e| Error::new(InvalidData, e))?;
+        let base = file.base.unwrap_or_else(|| String::from("classic"));
+        let base = Theme::builtin(&base)
+            .ok_or_else(|| Error::new(InvalidData, format!("Unknown base theme {}", base)))?;
+        Ok(Theme {
+            border: file.border.unwrap_or(base.border),
+            bullet: file.bullet.unwrap_or(base.bullet),
+            name: file.name.unwrap_or(base.name),
+            hp: file.hp.unwrap_or(base.hp),
+            armor: file.armor.unwrap_or(base.armor),
+            class: file.class.unwrap_or(base.class),
+            requires: file.requires.unwrap_or(base.requires),
+            replaces: file.replaces.unwrap_or(base.replaces),
+            instinct: file.instinct.unwrap_or(base.instinct),
+        })
+    }
+    /// Load the theme given by `name_or_path`.
+    /// This is either the name of a built-in theme or the path to a theme file.
+    pub fn load(name_or_path: &str) -> io::Result<Self> {
+        match Theme::builtin(name_or_path) {
+            Some(theme) => Ok(theme),
+            None if Path::new(name_or_path).exists() => Theme::parse(File::open(name_or_path)?),
+            None => Err(Error::new(
+                NotFound,
+                format!(
+                    "No theme {}, use one of {} or a theme file",
+                    name_or_path,
+                    BUILTIN.join(", ")
+                ),
+            )),
+        }
+    }
+    /// Load the theme file from the given `path` if it exists,
+    /// use the [classic](Theme::classic) theme otherwise.
+    pub fn load_or_default(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            Theme::parse(File::open(path)?)
+        } else {
+            Ok(Theme::classic())
+        }
+    }
+    /// Get the current theme.
+    pub fn current() -> Self {
+        CURRENT
+            .read()
+            .ok()
+            .and_then(|theme| theme.clone())
+            .unwrap_or_else(Theme::classic)
+    }
+    /// Make this theme the current one.
+    pub fn set_current(self) {
+        if let Ok(mut current) = CURRENT.write() {
+            *current = Some(self);
+        }
+    }
+}
+
+impl Style {
+    /// Create a new Style.
+    pub fn new(fg: Option<Color>, bg: Option<Color>, bold: bool) -> Self {
+        Style { fg, bg, bold }
+    }
+    /// Paint the given string `s` in this style.
+    pub fn paint(&self, s: &str) -> String {
+        let mut s = s.normal();
+        if let Some(fg) = self.fg {
+            s = s.color(fg);
+        }
+        if let Some(bg) = self.bg {
+            s = s.on_color(bg);
+        }
+        if self.bold {
+            s = s.bold();
+        }
+        s.to_string()
+    }
+}
+
+/// Deserialize a color from its name, i.e. `"bright white"`.
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+    use serde::de::Error;
+    let name = String::deserialize(deserializer)?;
+    name.parse()
+        .map(Some)
+        .map_err(|_| D::Error::custom(format!("unknown color {}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin() {
+        for name in BUILTIN.iter() {
+            assert!(Theme::builtin(name).is_some());
+        }
+        assert_eq!(Theme::builtin("ascii").unwrap().border, Border::Ascii);
+        assert_eq!(Theme::builtin("nope"), None);
+    }
+
+    #[test]
+    fn parse() {
+        let toml = r#"
+            base = "rounded"
+            bullet = "-"
+
+            [hp]
+            fg = "black"
+            bg = "bright red"
+            bold = true
+        "#;
+        let theme = Theme::parse(toml.as_bytes()).unwrap();
+        assert_eq!(theme.border, Border::Rounded);
+        assert_eq!(theme.bullet, '-');
+        assert_eq!(
+            theme.hp,
+            Style::new(Some(Color::Black), Some(Color::BrightRed), true)
+        );
+        assert_eq!(theme.armor, Theme::classic().armor);
+        assert!(Theme::parse("base = \"nope\"".as_bytes()).is_err());
+        assert!(Theme::parse("[hp]\nfg = \"pink\"".as_bytes()).is_err());
+    }
+}
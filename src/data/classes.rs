@@ -0,0 +1,332 @@
+//! Dungeon World Classes
+//!
+//! ```text
+//!  ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//!  ┃ Fighter              10+CON HP   d10 Damage   12+STR Load  ┃
+//!  ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//!  ┃ It’s a thankless job—living day to day by your armor and   ┃
+//!  ┃ the skill of your arm. To dive heedlessly into danger.     ┃
+//!  ┃ ...                                                        ┃
+//!  ┠────────────────────────────────────────────────────────────┨
+//!  ┃ Starting moves                                             ┃
+//!  ┃ • Bend Bars, Lift Gates                                    ┃
+//!  ┃ • Armored                                                  ┃
+//!  ┃ • Signature Weapon                                         ┃
+//!  ┠────────────────────────────────────────────────────────────┨
+//!  ┃ • Evil: Kill a defenseless or surrendered enemy.           ┃
+//!  ┃ • Good: Defend those weaker than you.                      ┃
+//!  ┃ • Neutral: Defeat a worthy opponent.                       ┃
+//!  ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::card::{Card, Theme};
+use super::moves::Move;
+use super::spells::{spells, Spell};
+use colored::Colorize;
+use regex::Regex;
+use rustyline;
+use rustyline::completion::Completer;
+use serde_json;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::io::Read;
+use std::io::{Error, ErrorKind::InvalidData};
+use std::ops::{Deref, DerefMut};
+
+/// Wrapper around a `Vec<Class>`.
+///
+/// For implementing some functions and traits.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Classes {
+    data: Vec<Class>,
+}
+
+/// Data about a character class.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Class {
+    /// Unique identifier of the class, the lowercase name if none is given.
+    #[serde(default)]
+    key: String,
+    /// Name of the class.
+    name: String,
+    /// A description of the class.
+    #[serde(default)]
+    description: String,
+    /// HP of a new character, CON is added to it.
+    #[serde(default)]
+    base_hp: u8,
+    /// Damage die of the class, i.e. `d8`.
+    #[serde(default)]
+    damage: String,
+    /// Maximum load, STR is added to it.
+    #[serde(default)]
+    load: u8,
+    /// Groups of looks, a character picks one of each group.
+    #[serde(default)]
+    looks: Vec<Vec<String>>,
+    /// Names for each race.
+    #[serde(default)]
+    names: BTreeMap<String, Vec<String>>,
+    /// Possible alignments by their key.
+    #[serde(default)]
+    alignments: BTreeMap<String, Alignment>,
+    /// Bond templates, with `__________` as blanks.
+    #[serde(default)]
+    bonds: Vec<String>,
+    /// Moves every character of this class starts with.
+    #[serde(default)]
+    starting_moves: Vec<Move>,
+    /// Moves to choose from on levels 2-5.
+    #[serde(default)]
+    advanced_moves_1: Vec<Move>,
+    /// Moves to choose from on levels 6-10.
+    #[serde(default)]
+    advanced_moves_2: Vec<Move>,
+    /// Moves depending on the race of the character.
+    #[serde(default)]
+    race_moves: Vec<Move>,
+    /// Spells of the class by their key.
+    #[serde(default, deserialize_with = "spells")]
+    spells: BTreeMap<String, Spell>,
+    /// Stat added to cast a spell, i.e. `INT`. Required if the class has spells.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    casting_stat: Option<String>,
+}
+
+/// An alignment of a class, with the drive which earns XP.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Alignment {
+    /// Name of the alignment, i.e. `Good`.
+    name: String,
+    /// What to do to mark XP.
+    description: String,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchType {
+    None,
+    Description,
+    Name,
+}
+
+impl Classes {
+    /// Parse the data given through the reader into `Classes`.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        let mut data: Vec<Class> =
+            serde_json::from_reader(reader).map_err(|e| Error::new(InvalidData, e))?;
+        data.iter_mut()
+            .filter(|class| class.key.is_empty())
+            .for_each(|class| class.key = class.name.to_lowercase());
+        if let Some(class) = data
+            .iter()
+            .find(|class| !class.spells.is_empty() && class.casting_stat.is_none())
+        {
+            return Err(Error::new(
+                InvalidData,
+                format!("The class {} has spells but no casting_stat", class.name),
+            ));
+        }
+        Ok(Classes { data })
+    }
+    /// Find a class that matches the given String `regex`.
+    /// Matches the given fields in the given order:
+    /// - `name`
+    /// - `description`
+    ///
+    /// The first class whose name matches is returned,
+    /// otherwise the first whose description matches.
+    pub fn find(&self, re: &str) -> Option<&Class> {
+        let mut best = (MatchType::None, None);
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        for class in &self.data {
+            if re.is_match(&class.name) {
+                best = (MatchType::Name, Some(class));
+                break;
+            } else if best.0 < MatchType::Description && re.is_match(&class.description) {
+                best = (MatchType::Description, Some(class));
+            }
+        }
+        best.1
+    }
+    /// Find the class with the given `key`.
+    /// If none is found, [None](Option::None) is returned.
+    pub fn get(&self, key: &str) -> Option<&Class> {
+        self.data.iter().find(|class| class.key == key)
+    }
+    /// Get all classes whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&Class> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data
+            .iter()
+            .filter(|class| re.is_match(&class.name))
+            .collect()
+    }
+    /// List all classes whose name match the given `regex`.
+    pub fn list(&self, re: &str) {
+        println!(">> {}", "Classes".bold());
+        self.filter(re)
+            .iter()
+            .for_each(|class| println!("   {}", class.name));
+    }
+}
+
+impl Class {
+    /// Get the unique identifier of the class.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the name of the class.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the description of the class.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Get the HP of a new character, without CON.
+    pub fn base_hp(&self) -> u8 {
+        self.base_hp
+    }
+    /// Get the damage die of the class.
+    pub fn damage(&self) -> &str {
+        &self.damage
+    }
+    /// Get the maximum load, without STR.
+    pub fn load(&self) -> u8 {
+        self.load
+    }
+    /// Get the groups of looks.
+    pub fn looks(&self) -> &[Vec<String>] {
+        &self.looks
+    }
+    /// Get the names for each race.
+    pub fn names(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.names
+    }
+    /// Get the possible alignments by their key.
+    pub fn alignments(&self) -> &BTreeMap<String, Alignment> {
+        &self.alignments
+    }
+    /// Get the bond templates.
+    pub fn bonds(&self) -> &[String] {
+        &self.bonds
+    }
+    /// Get the moves every character of this class starts with.
+    pub fn starting_moves(&self) -> &[Move] {
+        &self.starting_moves
+    }
+    /// Get the moves to choose from on levels 2-5.
+    pub fn advanced_moves_1(&self) -> &[Move] {
+        &self.advanced_moves_1
+    }
+    /// Get the moves to choose from on levels 6-10.
+    pub fn advanced_moves_2(&self) -> &[Move] {
+        &self.advanced_moves_2
+    }
+    /// Get the moves depending on the race.
+    pub fn race_moves(&self) -> &[Move] {
+        &self.race_moves
+    }
+    /// Get the spells of the class ordered by level and name.
+    pub fn spells(&self) -> Vec<&Spell> {
+        let mut spells: Vec<&Spell> = self.spells.values().collect();
+        spells.sort_by(|a, b| (a.level(), a.name()).cmp(&(b.level(), b.name())));
+        spells
+    }
+    /// Find the first spell of the class whose name matches the given `regex`.
+    pub fn find_spell(&self, re: &str) -> Option<&Spell> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.spells()
+            .into_iter()
+            .find(|spell| re.is_match(spell.name()))
+    }
+    /// Get the stat added to cast a spell, i.e. `INT`,
+    /// [None](Option::None) if the class casts no spells.
+    pub fn casting_stat(&self) -> Option<&str> {
+        self.casting_stat.as_deref()
+    }
+    /// Create the card showing this class.
+    pub fn card(&self) -> Card {
+        let width = 60;
+        let theme = Theme::current();
+        // Name, HP, damage and load
+        let name = theme.name.paint(&self.name);
+        let stats = format!(
+            "{}{{}}{} {} {}",
+            name,
+            theme.hp.paint(&format!(" {}+CON HP ", self.base_hp)),
+            theme.class.paint(&format!(" {} Damage ", self.damage)),
+            theme.armor.paint(&format!(" {}+STR Load ", self.load)),
+        );
+        // Starting moves
+        let moves = self
+            .starting_moves
+            .iter()
+            .map(|mv| mv.name().to_string())
+            .collect();
+        // Alignments
+        let alignments = self
+            .alignments
+            .values()
+            .map(|al| format!("{}: {}", al.name, al.description))
+            .collect();
+        Card::new()
+            .with_width(width)
+            .line(&stats)
+            .heavy_line()
+            .text(&self.description)
+            .light_line()
+            .line("Starting moves")
+            .list(moves)
+            .light_line()
+            .list(alignments)
+    }
+}
+
+impl Alignment {
+    /// Get the name of the alignment.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get what to do to mark XP.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for Classes {
+    type Target = Vec<Class>;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for Classes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl Completer for Classes {
+    fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
+        if line.starts_with("class ") && pos >= 6 {
+            let part_class = line.trim_start_matches("class ").to_lowercase();
+            let mut ret = Vec::new();
+            for class in &self.data {
+                if class.name.to_lowercase().starts_with(&part_class) {
+                    ret.push(class.name.clone());
+                }
+            }
+            Ok((6, ret))
+        } else {
+            Ok((pos, vec![]))
+        }
+    }
+}
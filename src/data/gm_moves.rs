@@ -0,0 +1,347 @@
+//! Moves of the GM and the dungeon
+//!
+//! ```text
+//!  ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//!  ┃ Separate them                                          GM  ┃
+//!  ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//!  ┃ Few things are worse than being alone in a dungeon. A      ┃
+//!  ┃ collapsing passage, a crowd, a portal or a closing door    ┃
+//!  ┃ splits the party.                                          ┃
+//!  ┠────────────────────────────────────────────────────────────┨
+//!  ┃ Fits horde, group, organized, large, huge                  ┃
+//!  ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::card::{Card, Theme};
+use super::Monster;
+use colored::Colorize;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::Regex;
+use rustyline;
+use rustyline::completion::Completer;
+use serde_json;
+use std::fmt;
+use std::io;
+use std::io::Read;
+use std::io::{Error, ErrorKind::InvalidData};
+use std::ops::{Deref, DerefMut};
+
+/// Wrapper around a `Vec<GmMove>`.
+///
+/// For implementing some functions and traits.
+#[derive(Default)]
+pub struct GmMoves {
+    data: Vec<GmMove>,
+}
+
+/// A move of the GM or of the dungeon.
+///
+/// Soft moves are made whenever everyone looks to the GM,
+/// hard moves only when a player misses or hands over a golden opportunity.
+/// The monster tags a move fits help to pick one for the foes at hand.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GmMove {
+    /// Unique identifier of the move.
+    key: String,
+    /// Name of the move.
+    name: String,
+    /// Whether this is a move of the GM or of the dungeon.
+    kind: GmMoveKind,
+    /// What the move looks like at the table.
+    description: String,
+    /// Whether the move may be made as a soft move.
+    #[serde(default = "soft_default")]
+    soft: bool,
+    /// Monster tags this move fits, empty if it fits any monster.
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Who makes a [GmMove].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GmMoveKind {
+    /// One of the moves of the GM.
+    Gm,
+    /// One of the moves when the party explores a dungeon.
+    Dungeon,
+}
+
+/// A soft move suggested by [GmMoves::suggest].
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    /// The suggested move.
+    gm_move: GmMove,
+    /// Name of a foe and one of its moves.
+    monster_move: Option<(String, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchType {
+    None,
+    Description,
+    Name,
+}
+
+impl GmMoves {
+    /// Parse the data given through the reader into `GmMoves`.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|data| GmMoves { data })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Find a move that matches the given String `re`.
+    /// Matches the given fields in the given order:
+    /// - `name`
+    /// - `description`
+    ///
+    /// The first move whose name matches is returned,
+    /// otherwise the first whose description matches.
+    pub fn find(&self, re: &str) -> Option<&GmMove> {
+        let mut best = (MatchType::None, None);
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        for mv in &self.data {
+            if re.is_match(&mv.name) {
+                best = (MatchType::Name, Some(mv));
+                break;
+            } else if best.0 < MatchType::Description && re.is_match(&mv.description) {
+                best = (MatchType::Description, Some(mv));
+            }
+        }
+        best.1
+    }
+    /// Find the move with the given `key`.
+    /// If none is found, [None](Option::None) is returned.
+    pub fn get(&self, key: &str) -> Option<&GmMove> {
+        self.data.iter().find(|mv| mv.key == key)
+    }
+    /// Get all moves whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&GmMove> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data
+            .iter()
+            .filter(|mv| re.is_match(&mv.name))
+            .collect()
+    }
+    /// List all moves whose name match the given `regex`,
+    /// the moves of the GM before those of the dungeon.
+    pub fn list(&self, re: &str) {
+        let moves = self.filter(re);
+        for (kind, title) in &[
+            (GmMoveKind::Gm, "GM Moves"),
+            (GmMoveKind::Dungeon, "Dungeon Moves"),
+        ] {
+            println!(">> {}", title.bold());
+            moves
+                .iter()
+                .filter(|mv| mv.kind == *kind)
+                .for_each(|mv| println!("   {}", mv.name));
+        }
+    }
+    /// Suggest a random soft move fitting any of the `monsters`, any soft move without monsters.
+    /// If one of the `monsters` has moves, one of them is suggested alongside.
+    /// Both are picked with the random number generator `rng`.
+    pub fn suggest<R: Rng + ?Sized>(
+        &self,
+        monsters: &[&Monster],
+        rng: &mut R,
+    ) -> Option<Suggestion> {
+        let candidates: Vec<&GmMove> = self
+            .data
+            .iter()
+            .filter(|mv| mv.soft)
+            .filter(|mv| monsters.is_empty() || monsters.iter().any(|m| mv.fits(m)))
+            .collect();
+        let with_moves: Vec<&&Monster> =
+            monsters.iter().filter(|m| !m.moves().is_empty()).collect();
+        let gm_move = (*candidates.choose(rng)?).clone();
+        let monster_move = with_moves.choose(rng).and_then(|monster| {
+            let mv = monster.moves().choose(rng)?;
+            Some((monster.name().to_string(), mv.clone()))
+        });
+        Some(Suggestion {
+            gm_move,
+            monster_move,
+        })
+    }
+}
+
+impl GmMove {
+    /// Get the unique identifier of the move.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the name of the move.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get whether this is a move of the GM or of the dungeon.
+    pub fn kind(&self) -> GmMoveKind {
+        self.kind
+    }
+    /// Get the description of the move.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Whether the move may be made as a soft move.
+    pub fn is_soft(&self) -> bool {
+        self.soft
+    }
+    /// Get the monster tags this move fits.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    /// Whether this move fits the `monster`,
+    /// i.e. it fits any monster or they share a tag.
+    pub fn fits(&self, monster: &Monster) -> bool {
+        self.tags.is_empty() || monster.tags().iter().any(|tag| self.tags.contains(tag))
+    }
+    /// Create the card showing this move.
+    pub fn card(&self) -> Card {
+        let width = 60;
+        let theme = Theme::current();
+        let name = theme.name.paint(&self.name);
+        let kind = theme.class.paint(&format!(" {} ", self.kind.name()));
+        let hard = format!(
+            "{} only on a miss or a golden opportunity",
+            theme.requires.paint(" Hard ")
+        );
+        let has_tags = !self.tags.is_empty();
+        Card::new()
+            .with_width(width)
+            .line(&format!("{}{{}}{}", name, kind))
+            .heavy_line()
+            .line_if(&hard, !self.soft)
+            .light_line_if(!self.soft)
+            .text(&self.description)
+            .light_line_if(has_tags)
+            .text_if(&format!("Fits {}", self.tags.join(", ")), has_tags)
+    }
+}
+
+impl GmMoveKind {
+    /// Get the name as shown on cards.
+    pub fn name(self) -> &'static str {
+        match self {
+            GmMoveKind::Gm => "GM",
+            GmMoveKind::Dungeon => "Dungeon",
+        }
+    }
+}
+
+impl Suggestion {
+    /// Get the suggested move.
+    pub fn gm_move(&self) -> &GmMove {
+        &self.gm_move
+    }
+    /// Get the name of a foe and one of its moves, if any.
+    pub fn monster_move(&self) -> Option<(&str, &str)> {
+        self.monster_move
+            .as_ref()
+            .map(|(monster, mv)| (monster.as_str(), mv.as_str()))
+    }
+    /// Create the card of the move with the move of the foe below.
+    pub fn card(&self) -> Card {
+        let card = self.gm_move.card();
+        match self.monster_move {
+            Some((ref monster, ref mv)) => {
+                card.light_line()
+                    .text(&format!("{}: {}", Theme::current().name.paint(monster), mv))
+            }
+            None => card,
+        }
+    }
+}
+
+impl fmt::Display for GmMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for GmMoves {
+    type Target = Vec<GmMove>;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for GmMoves {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl Completer for GmMoves {
+    fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
+        if line.starts_with("gm ") && pos >= 3 {
+            let part_mv = line.trim_start_matches("gm ").to_lowercase();
+            let mut ret = Vec::new();
+            for mv in &self.data {
+                if mv.name.to_lowercase().starts_with(&part_mv) {
+                    ret.push(mv.name.clone());
+                }
+            }
+            Ok((3, ret))
+        } else {
+            Ok((pos, vec![]))
+        }
+    }
+}
+
+/// Simple helper function for Serde, moves are soft unless told otherwise.
+fn soft_default() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+    use data::Monsters;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::fs::File;
+
+    #[test]
+    fn gm_moves() {
+        let moves = GmMoves::parse(File::open("data/gm_moves.json").unwrap()).unwrap();
+        let monsters = Monsters::parse(File::open("data/monsters.json").unwrap()).unwrap();
+        let damage = moves.find("deal damage").unwrap();
+        assert!(!damage.is_soft());
+        assert_eq!(damage.kind(), GmMoveKind::Gm);
+        assert!(strip_colors(&damage.to_string()).contains("Hard"));
+        assert_eq!(
+            moves.get("make_them_backtrack").unwrap().kind(),
+            GmMoveKind::Dungeon
+        );
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert!(moves.suggest(&[], &mut rng).unwrap().gm_move().is_soft());
+        }
+        let goblin = monsters.find("goblin").unwrap();
+        for _ in 0..20 {
+            let suggestion = moves.suggest(&[goblin], &mut rng).unwrap();
+            assert!(suggestion.gm_move().fits(goblin));
+            let (name, mv) = suggestion.monster_move().unwrap();
+            assert_eq!(name, goblin.name());
+            assert!(goblin.moves().contains(&mv.to_string()));
+        }
+        // The same seed gives the same suggestion
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let suggestion = moves.suggest(&[goblin], &mut rng).unwrap();
+        assert_eq!(suggestion.gm_move().key(), "tell_the_requirements_and_ask");
+        assert_eq!(
+            suggestion.monster_move(),
+            Some(("Goblin", "Retreat and return with (many) more"))
+        );
+    }
+}
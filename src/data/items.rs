@@ -1,5 +1,5 @@
 use super::card::helper::*;
-use super::card::Card;
+use super::card::{Card, Theme};
 use colored::*;
 use regex::Regex;
 use rustyline;
@@ -20,7 +20,7 @@ pub struct Items {
 }
 
 /// Item data.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Item {
     // Name of the item
     name: String,
@@ -37,11 +37,15 @@ pub struct Item {
     tags: Vec<ItemTag>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A tag of an item, possibly with a value.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
-enum ItemTag {
+pub enum ItemTag {
+    /// A tag with a numeric value, i.e. `{"weight": 1}`.
     MapI(BTreeMap<String, u16>),
+    /// A tag with a textual value, i.e. `{"range": "near"}`.
     MapS(BTreeMap<String, String>),
+    /// A tag without value, i.e. `"ration"`.
     Tag(String),
 }
 
@@ -63,6 +67,7 @@ impl Items {
     /// Matches the given fields in the given order:
     /// - `name`
     /// - `description`
+    ///
     /// The first item whose name matches is returned,
     /// otherwise the first whose description matches, ...
     pub fn find(&self, re: &str) -> Option<&Item> {
@@ -78,39 +83,110 @@ impl Items {
         }
         best.1
     }
+    /// Find the item with the given `key`.
+    /// If none is found, [None](Option::None) is returned.
+    pub fn get(&self, key: &str) -> Option<&Item> {
+        self.data.iter().find(|item| item.key == key)
+    }
+    /// Get all items whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&Item> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter().filter(|item| re.is_match(&item.name)).collect()
+    }
     /// List all items whose name match the given `regex`.
     pub fn list(&self, re: &str) {
         println!(">> {}", "Items".bold());
-        let re = Regex::new(&format!("(?i){}", re)).unwrap();
-        self.data
+        self.filter(re)
             .iter()
-            .filter(|item| re.is_match(&item.name))
-            .map(|item| println!("   {}", item.name))
-            .collect::<Vec<()>>();
+            .for_each(|item| println!("   {}", item.name));
     }
 }
 
-impl fmt::Display for Item {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+impl Item {
+    /// Get the name of the item.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the plural form of the name, empty if it's the default.
+    pub fn plural_name(&self) -> &str {
+        &self.plural_name
+    }
+    /// Get the unique identifier of the item.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the description of the item.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Get the valued tags of the item.
+    pub fn tags(&self) -> &[ItemTag] {
+        &self.tags
+    }
+    /// Get the value of the tag with the given `key`, i.e. `1` for `{"armor": 1}`.
+    pub fn value(&self, key: &str) -> Option<String> {
+        self.tags.iter().find_map(|tag| match tag {
+            ItemTag::MapI(map) => map.get(key).map(|value| value.to_string()),
+            ItemTag::MapS(map) => map.get(key).cloned(),
+            ItemTag::Tag(_) => None,
+        })
+    }
+    /// Get the armor of the item, 0 if it has none.
+    pub fn armor(&self) -> u8 {
+        self.value("armor")
+            .and_then(|armor| armor.trim_start_matches('+').parse().ok())
+            .unwrap_or(0)
+    }
+    /// Whether the armor adds to the armor of other items, like the `+1` of a shield.
+    pub fn adds_armor(&self) -> bool {
+        self.value("armor")
+            .map(|armor| armor.starts_with('+'))
+            .unwrap_or(false)
+    }
+    /// Get the weight of the item, 0 if it has none.
+    pub fn weight(&self) -> u8 {
+        self.value("weight")
+            .and_then(|weight| weight.parse().ok())
+            .unwrap_or(0)
+    }
+    /// Get the price in coins, [None](Option::None) if the item is not for sale.
+    pub fn coins(&self) -> Option<u32> {
+        self.value("coins").and_then(|coins| coins.parse().ok())
+    }
+    /// Whether the item has the tag `name` without a value, i.e. `close`.
+    pub fn has_tag(&self, name: &str) -> bool {
+        self.tags.iter().any(|tag| match tag {
+            ItemTag::Tag(tag) => tag == name,
+            _ => false,
+        })
+    }
+    /// Whether the item is a weapon, which have a range like `close` or `near`.
+    pub fn is_weapon(&self) -> bool {
+        ["hand", "close", "reach", "near", "far"]
+            .iter()
+            .any(|range| self.has_tag(range))
+    }
+    /// Create the card showing this item.
+    pub fn card(&self) -> Card {
         let width = 40;
         // Name
-        let name = format!("{}", self.name.bold().yellow());
+        let name = Theme::current().name.paint(&self.name);
         // Tags
         let tags = self.tags.iter().map(|tag| format!("{}", tag));
         let tags = concat(tags, ", ");
-        // Create the card and show it
-        write!(
-            f,
-            "{}",
-            Card::new()
-                .with_width(width)
-                .with_heavy_border()
-                .line(&name)
-                .heavy_line()
-                .line(&tags)
-                .light_line()
-                .text(&self.description)
-        )
+        Card::new()
+            .with_width(width)
+            .line(&name)
+            .heavy_line()
+            .line(&tags)
+            .light_line()
+            .text(&self.description)
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
     }
 }
 
@@ -119,14 +195,14 @@ impl fmt::Display for ItemTag {
         let (key, value) = match self {
             ItemTag::MapI(map) => {
                 if let Some((k, v)) = map.iter().next() {
-                    (k, format!("{}", v))
+                    (k, v.to_string())
                 } else {
                     return write!(f, "BUG WITH TAGS");
                 }
             }
             ItemTag::MapS(map) => {
                 if let Some((k, v)) = map.iter().next() {
-                    (k, format!("{}", v))
+                    (k, v.to_string())
                 } else {
                     return write!(f, "BUG WITH TAGS");
                 }
@@ -156,7 +232,7 @@ impl DerefMut for Items {
 impl Completer for Items {
     fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
         if line.starts_with("item ") && pos >= 5 {
-            let part_item = line.trim_left_matches("item ").to_lowercase();
+            let part_item = line.trim_start_matches("item ").to_lowercase();
             let mut ret = Vec::new();
             for item in &self.data {
                 if item.name.to_lowercase().starts_with(&part_item) {
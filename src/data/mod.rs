@@ -1,66 +1,127 @@
 pub mod card;
 
+mod classes;
+mod gm_moves;
 mod items;
 mod monsters;
 mod moves;
+mod npc;
+mod spells;
+mod tables;
 mod tags;
+mod treasure;
 
+pub use self::classes::Alignment;
+pub use self::classes::Class;
+pub use self::classes::Classes;
+pub use self::gm_moves::{GmMove, GmMoveKind, GmMoves, Suggestion};
 pub use self::items::Item;
+pub use self::items::ItemTag;
 pub use self::items::Items;
+pub use self::monsters::Attack;
 pub use self::monsters::Monster;
 pub use self::monsters::Monsters;
+pub use self::monsters::{Defense, KnownFor, MonsterBuilder, Organization, Size, Trait};
 pub use self::moves::Move;
 pub use self::moves::Moves;
+pub use self::npc::Npc;
+pub use self::spells::Spell;
+pub use self::tables::{Entry, Table, TableRoll, Tables};
 pub use self::tags::Tag;
 pub use self::tags::Tags;
+pub use self::treasure::{Hoard, Treasure};
 
 use rustyline;
 use rustyline::completion::Completer;
 use std::fs::File;
 use std::io;
+use std::path::PathBuf;
 
 /// Data wrapper.
 /// This wrapper contains the following data:
 /// - monsters. See [Monsters](self::monsters::Monsters)
 /// - moves. See [Moves](self::moves::Moves)
+/// - moves of the GM and the dungeon. See [GmMoves](self::gm_moves::GmMoves)
 /// - tags. See [Tags](self::tags::Tags)
 /// - items. See [Items](self::items::Items)
+/// - classes. See [Classes](self::classes::Classes)
+/// - tables of the GM. See [Tables](self::tables::Tables)
 pub struct Data {
     pub monsters: Monsters,
     pub moves: Moves,
+    pub gm_moves: GmMoves,
     pub tags: Tags,
     pub items: Items,
+    pub classes: Classes,
+    pub tables: Tables,
 }
 
 impl Data {
     /// Create a new Data object wrapping `monsters`' and `moves`' data.
+    /// The GM moves and classes are empty, [from](Data::from) parses them as well.
+    /// There are no tables until they are [loaded](Tables::load).
     pub fn new(monsters: Monsters, moves: Moves, tags: Tags, items: Items) -> Self {
         Data {
             monsters,
             moves,
+            gm_moves: GmMoves::default(),
             tags,
             items,
+            classes: Classes::default(),
+            tables: Tables::new(),
         }
     }
-    /// Create a new Data object by parsing the files given by their paths:
-    /// - `monster_file` for monsters data
-    /// - `moves_file` for moves data
-    pub fn from(
-        monster_file: &str,
-        moves_file: &str,
-        tags_file: &str,
-        items_file: &str,
-    ) -> io::Result<Self> {
-        let f = File::open(monster_file)?;
+    /// Create a new Data object by parsing the files given by their `paths`.
+    pub fn from(paths: &DataPaths) -> io::Result<Self> {
+        let f = File::open(&paths.moves)?;
         let moves = Moves::parse(f)?;
-        let f = File::open(moves_file)?;
+        let f = File::open(&paths.gm_moves)?;
+        let gm_moves = GmMoves::parse(f)?;
+        let f = File::open(&paths.monsters)?;
         let monsters = Monsters::parse(f)?;
-        let f = File::open(tags_file)?;
+        let f = File::open(&paths.tags)?;
         let tags = Tags::parse(f)?;
-        let f = File::open(items_file)?;
+        let f = File::open(&paths.items)?;
         let items = Items::parse(f)?;
+        let f = File::open(&paths.classes)?;
+        let classes = Classes::parse(f)?;
 
-        Ok(Data::new(monsters, moves, tags, items))
+        Ok(Data {
+            gm_moves,
+            classes,
+            ..Data::new(monsters, moves, tags, items)
+        })
+    }
+}
+
+/// Paths of the files [Data] is parsed [from](Data::from).
+/// By [default](Default) these are the files in `data/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataPaths {
+    /// Moves data.
+    pub moves: PathBuf,
+    /// Moves of the GM and the dungeon.
+    pub gm_moves: PathBuf,
+    /// Monsters data.
+    pub monsters: PathBuf,
+    /// Tags data.
+    pub tags: PathBuf,
+    /// Items data.
+    pub items: PathBuf,
+    /// Classes data.
+    pub classes: PathBuf,
+}
+
+impl Default for DataPaths {
+    fn default() -> Self {
+        DataPaths {
+            moves: PathBuf::from("data/moves.json"),
+            gm_moves: PathBuf::from("data/gm_moves.json"),
+            monsters: PathBuf::from("data/monsters.json"),
+            tags: PathBuf::from("data/tags.json"),
+            items: PathBuf::from("data/items.json"),
+            classes: PathBuf::from("data/classes.json"),
+        }
     }
 }
 
@@ -68,12 +129,16 @@ impl Completer for Data {
     fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
         if line.starts_with("move ") {
             self.moves.complete(line, pos)
+        } else if line.starts_with("gm ") {
+            self.gm_moves.complete(line, pos)
         } else if line.starts_with("monster ") {
             self.monsters.complete(line, pos)
         } else if line.starts_with("tag ") {
             self.tags.complete(line, pos)
         } else if line.starts_with("item ") {
             self.items.complete(line, pos)
+        } else if line.starts_with("class ") {
+            self.classes.complete(line, pos)
         } else {
             Ok((pos, vec![]))
         }
@@ -0,0 +1,543 @@
+//! Monster creation following the questions of the Dungeon World rules
+//!
+//! Every answer adjusts the HP, the damage, the armor and the tags of the monster:
+//!
+//! ```
+//! use console_hero::data::{Defense, KnownFor, MonsterBuilder, Organization, Size};
+//!
+//! let monster = MonsterBuilder::new("Cave Bear")
+//!     .organization(Organization::Solitary)
+//!     .size(Size::Large)
+//!     .defense(Defense::Leather)
+//!     .known_for(KnownFor::Strength)
+//!     .attack("Claws")
+//!     .build();
+//! assert_eq!(monster.hp(), 16);
+//! assert_eq!(monster.armor(), 1);
+//! assert_eq!(monster.attacks()[0].damage(), "d10+3");
+//! ```
+
+use super::{Attack, Monster};
+
+/// How does it usually hunt or fight?
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Organization {
+    /// In large groups: horde, d6 damage, 3 HP.
+    Horde,
+    /// In small groups, about 2-5: group, d8 damage, 6 HP.
+    Group,
+    /// All by its lonesome: solitary, d10 damage, 12 HP.
+    Solitary,
+}
+
+/// How big is it?
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Size {
+    /// Smaller than a house cat: tiny, hand, -2 damage.
+    Tiny,
+    /// Halfling-esque: small, close.
+    Small,
+    /// About human size: close.
+    Human,
+    /// As big as a cart: large, close, reach, +4 HP, +1 damage.
+    Large,
+    /// Much larger than a cart: huge, reach, +8 HP, +3 damage.
+    Huge,
+}
+
+/// What is its most important defense?
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defense {
+    /// Cloth or flesh: 0 armor.
+    Flesh,
+    /// Leathers or thick hide: 1 armor.
+    Leather,
+    /// Mail or scales: 2 armor.
+    Mail,
+    /// Plate or bone: 3 armor.
+    Plate,
+    /// Permanent magical protection: 4 armor, magical.
+    Magic,
+}
+
+/// What is it known for?
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownFor {
+    /// Unrelenting strength: +2 damage, forceful.
+    Strength,
+    /// Skill in offense: roll damage twice and take the better roll.
+    Offense,
+    /// Skill in defense: +1 armor.
+    Defense,
+    /// Deft strikes: +1 piercing.
+    DeftStrikes,
+    /// Uncanny endurance: +4 HP.
+    Endurance,
+    /// Deceit and trickery: stealthy.
+    Deceit,
+    /// The favor of the gods: divine, +2 damage, +2 HP.
+    Divine,
+    /// Spells and magic: magical.
+    Magic,
+}
+
+/// Which of these describe it?
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trait {
+    /// Dangerous for other reasons than its wounds: devious, smaller damage die.
+    Devious,
+    /// Organizes into larger groups it can call on for support: organized.
+    Organized,
+    /// As smart as a human or thereabouts: intelligent.
+    Intelligent,
+    /// Actively defends itself with a shield or similar: cautious, +1 armor.
+    Cautious,
+    /// Collects trinkets that humans would consider valuable: hoarder.
+    Hoarder,
+    /// From beyond this world: planar.
+    Planar,
+    /// Kept alive by something beyond simple biology: +4 HP.
+    Undying,
+    /// Made by someone: construct.
+    Construct,
+    /// Its appearance is disturbing, terrible, or horrible: terrifying.
+    Terrifying,
+    /// No organs or discernible anatomy: amorphous, +1 armor, -4 HP.
+    Amorphous,
+    /// Older than men, elves, and dwarves: larger damage die.
+    Ancient,
+    /// Abhors violence: roll damage twice and take the worse result.
+    Pacifist,
+}
+
+/// Sides of the damage dice, from smallest to largest.
+const DICE: [u32; 5] = [4, 6, 8, 10, 12];
+
+/// Builds a [Monster](Monster) from the answers to the questions of the rules.
+#[derive(Debug, Clone)]
+pub struct MonsterBuilder {
+    name: String,
+    organization: Organization,
+    size: Size,
+    defense: Defense,
+    known_for: Vec<KnownFor>,
+    traits: Vec<Trait>,
+    attack: String,
+    ranged: Vec<String>,
+    instinct: String,
+    moves: Vec<String>,
+    qualities: Vec<String>,
+    description: String,
+}
+
+impl Organization {
+    /// All answers, in the order of the rules.
+    pub const ALL: [Organization; 3] = [
+        Organization::Horde,
+        Organization::Group,
+        Organization::Solitary,
+    ];
+    /// Get the answer as written in the rules.
+    pub fn answer(&self) -> &'static str {
+        match *self {
+            Organization::Horde => "In large groups",
+            Organization::Group => "In small groups, about 2-5",
+            Organization::Solitary => "All by its lonesome",
+        }
+    }
+}
+
+impl Size {
+    /// All answers, in the order of the rules.
+    pub const ALL: [Size; 5] = [
+        Size::Tiny,
+        Size::Small,
+        Size::Human,
+        Size::Large,
+        Size::Huge,
+    ];
+    /// Get the answer as written in the rules.
+    pub fn answer(&self) -> &'static str {
+        match *self {
+            Size::Tiny => "Smaller than a house cat",
+            Size::Small => "Halfling-esque",
+            Size::Human => "About human size",
+            Size::Large => "As big as a cart",
+            Size::Huge => "Much larger than a cart",
+        }
+    }
+}
+
+impl Defense {
+    /// All answers, in the order of the rules.
+    pub const ALL: [Defense; 5] = [
+        Defense::Flesh,
+        Defense::Leather,
+        Defense::Mail,
+        Defense::Plate,
+        Defense::Magic,
+    ];
+    /// Get the answer as written in the rules.
+    pub fn answer(&self) -> &'static str {
+        match *self {
+            Defense::Flesh => "Cloth or flesh",
+            Defense::Leather => "Leathers or thick hide",
+            Defense::Mail => "Mail or scales",
+            Defense::Plate => "Plate or bone",
+            Defense::Magic => "Permanent magical protection",
+        }
+    }
+}
+
+impl KnownFor {
+    /// All answers, in the order of the rules.
+    pub const ALL: [KnownFor; 8] = [
+        KnownFor::Strength,
+        KnownFor::Offense,
+        KnownFor::Defense,
+        KnownFor::DeftStrikes,
+        KnownFor::Endurance,
+        KnownFor::Deceit,
+        KnownFor::Divine,
+        KnownFor::Magic,
+    ];
+    /// Get the answer as written in the rules.
+    pub fn answer(&self) -> &'static str {
+        match *self {
+            KnownFor::Strength => "Unrelenting strength",
+            KnownFor::Offense => "Skill in offense",
+            KnownFor::Defense => "Skill in defense",
+            KnownFor::DeftStrikes => "Deft strikes",
+            KnownFor::Endurance => "Uncanny endurance",
+            KnownFor::Deceit => "Deceit and trickery",
+            KnownFor::Divine => "The favor of the gods",
+            KnownFor::Magic => "Spells and magic",
+        }
+    }
+}
+
+impl Trait {
+    /// All answers, in the order of the rules.
+    pub const ALL: [Trait; 12] = [
+        Trait::Devious,
+        Trait::Organized,
+        Trait::Intelligent,
+        Trait::Cautious,
+        Trait::Hoarder,
+        Trait::Planar,
+        Trait::Undying,
+        Trait::Construct,
+        Trait::Terrifying,
+        Trait::Amorphous,
+        Trait::Ancient,
+        Trait::Pacifist,
+    ];
+    /// Get the answer as written in the rules.
+    pub fn answer(&self) -> &'static str {
+        match *self {
+            Trait::Devious => "It isn't dangerous because of the wounds it inflicts",
+            Trait::Organized => "It organizes into larger groups that it can call on",
+            Trait::Intelligent => "It's as smart as a human or thereabouts",
+            Trait::Cautious => "It actively defends itself with a shield or similar",
+            Trait::Hoarder => "It collects trinkets that humans would consider valuable",
+            Trait::Planar => "It's from beyond this world",
+            Trait::Undying => "It's kept alive by something beyond simple biology",
+            Trait::Construct => "It was made by someone",
+            Trait::Terrifying => "Its appearance is disturbing, terrible, or horrible",
+            Trait::Amorphous => "It doesn't have organs or discernible anatomy",
+            Trait::Ancient => "It (or its species) is ancient",
+            Trait::Pacifist => "It abhors violence",
+        }
+    }
+}
+
+impl MonsterBuilder {
+    /// Start a human sized, solitary monster without armor named `name`.
+    pub fn new(name: &str) -> Self {
+        MonsterBuilder {
+            name: name.to_string(),
+            organization: Organization::Solitary,
+            size: Size::Human,
+            defense: Defense::Flesh,
+            known_for: Vec::new(),
+            traits: Vec::new(),
+            attack: String::from("Attack"),
+            ranged: Vec::new(),
+            instinct: String::new(),
+            moves: Vec::new(),
+            qualities: Vec::new(),
+            description: String::new(),
+        }
+    }
+    /// Set how it usually hunts or fights.
+    pub fn organization(mut self, organization: Organization) -> Self {
+        self.organization = organization;
+        self
+    }
+    /// Set how big it is.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+    /// Set its most important defense.
+    pub fn defense(mut self, defense: Defense) -> Self {
+        self.defense = defense;
+        self
+    }
+    /// Add something it is known for.
+    pub fn known_for(mut self, known_for: KnownFor) -> Self {
+        if !self.known_for.contains(&known_for) {
+            self.known_for.push(known_for);
+        }
+        self
+    }
+    /// Add a trait that describes it.
+    pub fn describe(mut self, description: Trait) -> Self {
+        if !self.traits.contains(&description) {
+            self.traits.push(description);
+        }
+        self
+    }
+    /// Set the name of its most common form of attack.
+    pub fn attack(mut self, attack: &str) -> Self {
+        self.attack = attack.to_string();
+        self
+    }
+    /// Add a range tag to the attack, i.e. `near` or `far`.
+    pub fn range(mut self, range: &str) -> Self {
+        self.ranged.push(range.to_string());
+        self
+    }
+    /// Set what it wants that causes problems.
+    pub fn instinct(mut self, instinct: &str) -> Self {
+        self.instinct = instinct.to_string();
+        self
+    }
+    /// Add something it is known to do.
+    pub fn monster_move(mut self, monster_move: &str) -> Self {
+        self.moves.push(monster_move.to_string());
+        self
+    }
+    /// Add a special quality, i.e. `Amphibious` or `Wings`.
+    pub fn quality(mut self, quality: &str) -> Self {
+        self.qualities.push(quality.to_string());
+        self
+    }
+    /// Set the description.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+    fn is_known_for(&self, known_for: KnownFor) -> bool {
+        self.known_for.contains(&known_for)
+    }
+    fn has(&self, description: Trait) -> bool {
+        self.traits.contains(&description)
+    }
+    /// Get the HP of the monster.
+    pub fn hp(&self) -> u8 {
+        let mut hp: i32 = match self.organization {
+            Organization::Horde => 3,
+            Organization::Group => 6,
+            Organization::Solitary => 12,
+        };
+        hp += match self.size {
+            Size::Large => 4,
+            Size::Huge => 8,
+            _ => 0,
+        };
+        if self.is_known_for(KnownFor::Endurance) {
+            hp += 4;
+        }
+        if self.is_known_for(KnownFor::Divine) {
+            hp += 2;
+        }
+        if self.has(Trait::Undying) {
+            hp += 4;
+        }
+        if self.has(Trait::Amorphous) {
+            hp -= 4;
+        }
+        hp.max(1) as u8
+    }
+    /// Get the armor of the monster.
+    pub fn armor(&self) -> u8 {
+        let mut armor = match self.defense {
+            Defense::Flesh => 0,
+            Defense::Leather => 1,
+            Defense::Mail => 2,
+            Defense::Plate => 3,
+            Defense::Magic => 4,
+        };
+        if self.is_known_for(KnownFor::Defense) {
+            armor += 1;
+        }
+        if self.has(Trait::Cautious) {
+            armor += 1;
+        }
+        if self.has(Trait::Amorphous) {
+            armor += 1;
+        }
+        armor
+    }
+    /// Get the damage of the monster as a d20 expression, i.e. `b[2d10]+2`.
+    pub fn damage(&self) -> String {
+        let mut die = match self.organization {
+            Organization::Horde => 1,
+            Organization::Group => 2,
+            Organization::Solitary => 3,
+        };
+        if self.has(Trait::Devious) {
+            die -= 1;
+        }
+        if self.has(Trait::Ancient) {
+            die += 1;
+        }
+        let sides = DICE[die.max(0).min(DICE.len() as i32 - 1) as usize];
+        let mut bonus = match self.size {
+            Size::Tiny => -2,
+            Size::Large => 1,
+            Size::Huge => 3,
+            _ => 0,
+        };
+        if self.is_known_for(KnownFor::Strength) {
+            bonus += 2;
+        }
+        if self.is_known_for(KnownFor::Divine) {
+            bonus += 2;
+        }
+        let best = self.is_known_for(KnownFor::Offense);
+        let worst = self.has(Trait::Pacifist);
+        let dice = match (best, worst) {
+            (true, false) => format!("b[2d{}]", sides),
+            (false, true) => format!("w[2d{}]", sides),
+            _ => format!("d{}", sides),
+        };
+        match bonus {
+            0 => dice,
+            bonus if bonus > 0 => format!("{}+{}", dice, bonus),
+            bonus => format!("{}{}", dice, bonus),
+        }
+    }
+    /// Get the tags of the monster.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags = vec![match self.organization {
+            Organization::Horde => "horde",
+            Organization::Group => "group",
+            Organization::Solitary => "solitary",
+        }];
+        match self.size {
+            Size::Tiny => tags.push("tiny"),
+            Size::Small => tags.push("small"),
+            Size::Large => tags.push("large"),
+            Size::Huge => tags.push("huge"),
+            Size::Human => {}
+        }
+        if self.defense == Defense::Magic || self.is_known_for(KnownFor::Magic) {
+            tags.push("magical");
+        }
+        if self.is_known_for(KnownFor::Deceit) {
+            tags.push("stealthy");
+        }
+        if self.is_known_for(KnownFor::Divine) {
+            tags.push("divine");
+        }
+        for description in &self.traits {
+            match *description {
+                Trait::Devious => tags.push("devious"),
+                Trait::Organized => tags.push("organized"),
+                Trait::Intelligent => tags.push("intelligent"),
+                Trait::Cautious => tags.push("cautious"),
+                Trait::Hoarder => tags.push("hoarder"),
+                Trait::Planar => tags.push("planar"),
+                Trait::Construct => tags.push("construct"),
+                Trait::Terrifying => tags.push("terrifying"),
+                Trait::Amorphous => tags.push("amorphous"),
+                Trait::Undying | Trait::Ancient | Trait::Pacifist => {}
+            }
+        }
+        tags.into_iter().map(String::from).collect()
+    }
+    /// Get the tags of the attack.
+    pub fn attack_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = match self.size {
+            Size::Tiny => vec!["hand"],
+            Size::Small | Size::Human => vec!["close"],
+            Size::Large => vec!["close", "reach"],
+            Size::Huge => vec!["reach"],
+        }
+        .into_iter()
+        .map(String::from)
+        .collect();
+        tags.extend(self.ranged.iter().cloned());
+        if self.is_known_for(KnownFor::Strength) {
+            tags.push(String::from("forceful"));
+        }
+        if self.is_known_for(KnownFor::DeftStrikes) {
+            tags.push(String::from("1 piercing"));
+        }
+        tags
+    }
+    /// Create the monster.
+    pub fn build(self) -> Monster {
+        Monster {
+            key: self.name.to_lowercase().replace(' ', "_"),
+            tags: self.tags(),
+            armor: self.armor(),
+            hp: self.hp(),
+            attacks: vec![Attack {
+                name: self.attack.clone(),
+                damage: self.damage(),
+                tags: self.attack_tags(),
+            }],
+            name: self.name,
+            instinct: self.instinct,
+            moves: self.moves,
+            qualities: self.qualities,
+            description: self.description,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let monster = MonsterBuilder::new("Ancient Lich")
+            .organization(Organization::Solitary)
+            .size(Size::Human)
+            .defense(Defense::Magic)
+            .known_for(KnownFor::Offense)
+            .known_for(KnownFor::Magic)
+            .describe(Trait::Intelligent)
+            .describe(Trait::Undying)
+            .describe(Trait::Ancient)
+            .attack("Necrotic touch")
+            .range("near")
+            .instinct("To outlast the living")
+            .monster_move("Raise the dead")
+            .quality("Undead")
+            .build();
+        assert_eq!(monster.key(), "ancient_lich");
+        assert_eq!(monster.hp(), 16);
+        assert_eq!(monster.armor(), 4);
+        assert_eq!(monster.tags(), ["solitary", "magical", "intelligent"]);
+        assert_eq!(monster.qualities(), ["Undead"]);
+        let attack = &monster.attacks()[0];
+        assert_eq!(attack.damage(), "b[2d12]");
+        assert_eq!(attack.tags(), ["close", "near"]);
+        // A tiny, devious horde
+        let builder = MonsterBuilder::new("Swarm")
+            .organization(Organization::Horde)
+            .size(Size::Tiny)
+            .describe(Trait::Devious)
+            .describe(Trait::Amorphous)
+            .describe(Trait::Pacifist);
+        assert_eq!(builder.damage(), "w[2d4]-2");
+        assert_eq!(builder.hp(), 1);
+        assert_eq!(builder.armor(), 1);
+        assert_eq!(builder.attack_tags(), ["hand"]);
+    }
+}
@@ -1,5 +1,9 @@
+mod builder;
+
+pub use self::builder::{Defense, KnownFor, MonsterBuilder, Organization, Size, Trait};
+
 use super::card::helper::*;
-use super::card::Card;
+use super::card::{Card, Theme};
 use colored::*;
 use regex::Regex;
 use rustyline;
@@ -7,14 +11,14 @@ use rustyline::completion::Completer;
 use serde_json;
 use std::fmt;
 use std::io;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::io::{Error, ErrorKind::InvalidData};
 use std::ops::{Deref, DerefMut};
 
 /// Wrapper around a `Vec<Monster>`.
 ///
 /// For implementing some functions and traits.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Monsters {
     data: Vec<Monster>,
 }
@@ -47,7 +51,7 @@ pub struct Monsters {
 /// ┃ • Act with perfect foresight.                              ┃
 /// ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Monster {
     /// Unique identifier for the monster.
     key: String,
@@ -70,6 +74,9 @@ pub struct Monster {
     /// List of moves which are common to this monster.
     #[serde(default)]
     moves: Vec<String>,
+    /// Special qualities like `Amphibious` or `Wings`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    qualities: Vec<String>,
     /// A description about this monster.
     #[serde(default)]
     description: String,
@@ -82,7 +89,7 @@ pub struct Monster {
 /// - name
 /// - damage
 /// - tags
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Attack {
     name: String,
     damage: String,
@@ -103,10 +110,23 @@ impl Monsters {
             .map(|data| Monsters { data })
             .map_err(|e| Error::new(InvalidData, e))
     }
+    /// Write the monsters as JSON to the given writer,
+    /// which can be read again with [parse](Monsters::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.data).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Add the given `monster`, replacing the one with the same key.
+    pub fn insert(&mut self, monster: Monster) {
+        match self.data.iter().position(|m| m.key == monster.key) {
+            Some(index) => self.data[index] = monster,
+            None => self.data.push(monster),
+        }
+    }
     /// Find a move that matches the given String `regex`.
     /// Matches the given fields in the given order:
     /// - `name`
     /// - `description`
+    ///
     /// The first move whose name matches is returned,
     /// otherwise the first whose description matches, ...
     pub fn find(&self, re: &str) -> Option<&Monster> {
@@ -122,28 +142,80 @@ impl Monsters {
         }
         best.1
     }
-    /// List all monsters whose name match the given `regex`.
-    pub fn list(&self, re: &str) {
-        println!(">> {}", "Monsters".bold());
+    /// Find the monster with the given `key`.
+    /// If none is found, [None](Option::None) is returned.
+    pub fn get(&self, key: &str) -> Option<&Monster> {
+        self.data.iter().find(|monster| monster.key == key)
+    }
+    /// Get all monsters whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&Monster> {
         let re = Regex::new(&format!("(?i){}", re)).unwrap();
         self.data
             .iter()
             .filter(|monster| re.is_match(&monster.name))
-            .map(|monster| println!("   {}", monster.name))
-            .collect::<Vec<()>>();
+            .collect()
+    }
+    /// List all monsters whose name match the given `regex`.
+    pub fn list(&self, re: &str) {
+        println!(">> {}", "Monsters".bold());
+        self.filter(re)
+            .iter()
+            .for_each(|monster| println!("   {}", monster.name));
     }
 }
 
-impl fmt::Display for Monster {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+impl Monster {
+    /// Get the unique identifier of the monster.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the name of the monster.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the tags of the monster.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    /// Get the amount of armor of the monster.
+    pub fn armor(&self) -> u8 {
+        self.armor
+    }
+    /// Get the amount of HP of the monster.
+    pub fn hp(&self) -> u8 {
+        self.hp
+    }
+    /// Get the basic instinct of the monster.
+    pub fn instinct(&self) -> &str {
+        &self.instinct
+    }
+    /// Get the moves common to the monster.
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+    /// Get the special qualities of the monster.
+    pub fn qualities(&self) -> &[String] {
+        &self.qualities
+    }
+    /// Get the description of the monster.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Get the attacks the monster can make.
+    pub fn attacks(&self) -> &[Attack] {
+        &self.attacks
+    }
+    /// Create the card showing this monster.
+    pub fn card(&self) -> Card {
         let width = 60;
+        let theme = Theme::current();
         // Name
-        let name = self.name.bold().yellow();
+        let name = theme.name.paint(&self.name);
         let name_hp_armor = format!(
             "{} {{}} {} {}",
             name,
-            format!(" {} HP ", self.hp).on_red(),
-            format!(" {} Armor ", self.armor).on_blue(),
+            theme.hp.paint(&format!(" {} HP ", self.hp)),
+            theme.armor.paint(&format!(" {} Armor ", self.armor)),
         );
         // Has description
         let has_description = self.description != String::new();
@@ -153,14 +225,17 @@ impl fmt::Display for Monster {
         let attack = self.attacks
             .first()
             .map(|a| format!("{}", a))
-            .unwrap_or(String::new());
+            .unwrap_or_default();
         // Tags
         let tags = self.tags.iter().map(|tag| capitalize(tag));
         let tags = format!(" {{}}{}", concat(tags, ", "));
         // Has Tags
-        let has_tags = tags != String::from(" {}");
+        let has_tags = tags != " {}";
+        // Special qualities
+        let qualities = format!("Special qualities: {}", self.qualities.join(", "));
+        let has_qualities = !self.qualities.is_empty();
         // Instinct
-        let instinct = " Instinct ".on_bright_white().black();
+        let instinct = theme.instinct.paint(" Instinct ");
         let instinct = format!("{} {}!", instinct, self.instinct);
         // Has instinct
         let has_instinct = self.instinct != String::new();
@@ -173,25 +248,42 @@ impl fmt::Display for Monster {
                 s
             })
             .collect();
-        // Create the card and print it
-        write!(
-            f,
-            "{}",
-            Card::new()
-                .with_heavy_border()
-                .with_width(width)
-                .line(&name_hp_armor)
-                .heavy_line()
-                .line_if(&attack, !self.attacks.is_empty())
-                .light_line_if(!self.attacks.is_empty())
-                .line_if(&tags, has_tags)
-                .light_line_if(has_tags)
-                .text_if(&self.description, has_description)
-                .light_line_if(has_description)
-                .line_if(&instinct, has_instinct)
-                .light_line_if(has_instinct)
-                .list(moves)
-        )
+        Card::new()
+            .with_width(width)
+            .line(&name_hp_armor)
+            .heavy_line()
+            .line_if(&attack, !self.attacks.is_empty())
+            .light_line_if(!self.attacks.is_empty())
+            .line_if(&tags, has_tags)
+            .light_line_if(has_tags)
+            .text_if(&qualities, has_qualities)
+            .light_line_if(has_qualities)
+            .text_if(&self.description, has_description)
+            .light_line_if(has_description)
+            .line_if(&instinct, has_instinct)
+            .light_line_if(has_instinct)
+            .list(moves)
+    }
+}
+
+impl fmt::Display for Monster {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Attack {
+    /// Get the name of the attack.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the damage of the attack as a d20 expression, i.e. `b[2d12]+9`.
+    pub fn damage(&self) -> &str {
+        &self.damage
+    }
+    /// Get the tags of the attack.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
     }
 }
 
@@ -219,7 +311,7 @@ impl fmt::Display for Attack {
 impl Completer for Monsters {
     fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
         if line.starts_with("monster ") && pos >= 8 {
-            let part_monster = line.trim_left_matches("monster ").to_lowercase();
+            let part_monster = line.trim_start_matches("monster ").to_lowercase();
             let mut ret = Vec::new();
             for monster in &self.data {
                 if monster.name.to_lowercase().starts_with(&part_monster) {
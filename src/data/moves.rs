@@ -14,7 +14,7 @@
 //! ```
 
 use super::card::helper::*;
-use super::card::Card;
+use super::card::{Card, Theme};
 use colored::*;
 use regex::Regex;
 use rustyline;
@@ -89,7 +89,7 @@ impl Moves {
             if mv.replaces != String::new() {
                 let repl = lookup
                     .get(&mv.replaces)
-                    .expect(&format!("Key {} does not exist!", mv.replaces))
+                    .unwrap_or_else(|| panic!("Key {} does not exist!", mv.replaces))
                     .name
                     .clone();
                 mv.replaces = repl;
@@ -97,7 +97,7 @@ impl Moves {
             if mv.requires != String::new() {
                 let repl = lookup
                     .get(&mv.requires)
-                    .expect(&format!("Key {} does not exist!", mv.requires))
+                    .unwrap_or_else(|| panic!("Key {} does not exist!", mv.requires))
                     .name
                     .clone();
                 mv.requires = repl;
@@ -111,6 +111,7 @@ impl Moves {
     /// - `name`
     /// - `description`
     /// - `classes`
+    ///
     /// The first move whose name matches is returned,
     /// otherwise the first whose description matches, ...
     pub fn find(&self, re: &str) -> Option<&Move> {
@@ -124,7 +125,7 @@ impl Moves {
                 best = (MatchType::Description, Some(mv));
             } else if best.0 < MatchType::Classes {
                 for class in &mv.classes {
-                    if re.is_match(&class) {
+                    if re.is_match(class) {
                         best = (MatchType::Classes, Some(mv));
                     }
                 }
@@ -137,15 +138,17 @@ impl Moves {
     pub fn get(&self, key: &str) -> Option<&Move> {
         self.data.iter().find(|mv| mv.key == key)
     }
+    /// Get all moves whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&Move> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter().filter(|mv| re.is_match(&mv.name)).collect()
+    }
     /// List all moves whose name match the given `regex`.
     pub fn list(&self, re: &str) {
         println!(">> {}", "Moves".bold());
-        let re = Regex::new(&format!("(?i){}", re)).unwrap();
-        self.data
+        self.filter(re)
             .iter()
-            .filter(|mv| re.is_match(&mv.name))
-            .map(|mv| println!("   {}", mv.name))
-            .collect::<Vec<()>>();
+            .for_each(|mv| println!("   {}", mv.name));
     }
 }
 
@@ -165,7 +168,7 @@ impl DerefMut for Moves {
 impl Completer for Moves {
     fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
         if line.starts_with("move ") && pos >= 5 {
-            let part_mv = line.trim_left_matches("move ").to_lowercase();
+            let part_mv = line.trim_start_matches("move ").to_lowercase();
             let mut ret = Vec::new();
             for mv in &self.data {
                 if mv.name.to_lowercase().starts_with(&part_mv) {
@@ -179,48 +182,73 @@ impl Completer for Moves {
     }
 }
 
-impl fmt::Display for Move {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+impl Move {
+    /// Get the name of the move.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the unique identifier of the move.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the description of the move.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Get the classes who might have this move.
+    pub fn classes(&self) -> &[String] {
+        &self.classes
+    }
+    /// Get the explanation of the move.
+    pub fn explanation(&self) -> &str {
+        &self.explanation
+    }
+    /// Get the name of the move this move replaces, empty if none.
+    pub fn replaces(&self) -> &str {
+        &self.replaces
+    }
+    /// Get the name of the move required for this move, empty if none.
+    pub fn requires(&self) -> &str {
+        &self.requires
+    }
+    /// Create the card showing this move.
+    pub fn card(&self) -> Card {
         let width = 40;
+        let theme = Theme::current();
         // Name
-        let name = format!("{}", self.name.bold().yellow());
+        let name = theme.name.paint(&self.name);
         // Classes
         let classes = self.classes
             .iter()
             .map(|s| capitalize(s))
-            .map(|s| format!(" {} ", s).on_bright_white().black())
-            .map(|s| format!("{}", s));
+            .map(|s| theme.class.paint(&format!(" {} ", s)));
         let classes = concat(classes, ", ");
         // Combine name and classes
         let name_classes = format!("{}{{}}{}", name, classes);
         // Requires tag
         let has_requires = self.requires != String::new();
-        let req = format!("{} {}", " Requires ".on_red().black(), self.requires);
+        let req = format!("{} {}", theme.requires.paint(" Requires "), self.requires);
         // Has replaces tag
         let has_replaces = self.replaces != String::new();
-        let rep = format!(
-            "{} {}",
-            " Replaces ".on_bright_white().black(),
-            self.replaces
-        );
+        let rep = format!("{} {}", theme.replaces.paint(" Replaces "), self.replaces);
         // Has an explanation
         let has_explanation = self.explanation != String::new();
-        // Create the card and write it
-        write!(
-            f,
-            "{}",
-            Card::new()
-                .with_width(width)
-                .with_heavy_border()
-                .line(&name_classes)
-                .heavy_line()
-                .line_if(&req, has_requires)
-                .line_if(&rep, has_replaces)
-                .light_line_if(has_requires || has_replaces)
-                .text(&self.description)
-                .light_line_if(has_explanation)
-                .text_if(&self.explanation, has_explanation)
-        )
+        Card::new()
+            .with_width(width)
+            .line(&name_classes)
+            .heavy_line()
+            .line_if(&req, has_requires)
+            .line_if(&rep, has_replaces)
+            .light_line_if(has_requires || has_replaces)
+            .text(&self.description)
+            .light_line_if(has_explanation)
+            .text_if(&self.explanation, has_explanation)
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
     }
 }
 
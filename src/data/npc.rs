@@ -0,0 +1,232 @@
+//! Non-player characters generated from the class data
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Willem                                          Human Bard ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ Knowing Eyes, Wild Hair, Finery, Thin Body                 ┃
+//! ┠────────────────────────────────────────────────────────────┨
+//! ┃ Good: Perform your art to aid someone else.                ┃
+//! ┃ Instinct: To hoard knowledge                               ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::card::helper::capitalize;
+use super::card::{Card, Theme};
+use super::{Class, Classes, Monsters};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::Regex;
+use std::fmt;
+
+/// A non-player character with a name, look, drive and instinct.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Npc {
+    /// Name of the NPC.
+    name: String,
+    /// Race of the NPC, i.e. `human`.
+    race: String,
+    /// Name of the class the NPC is drawn from.
+    class: String,
+    /// One look of each group of the class.
+    looks: Vec<String>,
+    /// Name of the alignment.
+    alignment: String,
+    /// What drives the NPC, the description of the alignment.
+    drive: String,
+    /// What the NPC wants, like the instinct of a monster.
+    instinct: String,
+}
+
+impl Npc {
+    /// Generate an NPC of the race named `query` or the first class matching it,
+    /// without a query of any class and race.
+    /// The instinct is drawn from the intelligent `monsters`.
+    /// Returns [None](Option::None) if neither a race nor a class matches.
+    /// Everything is drawn with the random number generator `rng`.
+    pub fn generate<R: Rng + ?Sized>(
+        classes: &Classes,
+        monsters: &Monsters,
+        query: Option<&str>,
+        rng: &mut R,
+    ) -> Option<Self> {
+        let has_race = |class: &&Class, race: &str| {
+            class
+                .names()
+                .keys()
+                .any(|name| name.eq_ignore_ascii_case(race))
+        };
+        let (class, race) = match query {
+            Some(query) if classes.iter().any(|class| has_race(&class, query)) => {
+                let candidates: Vec<&Class> = classes
+                    .iter()
+                    .filter(|class| has_race(class, query))
+                    .collect();
+                (*pick(rng, &candidates)?, Some(query.to_lowercase()))
+            }
+            Some(query) => (classes.find(query)?, None),
+            None => {
+                let candidates: Vec<&Class> = classes
+                    .iter()
+                    .filter(|class| !class.names().is_empty())
+                    .collect();
+                (*pick(rng, &candidates)?, None)
+            }
+        };
+        let race = match race {
+            Some(race) => race,
+            None => {
+                let races: Vec<&String> = class.names().keys().collect();
+                pick(rng, &races)
+                    .map(|race| race.to_string())
+                    .unwrap_or_else(|| String::from("human"))
+            }
+        };
+        let names = class
+            .names()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&race))
+            .map(|(_, names)| names.as_slice())
+            .unwrap_or_default();
+        let alignments: Vec<_> = class.alignments().values().collect();
+        let alignment = pick(rng, &alignments);
+        let re = Regex::new(r"\bintelligent\b").unwrap();
+        let instincts: Vec<&str> = monsters
+            .iter()
+            .filter(|monster| monster.tags().iter().any(|tag| re.is_match(tag)))
+            .map(|monster| monster.instinct())
+            .filter(|instinct| !instinct.is_empty())
+            .collect();
+        Some(Npc {
+            name: pick(rng, names)
+                .cloned()
+                .unwrap_or_else(|| String::from("Nameless")),
+            race,
+            class: class.name().to_string(),
+            looks: class
+                .looks()
+                .iter()
+                .filter_map(|looks| pick(rng, looks).cloned())
+                .collect(),
+            alignment: alignment
+                .map(|alignment| alignment.name().to_string())
+                .unwrap_or_default(),
+            drive: alignment
+                .map(|alignment| alignment.description().to_string())
+                .unwrap_or_default(),
+            instinct: pick(rng, &instincts)
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+        })
+    }
+    /// Get the name of the NPC.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the race of the NPC.
+    pub fn race(&self) -> &str {
+        &self.race
+    }
+    /// Get the name of the class the NPC is drawn from.
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+    /// Get the looks of the NPC.
+    pub fn looks(&self) -> &[String] {
+        &self.looks
+    }
+    /// Get the name of the alignment.
+    pub fn alignment(&self) -> &str {
+        &self.alignment
+    }
+    /// Get what drives the NPC.
+    pub fn drive(&self) -> &str {
+        &self.drive
+    }
+    /// Get what the NPC wants.
+    pub fn instinct(&self) -> &str {
+        &self.instinct
+    }
+    /// Get a note about the NPC for the journal, linking to its class.
+    pub fn note(&self) -> String {
+        format!(
+            "NPC {}, {} [[class:{}]]: {}. {}: {} Instinct: {}",
+            self.name,
+            self.race,
+            self.class.to_lowercase(),
+            self.looks.join(", "),
+            self.alignment,
+            self.drive,
+            self.instinct
+        )
+    }
+    /// Create the card showing the NPC.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let title = format!(
+            "{}{{}}{}",
+            theme.name.paint(&self.name),
+            theme
+                .class
+                .paint(&format!(" {} {} ", capitalize(&self.race), self.class))
+        );
+        Card::new()
+            .with_width(60)
+            .line(&title)
+            .heavy_line()
+            .text(&self.looks.join(", "))
+            .light_line()
+            .text(&format!("{}: {}", self.alignment, self.drive))
+            .text(&format!("Instinct: {}", self.instinct))
+    }
+}
+
+impl fmt::Display for Npc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+/// Pick a random element of `slice`.
+fn pick<'a, T, R: Rng + ?Sized>(rng: &mut R, slice: &'a [T]) -> Option<&'a T> {
+    slice.choose(rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::fs::File;
+
+    #[test]
+    fn npc() {
+        let classes = Classes::parse(File::open("data/classes.json").unwrap()).unwrap();
+        let monsters = Monsters::parse(File::open("data/monsters.json").unwrap()).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let bard = classes.find("bard").unwrap();
+        let npc = Npc::generate(&classes, &monsters, Some("bard"), &mut rng).unwrap();
+        assert_eq!(npc.class(), "Bard");
+        assert!(bard.names()[npc.race()].contains(&npc.name().to_string()));
+        assert_eq!(npc.looks().len(), bard.looks().len());
+        assert!(!npc.instinct().is_empty());
+        let npc = Npc::generate(&classes, &monsters, Some("Dwarf"), &mut rng).unwrap();
+        assert_eq!(npc.race(), "dwarf");
+        assert!(npc.class() == "Cleric" || npc.class() == "Fighter");
+        assert!(npc.note().contains("[[class:"));
+        let card = strip_colors(&npc.to_string());
+        assert!(card.contains(&format!("Dwarf {}", npc.class())));
+        assert!(Npc::generate(&classes, &monsters, None, &mut rng).is_some());
+        assert!(Npc::generate(&classes, &monsters, Some("xyzzy"), &mut rng).is_none());
+        // The same seed gives the same NPC
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let npc = Npc::generate(&classes, &monsters, None, &mut rng).unwrap();
+        assert_eq!(npc.name(), "Aranwe");
+        assert_eq!(npc.race(), "elf");
+        assert_eq!(npc.class(), "Ranger");
+        assert_eq!(npc.looks(), &["Sharp Eyes", "Bald", "Cape", "Lithe Body"]);
+        assert_eq!(npc.alignment(), "Neutral");
+        assert_eq!(npc.instinct(), "To collect");
+    }
+}
@@ -0,0 +1,127 @@
+//! Spells of the casting classes
+//!
+//! ```text
+//!  ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//!  ┃ Magic Missile                          Level 1  Evocation  ┃
+//!  ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//!  ┃ Projectiles of pure magic spring from your fingers. Deal   ┃
+//!  ┃ 2d4 damage to one target.                                  ┃
+//!  ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::card::helper::capitalize;
+use super::card::{Card, Theme};
+use serde::{Deserialize, Deserializer};
+use serde_json;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A spell a wizard or cleric can prepare and cast.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Spell {
+    /// Unique identifier of the spell.
+    key: String,
+    /// Name of the spell.
+    name: String,
+    /// What the spell does.
+    #[serde(default)]
+    description: String,
+    /// Level of the spell, cantrips and rotes have level 0.
+    #[serde(deserialize_with = "level")]
+    level: u8,
+    /// Tags of the spell, i.e. `ongoing`.
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl Spell {
+    /// Get the unique identifier of the spell.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the name of the spell.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get what the spell does.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Get the level of the spell, 0 for cantrips and rotes.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+    /// Get the tags of the spell.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    /// Whether the spell lasts and gives -1 to cast a spell while it does.
+    pub fn is_ongoing(&self) -> bool {
+        self.tags.iter().any(|tag| tag == "ongoing")
+    }
+    /// Create the card showing this spell.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let level = if self.level == 0 {
+            String::from(" Always prepared ")
+        } else {
+            format!(" Level {} ", self.level)
+        };
+        let tags: Vec<String> = self.tags.iter().map(|tag| capitalize(tag)).collect();
+        let tags = if tags.is_empty() {
+            String::new()
+        } else {
+            theme.requires.paint(&format!(" {} ", tags.join(", ")))
+        };
+        let title = format!(
+            "{}{{}}{} {}",
+            theme.name.paint(&self.name),
+            theme.class.paint(&level),
+            tags
+        );
+        Card::new()
+            .with_width(60)
+            .line(&title)
+            .heavy_line()
+            .text(&self.description)
+    }
+}
+
+impl fmt::Display for Spell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+/// Deserialize the level of a spell, `cantrip` and `rote` are level 0.
+fn level<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    use serde::de::Error;
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Level {
+        Number(u8),
+        Name(String),
+    }
+    match Level::deserialize(deserializer)? {
+        Level::Number(level) => Ok(level),
+        Level::Name(ref name) if name == "cantrip" || name == "rote" => Ok(0),
+        Level::Name(name) => Err(D::Error::custom(format!("unknown spell level {}", name))),
+    }
+}
+
+/// Deserialize the spells of a class by their key,
+/// skipping the `"key": "spells"` entry of the data.
+pub(super) fn spells<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Spell>, D::Error> {
+    use serde::de::Error;
+    let entries = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    entries
+        .into_iter()
+        .filter(|(key, _)| key != "key")
+        .map(|(key, value)| match serde_json::from_value(value) {
+            Ok(spell) => Ok((key, spell)),
+            Err(e) => Err(D::Error::custom(format!("spell {}: {}", key, e))),
+        })
+        .collect()
+}
@@ -0,0 +1,569 @@
+//! Random tables of the GM
+//!
+//! Every table is a file in a `tables` directory, in TOML or JSON,
+//! named by its key, i.e. `weather.toml`:
+//!
+//! ```toml
+//! name = "Weather"
+//! dice = "2d6"
+//!
+//! [[entries]]
+//! range = "2-6"
+//! text = "Clear skies"
+//!
+//! [[entries]]
+//! range = "7-12"
+//! text = "Rain, {{table:wind}}"
+//! ```
+//!
+//! Entries of tables without dice are drawn by their `weight`, 1 by default,
+//! and may be plain strings. `{{table:KEY}}` is replaced by a roll on another table.
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Weather                                                2d6 ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ • Rain, a howling gale                                     ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::card::helper::capitalize;
+use super::card::{Card, Theme};
+use colored::Colorize;
+use die;
+use rand::Rng;
+use regex::Regex;
+use rustyline;
+use rustyline::completion::Completer;
+use serde::{Deserialize, Deserializer};
+use serde_json;
+use std::fmt;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData, ErrorKind::InvalidInput};
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use toml;
+
+/// How deep tables may refer to other tables.
+const MAX_DEPTH: usize = 8;
+
+/// Wrapper around a `Vec<Table>`.
+///
+/// For implementing some functions and traits.
+#[derive(Debug, Clone, Default)]
+pub struct Tables {
+    data: Vec<Table>,
+}
+
+/// A random table.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Table {
+    /// Unique identifier of the table, the name of its file.
+    #[serde(default)]
+    key: String,
+    /// Name of the table, the key by default.
+    #[serde(default)]
+    name: String,
+    /// Dice rolled on the table, entries are drawn by weight without.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dice: Option<String>,
+    /// The entries of the table.
+    #[serde(deserialize_with = "entries")]
+    entries: Vec<Entry>,
+}
+
+/// An entry of a random table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// The text of the entry, may refer to other tables.
+    text: String,
+    /// How likely the entry is drawn from a table without dice.
+    #[serde(default = "one")]
+    weight: u32,
+    /// The lowest and highest total of the dice giving this entry.
+    #[serde(
+        default,
+        deserialize_with = "range",
+        skip_serializing_if = "Option::is_none"
+    )]
+    range: Option<(i32, i32)>,
+}
+
+/// The results of rolling on a table.
+#[derive(Debug, Clone)]
+pub struct TableRoll {
+    /// Name of the table.
+    table: String,
+    /// Dice rolled on the table.
+    dice: Option<String>,
+    /// The drawn entries, with the rolls on other tables.
+    results: Vec<String>,
+}
+
+impl Tables {
+    /// Create an empty set of tables.
+    pub fn new() -> Self {
+        Tables::default()
+    }
+    /// Load every `.toml` and `.json` file in `dir` as a table named by the file.
+    /// Without the directory there are no tables.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut tables = Tables::new();
+        if !dir.is_dir() {
+            return Ok(tables);
+        }
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<_>>()?;
+        paths.sort();
+        for path in paths {
+            let key = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(key) => key.to_string(),
+                None => continue,
+            };
+            let table = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("toml") => Table::parse_toml(&key, &fs::read_to_string(&path)?),
+                Some("json") => Table::parse_json(&key, File::open(&path)?),
+                _ => continue,
+            };
+            let table =
+                table.map_err(|e| Error::new(InvalidData, format!("{}: {}", path.display(), e)))?;
+            tables.insert(table);
+        }
+        Ok(tables)
+    }
+    /// Add the `table`, replacing the one with the same key.
+    pub fn insert(&mut self, table: Table) {
+        match self.data.iter().position(|t| t.key == table.key) {
+            Some(index) => self.data[index] = table,
+            None => self.data.push(table),
+        }
+    }
+    /// Find the table with the given `key`.
+    /// If none is found, [None](Option::None) is returned.
+    pub fn get(&self, key: &str) -> Option<&Table> {
+        self.data.iter().find(|table| table.key == key)
+    }
+    /// Find the table with the key `re` or the first whose name matches the given `regex`.
+    pub fn find(&self, re: &str) -> Option<&Table> {
+        self.get(re).or_else(|| {
+            let re = Regex::new(&format!("(?i){}", re)).ok()?;
+            self.data.iter().find(|table| re.is_match(&table.name))
+        })
+    }
+    /// Get all tables whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&Table> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data
+            .iter()
+            .filter(|table| re.is_match(&table.name))
+            .collect()
+    }
+    /// List all tables whose name match the given `regex`.
+    pub fn list(&self, re: &str) {
+        println!(">> {}", "Tables".bold());
+        self.filter(re)
+            .iter()
+            .for_each(|table| println!("   {}", table.name));
+    }
+    /// Replace every `{{table:KEY}}` in `text` by a roll on the table KEY,
+    /// `depth` counts the tables rolled on so far.
+    fn expand<R: Rng + ?Sized>(&self, text: &str, depth: usize, rng: &mut R) -> io::Result<String> {
+        let re = Regex::new(r"\{\{\s*table:([^}]+)\}\}").unwrap();
+        let mut expanded = String::new();
+        let mut last = 0;
+        for caps in re.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            let key = caps[1].trim();
+            let table = self
+                .get(key)
+                .ok_or_else(|| Error::new(InvalidData, format!("Unknown table {}", key)))?;
+            if depth >= MAX_DEPTH {
+                return Err(Error::new(
+                    InvalidData,
+                    format!("The table {} refers to tables too deep", key),
+                ));
+            }
+            let mut weights = table.weights();
+            let entry = table.draw(&mut weights, rng).unwrap_or_default();
+            expanded.push_str(&text[last..whole.start()]);
+            expanded.push_str(&self.expand(&entry, depth + 1, rng)?);
+            last = whole.end();
+        }
+        expanded.push_str(&text[last..]);
+        Ok(expanded)
+    }
+}
+
+impl Table {
+    /// Parse the table with the given `key` in TOML.
+    pub fn parse_toml(key: &str, content: &str) -> io::Result<Self> {
+        let table = toml::from_str(content).map_err(|e| Error::new(InvalidData, e))?;
+        Table::checked(key, table)
+    }
+    /// Parse the table with the given `key` in JSON given through the reader.
+    pub fn parse_json<R: io::Read>(key: &str, reader: R) -> io::Result<Self> {
+        let table = serde_json::from_reader(reader).map_err(|e| Error::new(InvalidData, e))?;
+        Table::checked(key, table)
+    }
+    /// Get the unique identifier of the table.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the name of the table.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the dice rolled on the table.
+    pub fn dice(&self) -> Option<&str> {
+        self.dice.as_deref()
+    }
+    /// Get the entries of the table.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+    /// Roll `count` times on the table, `unique` never draws an entry twice.
+    /// References to other tables are rolled on the `tables`.
+    /// Entries are drawn with the random number generator `rng`.
+    pub fn roll<R: Rng + ?Sized>(
+        &self,
+        tables: &Tables,
+        count: usize,
+        unique: bool,
+        rng: &mut R,
+    ) -> io::Result<TableRoll> {
+        let mut weights = self.weights();
+        let possible = weights.iter().filter(|weight| **weight > 0.0).count();
+        if unique && count > possible {
+            return Err(Error::new(
+                InvalidInput,
+                format!("The table {} has only {} entries", self.name, possible),
+            ));
+        }
+        let mut results = Vec::new();
+        for _ in 0..count {
+            let entry = if unique {
+                self.draw(&mut weights, rng)
+            } else {
+                self.draw(&mut weights.clone(), rng)
+            };
+            results.push(tables.expand(&entry.unwrap_or_default(), 1, rng)?);
+        }
+        Ok(TableRoll {
+            table: self.name.clone(),
+            dice: self.dice.clone(),
+            results,
+        })
+    }
+    /// Create the card showing the entries of the table.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let dice = self.dice.as_deref().unwrap_or("Weighted");
+        let title = format!(
+            "{}{{}}{}",
+            theme.name.paint(&self.name),
+            theme.class.paint(&format!(" {} ", dice))
+        );
+        self.entries.iter().fold(
+            Card::new().with_width(60).line(&title).heavy_line(),
+            |card, entry| {
+                let chance = match entry.range {
+                    Some((low, high)) if low == high => low.to_string(),
+                    Some((low, high)) => format!("{}-{}", low, high),
+                    None => format!("×{}", entry.weight),
+                };
+                card.text(&format!("{:<5} {}", chance, entry.text))
+            },
+        )
+    }
+    /// Set the key and default name of the `table` and check its entries.
+    ///
+    /// Tables need entries, with dice every possible total needs one,
+    /// without dice the weights may not all be zero.
+    fn checked(key: &str, mut table: Table) -> io::Result<Self> {
+        table.key = key.to_string();
+        if table.name.is_empty() {
+            table.name = capitalize(&key.replace('_', " "));
+        }
+        let invalid = |msg: String| Err(Error::new(InvalidData, msg));
+        if table.entries.is_empty() {
+            return invalid(format!("The table {} has no entries", table.name));
+        }
+        match table.dice {
+            Some(ref dice) => {
+                let dist = die::distribution(dice).map_err(|e| Error::new(InvalidData, e))?;
+                for total in dist.probabilities().keys() {
+                    let covered = table.entries.iter().any(|entry| {
+                        entry
+                            .range
+                            .is_some_and(|(low, high)| low <= *total && *total <= high)
+                    });
+                    if !covered {
+                        return invalid(format!(
+                            "The table {} has no entry for a total of {}",
+                            table.name, total
+                        ));
+                    }
+                }
+            }
+            None if table.entries.iter().any(|entry| entry.range.is_some()) => {
+                return invalid(format!("The table {} has ranges but no dice", table.name));
+            }
+            None if table.entries.iter().all(|entry| entry.weight == 0) => {
+                return invalid(format!("The weights of the table {} are all 0", table.name));
+            }
+            None => {}
+        }
+        Ok(table)
+    }
+    /// Get how likely each entry is drawn, by its weight or the odds of its range.
+    fn weights(&self) -> Vec<f64> {
+        match self
+            .dice
+            .as_deref()
+            .and_then(|dice| die::distribution(dice).ok())
+        {
+            Some(dist) => self
+                .entries
+                .iter()
+                .map(|entry| match entry.range {
+                    Some((low, high)) if low <= high => {
+                        dist.probabilities().range(low..=high).map(|(_, p)| p).sum()
+                    }
+                    _ => 0.0,
+                })
+                .collect(),
+            None => self
+                .entries
+                .iter()
+                .map(|entry| f64::from(entry.weight))
+                .collect(),
+        }
+    }
+    /// Draw the text of a random entry by the `weights`,
+    /// the weight of the drawn entry is set to zero.
+    fn draw<R: Rng + ?Sized>(&self, weights: &mut [f64], rng: &mut R) -> Option<String> {
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut left = rng.gen_range(0.0..total);
+        let index = weights
+            .iter()
+            .position(|weight| {
+                left -= weight;
+                *weight > 0.0 && left < 0.0
+            })
+            .or_else(|| weights.iter().rposition(|weight| *weight > 0.0))?;
+        weights[index] = 0.0;
+        Some(self.entries[index].text.clone())
+    }
+}
+
+impl Entry {
+    /// Get the text of the entry.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Get how likely the entry is drawn from a table without dice.
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+    /// Get the lowest and highest total of the dice giving this entry.
+    pub fn range(&self) -> Option<(i32, i32)> {
+        self.range
+    }
+}
+
+impl TableRoll {
+    /// Get the name of the table.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+    /// Get the drawn entries.
+    pub fn results(&self) -> &[String] {
+        &self.results
+    }
+    /// Create the card showing the results.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let dice = self
+            .dice
+            .as_ref()
+            .map(|dice| theme.class.paint(&format!(" {} ", dice)))
+            .unwrap_or_default();
+        let title = format!("{}{{}}{}", theme.name.paint(&self.table), dice);
+        Card::new()
+            .with_width(60)
+            .line(&title)
+            .heavy_line()
+            .list(self.results.clone())
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl fmt::Display for TableRoll {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+impl Deref for Tables {
+    type Target = Vec<Table>;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for Tables {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl Completer for Tables {
+    fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = match line.rfind(' ') {
+            Some(space) if line.starts_with("table ") => space + 1,
+            _ => return Ok((pos, vec![])),
+        };
+        let part = &line[start..];
+        let keys = self
+            .data
+            .iter()
+            .filter(|table| table.key.starts_with(part))
+            .map(|table| table.key.clone())
+            .collect();
+        Ok((start, keys))
+    }
+}
+
+/// Deserialize the entries of a table, plain strings are entries of weight 1.
+fn entries<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Entry>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Item {
+        Text(String),
+        Entry(Entry),
+    }
+    Ok(Vec::<Item>::deserialize(deserializer)?
+        .into_iter()
+        .map(|item| match item {
+            Item::Text(text) => Entry {
+                text,
+                weight: 1,
+                range: None,
+            },
+            Item::Entry(entry) => entry,
+        })
+        .collect())
+}
+
+/// Deserialize the range of an entry, a total like `7` or a range like `"7-9"`.
+fn range<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<(i32, i32)>, D::Error> {
+    use serde::de::Error;
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Range {
+        Number(i32),
+        Text(String),
+    }
+    let parse = |s: &str| s.trim().parse::<i32>().map_err(D::Error::custom);
+    match Range::deserialize(deserializer)? {
+        Range::Number(total) => Ok(Some((total, total))),
+        Range::Text(ref text) => match text.find('-') {
+            Some(dash) if dash > 0 => Ok(Some((parse(&text[..dash])?, parse(&text[dash + 1..])?))),
+            _ => parse(text).map(|total| Some((total, total))),
+        },
+    }
+}
+
+/// Simple helper function for Serde to return `1`.
+fn one() -> u32 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn tables() {
+        let weather = Table::parse_toml(
+            "weather",
+            "dice = \"2d6\"\n\
+             [[entries]]\nrange = \"2-6\"\ntext = \"Clear skies\"\n\
+             [[entries]]\nrange = 7\ntext = \"Fog\"\n\
+             [[entries]]\nrange = \"8-12\"\ntext = \"Rain, {{table:wind}}\"\n",
+        )
+        .unwrap();
+        assert_eq!(weather.name(), "Weather");
+        assert_eq!(weather.entries()[1].range(), Some((7, 7)));
+        let wind = Table::parse_json(
+            "wind",
+            r#"{"entries": ["a breeze", {"text": "a gale", "weight": 3}, {"text": "calm", "weight": 0}]}"#
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(wind.entries()[1].weight(), 3);
+        let mut tables = Tables::new();
+        tables.insert(weather);
+        tables.insert(wind);
+        assert_eq!(tables.find("wea").unwrap().key(), "weather");
+        // Rolling on other tables
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let roll = tables
+            .get("weather")
+            .unwrap()
+            .roll(&tables, 20, false, &mut rng)
+            .unwrap();
+        assert_eq!(roll.results().len(), 20);
+        assert!(roll.results().iter().all(|r| !r.contains("{{")));
+        assert!(roll
+            .results()
+            .iter()
+            .filter(|r| r.starts_with("Rain"))
+            .all(|rain| rain.ends_with("breeze") || rain.ends_with("gale")));
+        // Never twice the same with unique
+        let wind = tables.get("wind").unwrap();
+        let mut results = wind
+            .roll(&tables, 2, true, &mut rng)
+            .unwrap()
+            .results()
+            .to_vec();
+        results.sort();
+        assert_eq!(results, ["a breeze", "a gale"]);
+        assert!(wind.roll(&tables, 3, true, &mut rng).is_err());
+        // The same seed gives the same results
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let roll = tables
+            .get("weather")
+            .unwrap()
+            .roll(&tables, 4, false, &mut rng)
+            .unwrap();
+        assert_eq!(
+            roll.results(),
+            &["Rain, a gale", "Fog", "Rain, a gale", "Clear skies"]
+        );
+        let card = strip_colors(&tables.get("weather").unwrap().to_string());
+        assert!(card.contains("8-12  Rain"));
+        // Broken tables
+        assert!(Table::parse_toml("t", "dice = \"d6\"\nentries = [\"a\"]").is_err());
+        assert!(Table::parse_toml("t", "entries = []").is_err());
+        assert!(Table::parse_toml("t", "[[entries]]\nrange = 1\ntext = \"a\"").is_err());
+        let looped = Table::parse_toml("loop", "entries = [\"{{table:loop}}\"]").unwrap();
+        tables.insert(looped);
+        let looped = tables.get("loop").unwrap();
+        assert!(looped.roll(&tables, 1, false, &mut rng).is_err());
+    }
+}
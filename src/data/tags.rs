@@ -1,5 +1,5 @@
 use super::card::helper::capitalize;
-use super::card::Card;
+use super::card::{Card, Theme};
 use colored::Colorize;
 use regex::Regex;
 use rustyline;
@@ -29,7 +29,7 @@ pub struct Tags {
 ///  ┃ to hack and slash.”                    ┃
 ///  ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Tag {
     /// Name of the tag.
     name: String,
@@ -57,6 +57,7 @@ impl Tags {
     /// Matches the given fields in the given order:
     /// - `name`
     /// - `description`
+    ///
     /// The first tag whose name matches is returned,
     /// otherwise the first whose description matches.
     pub fn find(&self, re: &str) -> Option<&Tag> {
@@ -72,32 +73,53 @@ impl Tags {
         }
         best.1
     }
+    /// Find the tag with the given `key`.
+    /// If none is found, [None](Option::None) is returned.
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        self.data.iter().find(|tag| tag.key == key)
+    }
+    /// Get all tags whose name match the given `regex`.
+    pub fn filter(&self, re: &str) -> Vec<&Tag> {
+        let re = Regex::new(&format!("(?i){}", re)).unwrap();
+        self.data.iter().filter(|tag| re.is_match(&tag.name)).collect()
+    }
     /// List all tags whose name match the given `regex`.
     pub fn list(&self, re: &str) {
         println!(">> {}", "Tags".bold());
-        let re = Regex::new(&format!("(?i){}", re)).unwrap();
-        self.data
+        self.filter(re)
             .iter()
-            .filter(|tag| re.is_match(&tag.name))
-            .map(|tag| println!("   {}", tag.name))
-            .collect::<Vec<()>>();
+            .for_each(|tag| println!("   {}", tag.name));
+    }
+}
+
+impl Tag {
+    /// Get the name of the tag.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the unique identifier of the tag.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Get the description of the tag.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Create the card showing this tag.
+    pub fn card(&self) -> Card {
+        let width = 40;
+        let name = Theme::current().name.paint(&capitalize(&self.name));
+        Card::new()
+            .with_width(width)
+            .line(&name)
+            .heavy_line()
+            .text(&self.description)
     }
 }
 
 impl fmt::Display for Tag {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        let width = 40;
-        let name = capitalize(&self.name);
-        let name = format!("{}", name.bold().yellow());
-        write!(
-            f,
-            "{}",
-            Card::new()
-                .with_width(width)
-                .line(&name)
-                .heavy_line()
-                .text(&self.description)
-        )
+        write!(f, "{}", self.card())
     }
 }
 
@@ -117,7 +139,7 @@ impl DerefMut for Tags {
 impl Completer for Tags {
     fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
         if line.starts_with("tag ") && pos >= 4 {
-            let part_tag = line.trim_left_matches("tag ").to_lowercase();
+            let part_tag = line.trim_start_matches("tag ").to_lowercase();
             let mut ret = Vec::new();
             for tag in &self.data {
                 if tag.name.to_lowercase().starts_with(&part_tag) {
@@ -0,0 +1,434 @@
+//! Treasure of defeated monsters
+//!
+//! The treasure of a monster is found by rolling its damage die,
+//! with bonuses depending on the monster, on the treasure table:
+//!
+//! ```text
+//! ┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓
+//! ┃ Treasure of the Goblin Orc-Bat                       1d8 7 ┃
+//! ┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫
+//! ┃ Coins                                                  300 ┃
+//! ┃ • A bag of coins, 1 weight per 100                         ┃
+//! ┃ • Dungeon rations                                          ┃
+//! ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
+//! ```
+
+use super::card::helper::capitalize;
+use super::card::{Card, Theme};
+use super::{Item, Items, Monster};
+use die::{Expr, Roll, Term};
+use rand::Rng;
+use regex::Regex;
+use std::fmt;
+
+/// How often the treasure table is rolled again at most, for `roll again` results.
+const MAX_ROLLS: usize = 4;
+
+/// What makes the treasure of a monster richer.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Hoard {
+    /// Roll the damage die twice and take the higher result.
+    pub hoarder: bool,
+    /// Far from civilization, the treasure holds a ration.
+    pub far: bool,
+    /// Some strange item, possibly magical.
+    pub magical: bool,
+    /// A sign of a deity.
+    pub divine: bool,
+    /// Something not of this earth.
+    pub planar: bool,
+    /// Lord over others, +1d4 to the roll.
+    pub lord: bool,
+    /// Ancient and noteworthy, +1d4 to the roll.
+    pub ancient: bool,
+}
+
+/// The rolled treasure of a monster.
+#[derive(Debug, Clone)]
+pub struct Treasure {
+    /// Name of the monster.
+    monster: String,
+    /// The rolls on the treasure table.
+    rolls: Vec<Roll>,
+    /// The coins found.
+    coins: u32,
+    /// Everything else found, i.e. items and valuables.
+    finds: Vec<String>,
+    /// Keys of the found items.
+    items: Vec<String>,
+}
+
+impl Hoard {
+    /// Get the bonuses given by the tags of the `monster`,
+    /// like `hoarder`, `magical`, `divine` and `planar`.
+    pub fn of(monster: &Monster) -> Self {
+        let tagged = |name: &str| monster.tags().iter().any(|tag| tag == name);
+        Hoard {
+            hoarder: tagged("hoarder"),
+            magical: tagged("magical"),
+            divine: tagged("divine"),
+            planar: tagged("planar"),
+            ..Hoard::default()
+        }
+    }
+    /// Set whether the monster lives far from civilization.
+    pub fn with_far(mut self, far: bool) -> Self {
+        self.far = far;
+        self
+    }
+    /// Set whether the monster is lord over others.
+    pub fn with_lord(mut self, lord: bool) -> Self {
+        self.lord = lord;
+        self
+    }
+    /// Set whether the monster is ancient and noteworthy.
+    pub fn with_ancient(mut self, ancient: bool) -> Self {
+        self.ancient = ancient;
+        self
+    }
+    /// Get the expression rolled on the treasure table for the `damage` of a monster,
+    /// i.e. `1d8+1d4` for a lord dealing `d8+2`.
+    /// Monsters without a damage die use a `d6`.
+    /// A hoarder rolls the damage die twice when it is [rolled](Hoard::roll).
+    pub fn expr(&self, damage: &str) -> String {
+        let re = Regex::new(r"^\s*([bw]\[[^\]]*\]|\d*d\d+)").unwrap();
+        let term = re
+            .captures(damage)
+            .and_then(|caps| caps[1].parse::<Expr>().ok())
+            .and_then(|expr| expr.terms().first().map(|(_, term)| *term))
+            .unwrap_or(Term::Sum { count: 1, sides: 6 });
+        let mut expr = term.to_string();
+        if self.lord {
+            expr.push_str("+1d4");
+        }
+        if self.ancient {
+            expr.push_str("+1d4");
+        }
+        expr
+    }
+    /// Roll the `expr` of the treasure table with the random number generator `rng`.
+    /// A hoarder rolls the damage die, the first term, twice and takes the higher result.
+    pub fn roll<R: Rng + ?Sized>(&self, expr: &Expr, rng: &mut R) -> Result<Roll, String> {
+        let mut total: i32 = 0;
+        let mut dice = Vec::new();
+        for (i, (sign, term)) in expr.terms().iter().enumerate() {
+            let term: Expr = term.to_string().parse()?;
+            let mut roll = term.roll(rng)?;
+            if i == 0 && self.hoarder {
+                let again = term.roll(rng)?;
+                if again.total() > roll.total() {
+                    roll = again;
+                }
+            }
+            total = sign
+                .checked_mul(roll.total())
+                .and_then(|value| total.checked_add(value))
+                .ok_or_else(|| String::from("Result out of range"))?;
+            dice.extend(roll.dice().iter().cloned());
+        }
+        Ok(Roll::new(expr, dice, total))
+    }
+}
+
+impl Treasure {
+    /// Roll the treasure of the `monster` with the bonuses of the `hoard`,
+    /// the found items are drawn from `items`.
+    /// Everything is rolled with the random number generator `rng`.
+    pub fn roll<R: Rng + ?Sized>(
+        monster: &Monster,
+        hoard: Hoard,
+        items: &Items,
+        rng: &mut R,
+    ) -> Self {
+        let damage = monster
+            .attacks()
+            .first()
+            .map(|attack| attack.damage())
+            .unwrap_or_default();
+        let mut treasure = Treasure {
+            monster: monster.name().to_string(),
+            rolls: Vec::new(),
+            coins: 0,
+            finds: Vec::new(),
+            items: Vec::new(),
+        };
+        if let Ok(expr) = hoard.expr(damage).parse::<Expr>() {
+            while treasure.rolls.len() < MAX_ROLLS {
+                let roll = match hoard.roll(&expr, rng) {
+                    Ok(roll) => roll,
+                    Err(_) => break,
+                };
+                let total = roll.total();
+                treasure.rolls.push(roll);
+                if !treasure.resolve(total, items, rng) {
+                    break;
+                }
+            }
+        }
+        if hoard.far {
+            let ration = items.get("dungeon_rations").map(|item| item.name());
+            treasure.find(&capitalize(ration.unwrap_or("dungeon rations")));
+        }
+        if hoard.magical {
+            treasure.find_item(rng, items, true, "Some strange item, possibly magical");
+        }
+        if hoard.divine {
+            treasure.find("A sign of a deity");
+        }
+        if hoard.planar {
+            treasure.find("Something not of this earth");
+        }
+        treasure
+    }
+    /// Get the name of the monster.
+    pub fn monster(&self) -> &str {
+        &self.monster
+    }
+    /// Get the rolls on the treasure table.
+    pub fn rolls(&self) -> &[Roll] {
+        &self.rolls
+    }
+    /// Get the rolls on the treasure table labeled with the monster, to log them.
+    pub fn labeled_rolls(&self) -> Vec<Roll> {
+        let label = format!("Treasure: {}", self.monster);
+        self.rolls
+            .iter()
+            .map(|roll| roll.clone().with_label(Some(&label)))
+            .collect()
+    }
+    /// Get the coins found.
+    pub fn coins(&self) -> u32 {
+        self.coins
+    }
+    /// Get everything else found.
+    pub fn finds(&self) -> &[String] {
+        &self.finds
+    }
+    /// Get the keys of the found items.
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+    /// Create the card showing the treasure.
+    pub fn card(&self) -> Card {
+        let theme = Theme::current();
+        let totals: Vec<String> = self
+            .rolls
+            .iter()
+            .map(|roll| roll.total().to_string())
+            .collect();
+        let expr = self
+            .rolls
+            .first()
+            .map(|roll| roll.expr())
+            .unwrap_or_default();
+        let title = format!(
+            "{}{{}}{} {}",
+            theme
+                .name
+                .paint(&format!("Treasure of the {}", self.monster)),
+            expr,
+            totals.join(", ")
+        );
+        Card::new()
+            .with_width(60)
+            .line(&title)
+            .heavy_line()
+            .line(&format!("Coins{{}}{}", self.coins))
+            .list_if(self.finds.clone(), !self.finds.is_empty())
+    }
+    /// Add the result of the treasure table for `total`,
+    /// returns whether to roll again.
+    fn resolve<R: Rng + ?Sized>(&mut self, total: i32, items: &Items, rng: &mut R) -> bool {
+        match total {
+            i32::MIN..=1 => self.coins += roll(rng, "2d8"),
+            2 => self.find_item(rng, items, false, "An item useful to the current situation"),
+            3 => self.coins += roll(rng, "4d10"),
+            4 => self.valuable("A small gem or work of art", roll(rng, "2d10") * 10),
+            5 => self.find_item(rng, items, true, "A minor magical trinket"),
+            6 => self.find("Useful information, like clues or notes"),
+            7 => {
+                self.coins += roll(rng, "1d4") * 100;
+                self.find("A bag of coins, 1 weight per 100");
+            }
+            8 => self.valuable(
+                "A very valuable small gem or work of art",
+                roll(rng, "2d6") * 100,
+            ),
+            9 => {
+                self.coins += roll(rng, "3d6") * 100;
+                self.find("A chest of coins and other small valuables, 1 weight");
+            }
+            10 => self.find_item(rng, items, true, "A magical item"),
+            11 => {
+                self.coins += roll(rng, "2d4") * 100;
+                self.find("Many bags of coins");
+            }
+            12 => self.valuable(
+                "A sign of office, like a crown or banner",
+                roll(rng, "3d4") * 100,
+            ),
+            13 => self.valuable("A large work of art, 1 weight", roll(rng, "4d4") * 100),
+            14 => self.valuable("A unique item", roll(rng, "5d4") * 100),
+            15 => {
+                self.find("All the information needed to learn a new spell");
+                return true;
+            }
+            16 => {
+                self.find("A portal or secret path, or directions to one");
+                return true;
+            }
+            17 => {
+                self.find("Something relating to one of the characters");
+                return true;
+            }
+            _ => {
+                self.coins += roll(rng, "1d10") * 1000;
+                let gems = roll(rng, "1d10") * 10;
+                let worth: u32 = (0..gems).map(|_| roll(rng, "2d6") * 100).sum();
+                self.find(&format!("A hoard of {} gems worth {} coins", gems, worth));
+            }
+        }
+        false
+    }
+    /// Add something found.
+    fn find(&mut self, find: &str) {
+        self.finds.push(find.to_string());
+    }
+    /// Add the `find` of a random item of `items`, a `magical` one has no price.
+    fn find_item<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        items: &Items,
+        magical: bool,
+        find: &str,
+    ) {
+        match random_item(rng, items, magical) {
+            Some(item) => {
+                self.find(&format!("{}: {}", find, item.name()));
+                self.items.push(item.key().to_string());
+            }
+            None => self.find(&format!("{}: of the GM's choice", find)),
+        }
+    }
+    /// Add a valuable `worth` coins.
+    fn valuable(&mut self, name: &str, worth: u32) {
+        self.find(&format!("{} worth {} coins", name, worth));
+    }
+}
+
+impl fmt::Display for Treasure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.card())
+    }
+}
+
+/// Roll the dice expression `s`, which is known to be valid.
+fn roll<R: Rng + ?Sized>(rng: &mut R, s: &str) -> u32 {
+    s.parse::<Expr>()
+        .and_then(|expr| expr.roll(rng))
+        .map_or(0, |roll| roll.total().max(0) as u32)
+}
+
+/// Get a random item, a `magical` one has no price.
+fn random_item<'a, R: Rng + ?Sized>(
+    rng: &mut R,
+    items: &'a Items,
+    magical: bool,
+) -> Option<&'a Item> {
+    let items: Vec<&Item> = items
+        .iter()
+        .filter(|item| item.coins().is_none() == magical)
+        .collect();
+    if items.is_empty() {
+        return None;
+    }
+    let i = rng.gen_range(0..items.len());
+    Some(items[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+    use data::Monsters;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::fs::File;
+
+    #[test]
+    fn expr() {
+        let hoard = Hoard::default();
+        assert_eq!(hoard.expr("d10+3 ignores armor"), "1d10");
+        assert_eq!(hoard.expr("w[2d8]damage"), "w[2d8]");
+        assert_eq!(hoard.expr(""), "1d6");
+        let hoard = Hoard {
+            hoarder: true,
+            ..hoard
+        }
+        .with_lord(true);
+        assert_eq!(hoard.expr("d8+2"), "1d8+1d4");
+        assert_eq!(hoard.expr("b[2d12]+9"), "b[2d12]+1d4");
+    }
+
+    #[test]
+    fn hoarder() {
+        // The same dice are rolled first, the hoarder rolls the damage die again
+        let expr: Expr = "2d4".parse().unwrap();
+        let hoarder = Hoard {
+            hoarder: true,
+            ..Hoard::default()
+        };
+        let mut higher = 0;
+        for seed in 0..20 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let roll = Hoard::default().roll(&expr, &mut rng).unwrap();
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let best = hoarder.roll(&expr, &mut rng).unwrap();
+            assert!(best.total() >= roll.total());
+            assert_eq!(best.expr(), "2d4");
+            assert_eq!(best.dice()[0].len(), 2);
+            if best.total() > roll.total() {
+                higher += 1;
+            }
+        }
+        assert!(higher > 0);
+        let expr: Expr = "1d8+1d4".parse().unwrap();
+        let roll = hoarder
+            .roll(&expr, &mut ChaCha8Rng::seed_from_u64(1))
+            .unwrap();
+        let faces: u32 = roll.dice().iter().flatten().sum();
+        assert_eq!(roll.total(), faces as i32);
+    }
+
+    #[test]
+    fn treasure() {
+        let monsters = Monsters::parse(File::open("data/monsters.json").unwrap()).unwrap();
+        let items = Items::parse(File::open("data/items.json").unwrap()).unwrap();
+        let dragon = monsters.find("apocalypse dragon").unwrap();
+        let hoard = Hoard::of(dragon).with_far(true);
+        assert!(hoard.far);
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..20 {
+            let treasure = Treasure::roll(dragon, hoard, &items, &mut rng);
+            assert!(!treasure.rolls().is_empty() && treasure.rolls().len() <= MAX_ROLLS);
+            assert!(treasure.finds().contains(&String::from("Dungeon rations")));
+            let card = strip_colors(&treasure.to_string());
+            assert!(card.contains("Treasure of the Apocalypse Dragon"));
+            let rolls = treasure.labeled_rolls();
+            assert_eq!(rolls.len(), treasure.rolls().len());
+            assert_eq!(rolls[0].label(), Some("Treasure: Apocalypse Dragon"));
+        }
+        // The same seed gives the same treasure
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let goblin = monsters.find("goblin").unwrap();
+        let treasure = Treasure::roll(goblin, Hoard::of(goblin), &items, &mut rng);
+        assert_eq!(treasure.rolls().len(), 1);
+        assert_eq!(treasure.rolls()[0].dice(), &[vec![2]]);
+        assert_eq!(treasure.coins(), 0);
+        assert_eq!(
+            treasure.finds(),
+            &["An item useful to the current situation: Bloodweed"]
+        );
+        assert_eq!(treasure.items(), &["bloodweed"]);
+    }
+}
@@ -0,0 +1,274 @@
+//! Parsed dice expressions.
+
+use super::log::Roll;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Most dice allowed in a single term.
+const MAX_COUNT: u32 = 100;
+/// Most sides allowed on a single die.
+const MAX_SIDES: u32 = 1000;
+/// Largest constant allowed in a term.
+const MAX_CONSTANT: u32 = 1_000_000;
+
+/// A dice expression like `b[2d8]+2`.
+///
+/// ```text
+///         <expr> ::= [ "-" ] <term> | <expr> <add_del> <term>
+///         <term> ::= <dice> | <constant> | "b[" <dice> "]" | "w[" <dice> "]"
+///         <dice> ::= [ <constant> ] "d" <constant>
+///     <constant> ::= "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" | <constant>
+///      <add_del> ::= "+" | "-"
+/// ```
+///
+/// `b[2d8]` keeps the best and `w[2d8]` the worst of the rolled dice.
+/// Whitespace is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr {
+    /// The terms with their sign, `1` or `-1`.
+    terms: Vec<(i32, Term)>,
+}
+
+/// A single term of an [Expr](Expr).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Term {
+    /// A constant number.
+    Constant(i32),
+    /// The sum of `count` dice with `sides` sides each.
+    Sum { count: u32, sides: u32 },
+    /// The highest of `count` dice with `sides` sides each.
+    Best { count: u32, sides: u32 },
+    /// The lowest of `count` dice with `sides` sides each.
+    Worst { count: u32, sides: u32 },
+}
+
+impl Expr {
+    /// Get the terms with their sign, `1` or `-1`.
+    pub fn terms(&self) -> &[(i32, Term)] {
+        &self.terms
+    }
+    /// Roll all dice of the expression with the given random number generator.
+    /// Fails if the total does not fit an `i32`.
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Roll, String> {
+        let mut total: i32 = 0;
+        let mut dice = Vec::new();
+        for (sign, term) in &self.terms {
+            let (faces, value) = match *term {
+                Term::Constant(n) => (vec![], n),
+                Term::Sum { count, sides } => {
+                    let faces = throw(rng, count, sides);
+                    let sum = faces.iter().sum::<u32>();
+                    (faces, sum as i32)
+                }
+                Term::Best { count, sides } => {
+                    let faces = throw(rng, count, sides);
+                    let best = faces.iter().cloned().max().unwrap_or(0);
+                    (faces, best as i32)
+                }
+                Term::Worst { count, sides } => {
+                    let faces = throw(rng, count, sides);
+                    let worst = faces.iter().cloned().min().unwrap_or(0);
+                    (faces, worst as i32)
+                }
+            };
+            total = sign
+                .checked_mul(value)
+                .and_then(|value| total.checked_add(value))
+                .ok_or_else(|| String::from("Result out of range"))?;
+            dice.push(faces);
+        }
+        Ok(Roll::new(self, dice, total))
+    }
+    /// Whether this is a move roll, `2d6` plus or minus constants.
+    pub fn is_move_roll(&self) -> bool {
+        let dice: Vec<_> = self
+            .terms
+            .iter()
+            .filter(|(_, term)| !matches!(term, Term::Constant(_)))
+            .collect();
+        dice == [&(1, Term::Sum { count: 2, sides: 6 })]
+    }
+}
+
+impl FromStr for Expr {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        if s.is_empty() {
+            return Err(String::from("Empty expression"));
+        }
+        let mut terms = Vec::new();
+        let mut rest = s.as_str();
+        let mut sign = 1;
+        if rest.starts_with('-') {
+            sign = -1;
+            rest = &rest[1..];
+        }
+        loop {
+            let (term, tail) = parse_term(rest)?;
+            terms.push((sign, term));
+            rest = tail;
+            sign = match rest.chars().next() {
+                None => break,
+                Some('+') => 1,
+                Some('-') => -1,
+                Some(c) => return Err(format!("Unexpected '{}' in {}", c, s)),
+            };
+            rest = &rest[1..];
+        }
+        Ok(Expr { terms })
+    }
+}
+
+/// Roll `count` dice with `sides` sides each.
+fn throw<R: Rng + ?Sized>(rng: &mut R, count: u32, sides: u32) -> Vec<u32> {
+    (0..count).map(|_| rng.gen_range(1..=sides)).collect()
+}
+
+/// Parse the term at the start of `s` and return it and the rest of `s`.
+fn parse_term(s: &str) -> Result<(Term, &str), String> {
+    let keep = if s.starts_with("b[") {
+        Some(true)
+    } else if s.starts_with("w[") {
+        Some(false)
+    } else {
+        None
+    };
+    match keep {
+        Some(best) => {
+            let (count, sides, rest) = match parse_dice(&s[2..])? {
+                (count, Some(sides), rest) => (count.unwrap_or(1), sides, rest),
+                _ => return Err(format!("Expected dice in {}", s)),
+            };
+            if !rest.starts_with(']') {
+                return Err(format!("Missing ']' in {}", s));
+            }
+            let term = if best {
+                Term::Best { count, sides }
+            } else {
+                Term::Worst { count, sides }
+            };
+            Ok((term, &rest[1..]))
+        }
+        None => match parse_dice(s)? {
+            (count, Some(sides), rest) => Ok((
+                Term::Sum {
+                    count: count.unwrap_or(1),
+                    sides,
+                },
+                rest,
+            )),
+            (Some(n), None, rest) if n <= MAX_CONSTANT => Ok((Term::Constant(n as i32), rest)),
+            (Some(n), None, _) => Err(format!("Number {} is too large", n)),
+            (None, None, _) => Err(format!("Expected a number or dice in '{}'", s)),
+        },
+    }
+}
+
+/// Parse `[count]d<sides>` or a constant at the start of `s`.
+fn parse_dice(s: &str) -> Result<(Option<u32>, Option<u32>, &str), String> {
+    let (count, rest) = parse_number(s)?;
+    if !rest.starts_with('d') {
+        return Ok((count, None, rest));
+    }
+    let (sides, rest) = parse_number(&rest[1..])?;
+    let sides = sides.ok_or_else(|| format!("Missing sides in '{}'", s))?;
+    if count == Some(0) || sides == 0 {
+        return Err(format!(
+            "Dice need at least one die and one side in '{}'",
+            s
+        ));
+    }
+    if count.unwrap_or(1) > MAX_COUNT || sides > MAX_SIDES {
+        return Err(format!(
+            "At most {}d{} are supported in '{}'",
+            MAX_COUNT, MAX_SIDES, s
+        ));
+    }
+    Ok((count, Some(sides), rest))
+}
+
+/// Parse the number at the start of `s`, if there is one.
+fn parse_number(s: &str) -> Result<(Option<u32>, &str), String> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    if end == 0 {
+        return Ok((None, s));
+    }
+    s[..end]
+        .parse()
+        .map(|n| (Some(n), &s[end..]))
+        .map_err(|_| format!("Number {} is too large", &s[..end]))
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (i, (sign, term)) in self.terms.iter().enumerate() {
+            if *sign < 0 {
+                write!(f, "-")?;
+            } else if i > 0 {
+                write!(f, "+")?;
+            }
+            write!(f, "{}", term)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Term::Constant(n) => write!(f, "{}", n),
+            Term::Sum { count, sides } => write!(f, "{}d{}", count, sides),
+            Term::Best { count, sides } => write!(f, "b[{}d{}]", count, sides),
+            Term::Worst { count, sides } => write!(f, "w[{}d{}]", count, sides),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn parse() {
+        let expr: Expr = " b[2D8] + 2 - d4".parse().unwrap();
+        assert_eq!(
+            expr.terms(),
+            &[
+                (1, Term::Best { count: 2, sides: 8 }),
+                (1, Term::Constant(2)),
+                (-1, Term::Sum { count: 1, sides: 4 }),
+            ]
+        );
+        assert_eq!(expr.to_string(), "b[2d8]+2-1d4");
+        assert_eq!(
+            "-9+w[3d6]".parse::<Expr>().unwrap().to_string(),
+            "-9+w[3d6]"
+        );
+        assert!("2d6+1".parse::<Expr>().unwrap().is_move_roll());
+        assert!(!"2d6+1d4".parse::<Expr>().unwrap().is_move_roll());
+        // The same seed gives the same rolls
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let roll = "10d20".parse::<Expr>().unwrap().roll(&mut rng).unwrap();
+        assert_eq!(roll.dice(), &[vec![5, 20, 16, 9, 13, 7, 7, 4, 17, 13]]);
+        assert_eq!(roll.total(), 111);
+        let roll = "b[2d6]-1".parse::<Expr>().unwrap().roll(&mut rng).unwrap();
+        let best = *roll.dice()[0].iter().max().unwrap() as i32;
+        assert_eq!(roll.total(), best - 1);
+        for bad in &[
+            "", "2d", "2d6+", "b[2d6", "b[3]", "0d6", "1d0", "2x6", "1000d6", "9999999",
+        ] {
+            assert!(bad.parse::<Expr>().is_err(), "{} should fail", bad);
+        }
+        // Totals out of range are an error, not an overflow
+        let huge = vec!["1000000"; 2200].join("+").parse::<Expr>().unwrap();
+        assert!(huge.roll(&mut rng).is_err());
+    }
+}
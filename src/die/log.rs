@@ -0,0 +1,236 @@
+//! Recorded rolls of a session
+//!
+//! ```text
+//!   1  20:14:03  2d6+2 = [3, 5]+2 = 10  Defy Danger for Ayla
+//!   2  20:15:41  b[2d8] = b[7, 2] = 7
+//! ```
+
+use super::expr::{Expr, Term};
+use chrono::{DateTime, Local};
+use colored::Colorize;
+use serde_json;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::{Error, ErrorKind::InvalidData, ErrorKind::InvalidInput};
+use std::io::{Read, Write};
+use std::ops::Deref;
+use std::path::Path;
+
+/// A rolled expression with the faces of all dice.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Roll {
+    /// When the dice were rolled.
+    time: DateTime<Local>,
+    /// The rolled expression.
+    expr: String,
+    /// What the roll was for, i.e. `Defy Danger for Ayla`.
+    #[serde(default)]
+    label: Option<String>,
+    /// The faces of the dice of each term, empty for constants.
+    dice: Vec<Vec<u32>>,
+    /// The total of the roll.
+    total: i32,
+}
+
+/// All rolls of a session, the latest last.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RollLog {
+    rolls: Vec<Roll>,
+}
+
+impl Roll {
+    /// Create a new roll of `expr`, rolled just now.
+    pub fn new(expr: &Expr, dice: Vec<Vec<u32>>, total: i32) -> Self {
+        Roll {
+            time: Local::now(),
+            expr: expr.to_string(),
+            label: None,
+            dice,
+            total,
+        }
+    }
+    /// Set what the roll was for.
+    pub fn with_label(mut self, label: Option<&str>) -> Self {
+        self.label = label.map(|label| label.to_string());
+        self
+    }
+    /// Get when the dice were rolled.
+    pub fn time(&self) -> &DateTime<Local> {
+        &self.time
+    }
+    /// Get the rolled expression.
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+    /// Get what the roll was for.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+    /// Get the faces of the dice of each term, empty for constants.
+    pub fn dice(&self) -> &[Vec<u32>] {
+        &self.dice
+    }
+    /// Get the total of the roll.
+    pub fn total(&self) -> i32 {
+        self.total
+    }
+    /// Get the expression with the faces of the dice in place of the dice,
+    /// i.e. `[3, 5]+2` for `2d6+2`.
+    pub fn details(&self) -> String {
+        let expr: Expr = match self.expr.parse() {
+            Ok(expr) => expr,
+            Err(_) => return self.expr.clone(),
+        };
+        let mut details = String::new();
+        for (i, ((sign, term), faces)) in expr.terms().iter().zip(&self.dice).enumerate() {
+            if *sign < 0 {
+                details.push('-');
+            } else if i > 0 {
+                details.push('+');
+            }
+            let faces = faces
+                .iter()
+                .map(|face| face.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            match term {
+                Term::Constant(n) => details += &n.to_string(),
+                Term::Sum { .. } => details += &format!("[{}]", faces),
+                Term::Best { .. } => details += &format!("b[{}]", faces),
+                Term::Worst { .. } => details += &format!("w[{}]", faces),
+            }
+        }
+        details
+    }
+}
+
+impl RollLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        RollLog::default()
+    }
+    /// Parse a log given as JSON array of rolls through the reader.
+    pub fn parse<R: Read>(reader: R) -> io::Result<Self> {
+        serde_json::from_reader(reader)
+            .map(|rolls| RollLog { rolls })
+            .map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Write the log as JSON array of rolls to the given writer,
+    /// which can be read again with [parse](RollLog::parse).
+    pub fn save<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.rolls).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Record the given `roll`.
+    pub fn push(&mut self, roll: Roll) {
+        self.rolls.push(roll);
+    }
+    /// Serialize the log as CSV with the columns `time,expr,label,details,total`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time,expr,label,details,total\n");
+        for roll in &self.rolls {
+            let fields = [
+                roll.time.to_rfc3339(),
+                roll.expr.clone(),
+                roll.label.clone().unwrap_or_default(),
+                roll.details(),
+                roll.total.to_string(),
+            ];
+            let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv += &fields.join(",");
+            csv.push('\n');
+        }
+        csv
+    }
+    /// Serialize the log as a JSON array of rolls.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.rolls).unwrap_or_default()
+    }
+    /// Write the log to the file at `path`.
+    /// The format is chosen by the extension, `csv` or `json`.
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let content = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => self.to_csv(),
+            Some("json") => self.to_json(),
+            _ => {
+                return Err(Error::new(
+                    InvalidInput,
+                    format!("Cannot export to {}, use .csv or .json", path.display()),
+                ))
+            }
+        };
+        fs::write(path, content)
+    }
+}
+
+/// Quote a CSV field if necessary.
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl fmt::Display for Roll {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{} = {} = {}",
+            self.expr,
+            self.details(),
+            self.total.to_string().bold()
+        )?;
+        if let Some(ref label) = self.label {
+            write!(f, "  {}", label.italic())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RollLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (i, roll) in self.rolls.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3}  {}  {}",
+                i + 1,
+                roll.time.format("%H:%M:%S"),
+                roll
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for RollLog {
+    type Target = Vec<Roll>;
+    fn deref(&self) -> &Self::Target {
+        &self.rolls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::card::strip_colors;
+
+    #[test]
+    fn export() {
+        let expr: Expr = "b[2d8]+2".parse().unwrap();
+        let mut log = RollLog::new();
+        log.push(Roll::new(&expr, vec![vec![7, 2], vec![]], 9).with_label(Some("Hack, \"slash\"")));
+        assert_eq!(log[0].details(), "b[7, 2]+2");
+        assert_eq!(
+            strip_colors(&log[0].to_string()),
+            "b[2d8]+2 = b[7, 2]+2 = 9  Hack, \"slash\""
+        );
+        let csv = log.to_csv();
+        assert!(csv.ends_with(",b[2d8]+2,\"Hack, \"\"slash\"\"\",\"b[7, 2]+2\",9\n"));
+        let json: Vec<Roll> = serde_json::from_str(&log.to_json()).unwrap();
+        assert_eq!(json, *log);
+        assert_eq!(RollLog::parse(log.to_json().as_bytes()).unwrap(), log);
+        assert!(log.export("rolls.txt").is_err());
+    }
+}
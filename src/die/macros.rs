@@ -0,0 +1,193 @@
+//! Named roll macros and variables
+//!
+//! Macros are stored as TOML, mapping names to expressions:
+//!
+//! ```toml
+//! hack = "2d6+$STR"
+//! dmg = "d10+2"
+//! ```
+//!
+//! Expressions may reference variables like `$STR`, which are replaced by their value
+//! when the macro is [expanded](Macros::expand).
+
+use super::expr::Expr;
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::{Error, ErrorKind::InvalidData, ErrorKind::InvalidInput};
+use std::path::Path;
+use toml;
+
+/// Values of variables by their name, i.e. `STR` → `2`.
+pub type Variables = BTreeMap<String, i32>;
+
+/// Named roll expressions.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Macros {
+    macros: BTreeMap<String, String>,
+}
+
+impl Macros {
+    /// Create an empty set of macros.
+    pub fn new() -> Self {
+        Macros::default()
+    }
+    /// Parse macros given in TOML through the reader.
+    pub fn parse<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        toml::from_str(&content).map_err(|e| Error::new(InvalidData, e))
+    }
+    /// Load the macros from the given `path` if it exists, start without any otherwise.
+    pub fn load_or_default(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            Macros::parse(File::open(path)?)
+        } else {
+            Ok(Macros::new())
+        }
+    }
+    /// Save the macros to the given `path`, creating its directory if necessary.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string(self).map_err(|e| Error::new(InvalidData, e))?;
+        fs::write(path, content)
+    }
+    /// Define the macro `name` as `expr`, replacing an existing one.
+    ///
+    /// The name has to start with a letter and may not be an expression itself,
+    /// `expr` has to be a valid expression once its variables are replaced.
+    pub fn set(&mut self, name: &str, expr: &str) -> io::Result<()> {
+        let starts_with_letter = name.chars().next().is_some_and(char::is_alphabetic);
+        let valid = name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if !starts_with_letter || !valid || name.parse::<Expr>().is_ok() {
+            return Err(Error::new(
+                InvalidInput,
+                format!("Invalid macro name {}", name),
+            ));
+        }
+        let expr: String = expr.chars().filter(|c| !c.is_whitespace()).collect();
+        substitute(&expr, &Variables::new(), true)
+            .and_then(|expr| expr.parse::<Expr>())
+            .map_err(|e| Error::new(InvalidInput, e))?;
+        self.macros.insert(name.to_string(), expr);
+        Ok(())
+    }
+    /// Remove the macro `name` and return its expression.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.macros.remove(name)
+    }
+    /// Get the expression of the macro `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.macros.get(name).map(|expr| expr.as_str())
+    }
+    /// Get the names of all macros.
+    pub fn names(&self) -> Vec<String> {
+        self.macros.keys().cloned().collect()
+    }
+    /// Expand `s` into an expression that can be rolled.
+    ///
+    /// If `s` is the name of a macro, its expression is used.
+    /// All variables like `$STR` are replaced by their value in `vars`.
+    pub fn expand(&self, s: &str, vars: &Variables) -> Result<String, String> {
+        let s = s.trim();
+        let expr = self.get(s).unwrap_or(s);
+        substitute(expr, vars, false)
+    }
+    /// List all macros.
+    pub fn list(&self) {
+        println!(">> {}", "Macros".bold());
+        let width = self.macros.keys().map(|name| name.len()).max().unwrap_or(0);
+        self.macros
+            .iter()
+            .for_each(|(name, expr)| println!("   {:width$} = {}", name, expr, width = width));
+    }
+}
+
+/// Replace the variables in `expr` by their values.
+/// If `dummy` is set, all variables are replaced by zero.
+fn substitute(expr: &str, vars: &Variables, dummy: bool) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = expr;
+    while let Some(start) = rest.find('$') {
+        result += &rest[..start];
+        let name_len = rest[start + 1..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len() - start - 1);
+        let name = &rest[start + 1..start + 1 + name_len];
+        if name.is_empty() {
+            return Err(format!("Missing variable name in {}", expr));
+        }
+        let value = if dummy {
+            0
+        } else {
+            match vars.get(&name.to_uppercase()) {
+                Some(value) => *value,
+                None if vars.is_empty() => {
+                    return Err(format!(
+                        "Unknown variable ${}, no character is active",
+                        name
+                    ))
+                }
+                None => return Err(format!("Unknown variable ${}", name)),
+            }
+        };
+        // Merge the sign of negative values into the preceding operator
+        if value < 0 && result.ends_with('+') {
+            result.pop();
+            result += &value.to_string();
+        } else if value < 0 && result.ends_with('-') {
+            result.pop();
+            if !result.is_empty() {
+                result.push('+');
+            }
+            result += &(-value).to_string();
+        } else {
+            result += &value.to_string();
+        }
+        rest = &rest[start + 1 + name_len..];
+    }
+    result += rest;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn macros() {
+        let mut macros = Macros::new();
+        macros.set("hack", "2d6 + $STR").unwrap();
+        macros.set("dmg", "d10+2").unwrap();
+        assert!(macros.set("d6", "1d6").is_err());
+        assert!(macros.set("2x", "1d6").is_err());
+        assert!(macros.set("bad", "2d").is_err());
+        let mut vars = Variables::new();
+        assert!(macros
+            .expand("hack", &vars)
+            .unwrap_err()
+            .contains("no character"));
+        vars.insert(String::from("STR"), -1);
+        vars.insert(String::from("DEX"), 2);
+        assert_eq!(macros.expand("hack", &vars).unwrap(), "2d6-1");
+        assert_eq!(macros.expand("2d6-$str", &vars).unwrap(), "2d6+1");
+        assert_eq!(macros.expand("2d6+$DEX", &vars).unwrap(), "2d6+2");
+        assert_eq!(macros.expand("-$STR+d4", &vars).unwrap(), "1+d4");
+        assert_eq!(macros.expand("dmg", &vars).unwrap(), "d10+2");
+        assert!(macros.expand("2d6+$WIS", &vars).is_err());
+        assert_eq!(macros.names(), vec!["dmg", "hack"]);
+        // Survives saving and loading
+        let toml = toml::to_string(&macros).unwrap();
+        assert_eq!(Macros::parse(toml.as_bytes()).unwrap(), macros);
+        assert_eq!(macros.remove("dmg"), Some(String::from("d10+2")));
+        assert_eq!(macros.get("dmg"), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Dice rolls and their odds
+
+pub mod expr;
+pub mod log;
+pub mod macros;
+pub mod odds;
+
+pub use self::expr::{Expr, Term};
+pub use self::log::{Roll, RollLog};
+pub use self::macros::{Macros, Variables};
+pub use self::odds::Distribution;
+
+use colored::Colorize;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::sync::Mutex;
+
+/// The random number generator of all rolls, see [seed](seed).
+static RNG: Mutex<Option<ChaCha8Rng>> = Mutex::new(None);
+
+/// Seed the random number generator of all rolls.
+///
+/// The same seed followed by the same rolls always gives the same results.
+pub fn seed(seed: u64) {
+    let mut rng = RNG.lock().unwrap_or_else(|e| e.into_inner());
+    *rng = Some(ChaCha8Rng::seed_from_u64(seed));
+}
+
+/// Call `f` with the random number generator of all rolls.
+///
+/// Until [seed](seed) is called, the generator is seeded from the system's entropy.
+pub fn with_rng<T, F: FnOnce(&mut ChaCha8Rng) -> T>(f: F) -> T {
+    let mut rng = RNG.lock().unwrap_or_else(|e| e.into_inner());
+    f(rng.get_or_insert_with(ChaCha8Rng::from_entropy))
+}
+
+/// Execute a d20 expression and print the total with the faces of the dice.
+///
+/// Roll a die using a d20 expression, see [Expr](expr::Expr) for its format.
+/// The roll, with the optional `label`, is returned to be recorded in a [RollLog](log::RollLog).
+///
+/// # Examples
+///
+/// |      Expression | Meaning                                                  |
+/// |             --- | ---                                                      |
+/// |             1d6 | Roll a normal die                                        |
+/// |            1d20 | Roll a die with 20 sides                                 |
+/// |            4d20 | Roll 4 die with 20 sides                                 |
+/// |          9d4+14 | Roll 9 die with 4 sides and add 14                       |
+/// |       3d3-9+2d6 | Roll 3 die with 3 sides subtract 9 and add 2 d6          |
+/// |              -9 | Return -9                                                |
+/// |        b[2d8]+2 | Roll 2 die with 8 sides, keep the higher one and add 2   |
+/// |         w[2d20] | Roll 2 die with 20 sides and keep the lower one          |
+/// | -9+25-2+14-7+21 | Abuse this program to calculate the answer to everything |
+///
+pub fn roll(s: &str, label: Option<&str>) -> Option<Roll> {
+    match throw(s) {
+        Ok(roll) => {
+            let roll = roll.with_label(label);
+            let total = format!("{}", roll.total()).bold();
+            println!("\n >> {}   {}\n", total, roll.details());
+            Some(roll)
+        }
+        Err(fail) => {
+            println!("Error: {}", fail);
+            None
+        }
+    }
+}
+
+/// Roll a d20 expression and return the roll with the faces of all dice.
+///
+/// See [Expr](expr::Expr) for the format of the expression.
+pub fn throw(s: &str) -> Result<Roll, String> {
+    let expr: Expr = s.parse()?;
+    with_rng(|rng| expr.roll(rng))
+}
+
+/// Evaluate a d20 expression and return the total.
+///
+/// See [Expr](expr::Expr) for the format of the expression.
+/// A leading `d` is read as `1d`.
+pub fn evaluate(s: &str) -> Result<i32, String> {
+    throw(s).map(|roll| roll.total())
+}
+
+/// Print the exact odds of a dice expression.
+///
+/// Shows the mean, the lowest and highest total, a percentile table and a histogram
+/// with the odds of each total and of reaching at least that total.
+/// For move rolls (`2d6` plus modifiers) the odds of `10+`, `7-9` and `6-` are shown, too.
+/// See [Expr](expr::Expr) for the format of the expression.
+pub fn odds(s: &str) {
+    let expr: Expr = match s.parse() {
+        Ok(expr) => expr,
+        Err(fail) => {
+            println!("Error: {}", fail);
+            return;
+        }
+    };
+    let dist = match Distribution::of(&expr) {
+        Ok(dist) => dist,
+        Err(fail) => {
+            println!("Error: {}", fail);
+            return;
+        }
+    };
+    println!("\n >> {}\n", expr.to_string().bold());
+    if expr.is_move_roll() {
+        for (band, p) in &dist.move_bands() {
+            println!("    {:<4} {:>6.2}%", band.bold(), p * 100.0);
+        }
+        println!();
+    }
+    println!("{}", dist);
+}
+
+/// Compute the exact distribution of a dice expression.
+///
+/// See [Expr](expr::Expr) for the format of the expression.
+pub fn distribution(s: &str) -> Result<Distribution, String> {
+    Distribution::of(&s.parse()?)
+}
@@ -0,0 +1,302 @@
+//! Exact probability distributions of dice expressions.
+//!
+//! ```text
+//!  >> b[2d8]+2
+//!
+//!     Mean 7.81   Min 3   Max 10
+//!
+//!     Percentile   5%  10%  25%  50%  75%  90%  95%
+//!     Result        4    5    6    8    9   10   10
+//!
+//!      3    1.56%  100.00% ███
+//!      4    4.69%   98.44% ████████
+//!      ...
+//!     10   23.44%   23.44% ████████████████████████████████████████
+//! ```
+
+use super::expr::{Expr, Term};
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Percentiles shown in the table.
+const PERCENTILES: [u32; 7] = [5, 10, 25, 50, 75, 90, 95];
+/// Length of the longest bar of the histogram.
+const BAR_WIDTH: f64 = 40.0;
+/// Most rows of the histogram, more totals are grouped into ranges.
+const MAX_ROWS: i32 = 40;
+/// Most possible totals of an expression whose distribution is computed.
+const MAX_TOTALS: i64 = 2000;
+
+/// Probability of every possible total of an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    /// Probability by total, all totals with a probability above zero.
+    probabilities: BTreeMap<i32, f64>,
+}
+
+impl Distribution {
+    /// Compute the distribution of the given expression.
+    ///
+    /// Fails if the expression has more than 2000 possible totals or its totals do not fit an `i32`.
+    pub fn of(expr: &Expr) -> Result<Self, String> {
+        let (mut min, mut max) = (0_i64, 0_i64);
+        for (sign, term) in expr.terms() {
+            let (low, high) = term_range(*term);
+            let (low, high) = if *sign < 0 {
+                (-high, -low)
+            } else {
+                (low, high)
+            };
+            min += low;
+            max += high;
+            if min < i64::from(i32::MIN) || max > i64::from(i32::MAX) {
+                return Err(String::from("Result out of range"));
+            }
+        }
+        if max - min + 1 > MAX_TOTALS {
+            return Err(format!(
+                "Too many possible totals, at most {} are supported",
+                MAX_TOTALS
+            ));
+        }
+        Ok(expr
+            .terms()
+            .iter()
+            .fold(Distribution::constant(0), |dist, (sign, term)| {
+                dist.add(&Distribution::of_term(*term), *sign)
+            }))
+    }
+    /// The distribution that is always `n`.
+    pub fn constant(n: i32) -> Self {
+        let mut probabilities = BTreeMap::new();
+        probabilities.insert(n, 1.0);
+        Distribution { probabilities }
+    }
+    fn of_term(term: Term) -> Self {
+        match term {
+            Term::Constant(n) => Distribution::constant(n),
+            Term::Sum { count, sides } => {
+                let die = Distribution::weighted(sides, |_| 1.0);
+                (0..count).fold(Distribution::constant(0), |dist, _| dist.add(&die, 1))
+            }
+            // P(max = k) = (k/s)^n - ((k-1)/s)^n
+            Term::Best { count, sides } => Distribution::weighted(sides, |k| {
+                let s = f64::from(sides);
+                (f64::from(k) / s).powi(count as i32) - (f64::from(k - 1) / s).powi(count as i32)
+            }),
+            // P(min = k) = ((s-k+1)/s)^n - ((s-k)/s)^n
+            Term::Worst { count, sides } => Distribution::weighted(sides, |k| {
+                let s = f64::from(sides);
+                (f64::from(sides - k + 1) / s).powi(count as i32)
+                    - (f64::from(sides - k) / s).powi(count as i32)
+            }),
+        }
+    }
+    /// Distribution of the sides `1..=sides` of a die with the weights of `weight`.
+    fn weighted<F: Fn(u32) -> f64>(sides: u32, weight: F) -> Self {
+        let total: f64 = (1..=sides).map(&weight).sum();
+        Distribution {
+            probabilities: (1..=sides).map(|k| (k as i32, weight(k) / total)).collect(),
+        }
+    }
+    /// The distribution of `self + sign * other`.
+    ///
+    /// The totals are collected in a `Vec` from the lowest possible total on,
+    /// which is a lot faster than a map for the many totals of large dice.
+    fn add(&self, other: &Distribution, sign: i32) -> Self {
+        let (low, high) = if sign < 0 {
+            (-other.max(), -other.min())
+        } else {
+            (other.min(), other.max())
+        };
+        let min = self.min() + low;
+        let mut sums = vec![0.0; (self.max() + high - min + 1) as usize];
+        for (a, p) in &self.probabilities {
+            for (b, q) in &other.probabilities {
+                sums[(a + sign * b - min) as usize] += p * q;
+            }
+        }
+        let probabilities = sums
+            .into_iter()
+            .enumerate()
+            .filter(|(_, p)| *p > 0.0)
+            .map(|(i, p)| (min + i as i32, p))
+            .collect();
+        Distribution { probabilities }
+    }
+    /// Get the probability of every total, lowest first.
+    pub fn probabilities(&self) -> &BTreeMap<i32, f64> {
+        &self.probabilities
+    }
+    /// Get the probability of exactly `total`.
+    pub fn exactly(&self, total: i32) -> f64 {
+        self.probabilities.get(&total).cloned().unwrap_or(0.0)
+    }
+    /// Get the probability of `total` or more.
+    pub fn at_least(&self, total: i32) -> f64 {
+        self.probabilities.range(total..).map(|(_, p)| p).sum()
+    }
+    /// Get the probability of `total` or less.
+    pub fn at_most(&self, total: i32) -> f64 {
+        self.probabilities.range(..=total).map(|(_, p)| p).sum()
+    }
+    /// Get the expected total.
+    pub fn mean(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .map(|(total, p)| f64::from(*total) * p)
+            .sum()
+    }
+    /// Get the lowest possible total.
+    pub fn min(&self) -> i32 {
+        *self.probabilities.keys().next().unwrap_or(&0)
+    }
+    /// Get the highest possible total.
+    pub fn max(&self) -> i32 {
+        *self.probabilities.keys().next_back().unwrap_or(&0)
+    }
+    /// Get the lowest total that at least `percent` percent of the rolls do not exceed,
+    /// i.e. at most 10 percent of the rolls beat the 90th percentile.
+    pub fn percentile(&self, percent: u32) -> i32 {
+        let wanted = f64::from(percent) / 100.0 - 1e-9;
+        let mut sum = 0.0;
+        for (total, p) in &self.probabilities {
+            sum += p;
+            if sum >= wanted {
+                return *total;
+            }
+        }
+        self.max()
+    }
+    /// Get the odds of the outcomes of a move roll: `10+`, `7-9` and `6-`.
+    pub fn move_bands(&self) -> [(&'static str, f64); 3] {
+        [
+            ("10+", self.at_least(10)),
+            ("7-9", self.at_least(7) - self.at_least(10)),
+            ("6-", self.at_most(6)),
+        ]
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        writeln!(
+            f,
+            "    Mean {:.2}   Min {}   Max {}\n",
+            self.mean(),
+            self.min(),
+            self.max()
+        )?;
+        // Percentile table
+        let results: Vec<String> = PERCENTILES
+            .iter()
+            .map(|p| self.percentile(*p).to_string())
+            .collect();
+        let width = results.iter().map(|r| r.len()).max().unwrap_or(0).max(3) + 2;
+        write!(f, "    {}", "Percentile".bold())?;
+        for p in &PERCENTILES {
+            write!(f, "{:>width$}", format!("{}%", p), width = width)?;
+        }
+        write!(f, "\n    {}    ", "Result".bold())?;
+        for r in &results {
+            write!(f, "{:>width$}", r, width = width)?;
+        }
+        writeln!(f, "\n")?;
+        // Histogram with the odds of each total and of at least that total,
+        // with many totals the rows are ranges of totals
+        let size = (self.max() - self.min()) / MAX_ROWS + 1;
+        let rows: Vec<(String, f64, f64)> = (self.min()..=self.max())
+            .step_by(size as usize)
+            .map(|low| {
+                let high = (low + size - 1).min(self.max());
+                let label = if low == high {
+                    low.to_string()
+                } else {
+                    format!("{}-{}", low, high)
+                };
+                let p = self.probabilities.range(low..=high).map(|(_, p)| p).sum();
+                (label, p, self.at_least(low))
+            })
+            .filter(|(_, p, _)| *p > 0.0)
+            .collect();
+        let highest = rows.iter().map(|(_, p, _)| *p).fold(0.0_f64, f64::max);
+        let width = rows
+            .iter()
+            .map(|(label, _, _)| label.len())
+            .max()
+            .unwrap_or(0);
+        for (label, p, at_least) in &rows {
+            let bar = "█".repeat((p / highest * BAR_WIDTH).round().max(1.0) as usize);
+            writeln!(
+                f,
+                "    {:>width$} {:>7.2}% {:>7.2}% {}",
+                label,
+                p * 100.0,
+                at_least * 100.0,
+                bar,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the lowest and highest value of the given `term`.
+fn term_range(term: Term) -> (i64, i64) {
+    match term {
+        Term::Constant(n) => (i64::from(n), i64::from(n)),
+        Term::Sum { count, sides } => (i64::from(count), i64::from(count) * i64::from(sides)),
+        Term::Best { sides, .. } | Term::Worst { sides, .. } => (1, i64::from(sides)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dist(s: &str) -> Distribution {
+        Distribution::of(&s.parse().unwrap()).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn distributions() {
+        let d = dist("2d6+1");
+        assert_eq!((d.min(), d.max()), (3, 13));
+        assert!(close(d.mean(), 8.0));
+        assert!(close(d.exactly(8), 6.0 / 36.0));
+        assert!(close(d.probabilities().values().sum(), 1.0));
+        let bands = d.move_bands();
+        assert!(close(bands[0].1, 10.0 / 36.0));
+        assert!(close(bands[1].1, 16.0 / 36.0));
+        assert!(close(bands[2].1, 10.0 / 36.0));
+        assert_eq!(d.percentile(50), 8);
+        // Best of two d8: P(8) = 1 - (7/8)^2
+        let d = dist("b[2d8]+2");
+        assert_eq!((d.min(), d.max()), (3, 10));
+        assert!(close(d.exactly(10), 15.0 / 64.0));
+        assert!(close(dist("w[2d8]").exactly(1), 15.0 / 64.0));
+        assert!(close(dist("-d4").mean(), -2.5));
+        assert_eq!(dist("5"), Distribution::constant(5));
+        // Too many totals are an error, a few hundred are grouped into ranges
+        assert!(Distribution::of(&"100d1000".parse().unwrap()).is_err());
+        let d = dist("20d20");
+        assert!(close(d.probabilities().values().sum(), 1.0));
+        assert!(d.to_string().lines().count() < 60);
+        assert!(d.to_string().contains("20-29"));
+    }
+
+    #[test]
+    fn percentile() {
+        // 2d6 rolls 10 or less 33 out of 36 times, 9 or less only 30 times
+        let d = dist("2d6");
+        assert_eq!(d.percentile(90), 10);
+        assert_eq!(d.percentile(50), 7);
+        assert_eq!(d.percentile(10), 4);
+        assert_eq!(d.percentile(100), 12);
+    }
+}
@@ -0,0 +1,50 @@
+//! Console Hero
+//!
+//! Your [Dungeon World](http://www.dungeon-world.com/) library.
+//! Contains the data model for monsters, moves, items and tags, the search
+//! over them, a dice roller, the campaign state of the GM and the card rendering used by the `console-hero` binary.
+//! The [server](server) makes all of it available over HTTP,
+//! [rpc](rpc) over JSON-RPC on stdio and [tui](tui) is a full-screen browser.
+//!
+//! ```
+//! extern crate console_hero;
+//!
+//! use console_hero::data::{Data, DataPaths};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let data = Data::from(&DataPaths::default())?;
+//! let dragon = data.monsters.find("dragon").unwrap();
+//! assert_eq!(dragon.name(), "Apocalypse Dragon");
+//! println!("{}", dragon);
+//! # Ok(())
+//! # }
+//! ```
+
+extern crate regex;
+extern crate rustyline;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+extern crate textwrap;
+#[macro_use]
+extern crate serde_derive;
+extern crate chrono;
+extern crate colored;
+extern crate crossterm;
+extern crate dirs;
+extern crate pad;
+extern crate rand;
+extern crate rand_chacha;
+extern crate ratatui;
+extern crate tiny_http;
+extern crate toml;
+extern crate unicode_width;
+
+pub mod campaign;
+pub mod completion;
+pub mod config;
+pub mod data;
+pub mod die;
+pub mod rpc;
+pub mod server;
+pub mod tui;
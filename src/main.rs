@@ -1,30 +1,41 @@
-extern crate regex;
+extern crate console_hero;
 extern crate rustyline;
-extern crate serde;
-extern crate serde_json;
-extern crate textwrap;
-#[macro_use]
-extern crate serde_derive;
-extern crate colored;
-extern crate pad;
-extern crate unicode_width;
 #[macro_use]
 extern crate clap;
+extern crate colored;
 #[macro_use]
 extern crate version;
-extern crate d20;
 
-mod completion;
-mod data;
-mod die;
 #[cfg(test)]
 mod tests;
 
-use clap::App;
-use completion::HeroCompleter;
-use data::Data;
+use clap::{App, ArgMatches};
+use colored::Colorize;
+use console_hero::campaign::steadings::TAGS;
+use console_hero::campaign::{
+    Bond, Campaign, CastCost, Character, Clock, Clocks, Danger, Debility, Defenses, Front, Fronts,
+    Gear, Link, Population, Prosperity, Settlement, Stat, Steading,
+};
+use console_hero::completion::HeroCompleter;
+use console_hero::config;
+use console_hero::data::card::Theme;
+use console_hero::data::{
+    Class, Data, DataPaths, Defense, Hoard, KnownFor, Monster, MonsterBuilder, Monsters, Npc,
+    Organization, Size, Tables, Trait, Treasure,
+};
+use console_hero::die;
+use console_hero::die::{Macros, Roll, Variables};
+use console_hero::rpc;
+use console_hero::server::Server;
+use console_hero::tui;
+use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
+use rustyline::Editor;
+use std::cell::RefCell;
+use std::fs::File;
 use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
 
 fn main() -> io::Result<()> {
     let cli_yaml = load_yaml!("../cli.yml");
@@ -32,44 +43,177 @@ fn main() -> io::Result<()> {
     let matches = app.clone().get_matches();
 
     // Load data
-    let data = Data::from(
-        matches.value_of("move_data").unwrap(),
-        matches.value_of("monster_data").unwrap(),
-        matches.value_of("tag_data").unwrap(),
-        matches.value_of("item_data").unwrap(),
-    )?;
+    let paths = DataPaths {
+        moves: PathBuf::from(matches.value_of("move_data").unwrap()),
+        gm_moves: PathBuf::from(matches.value_of("gm_move_data").unwrap()),
+        monsters: PathBuf::from(matches.value_of("monster_data").unwrap()),
+        tags: PathBuf::from(matches.value_of("tag_data").unwrap()),
+        items: PathBuf::from(matches.value_of("item_data").unwrap()),
+        classes: PathBuf::from(matches.value_of("class_data").unwrap()),
+    };
+
+    // Open the campaign only to play it or to log a roll, lookups leave it alone,
+    // and load the data with its overlay
+    let play = !matches.is_present("rpc")
+        && (matches.subcommand_name().is_none() || matches.is_present("interactive"));
+    let mut campaign = if play || matches.subcommand_matches("roll").is_some() {
+        let dir = matches
+            .value_of("campaign")
+            .map(PathBuf::from)
+            .or_else(config::campaign_dir)
+            .unwrap_or_else(|| PathBuf::from("campaign"));
+        Some(Campaign::open(dir)?)
+    } else {
+        None
+    };
+    let data = load_data(&paths, campaign.as_ref())?;
+
+    // Load the theme
+    let theme = match matches.value_of("theme") {
+        Some(theme) => Theme::load(theme)?,
+        None => match config::theme_file() {
+            Some(path) => Theme::load_or_default(&path)?,
+            None => Theme::classic(),
+        },
+    };
+    theme.set_current();
+
+    // Seed the dice
+    if matches.is_present("seed") {
+        let seed = value_t!(matches, "seed", u64)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        die::seed(seed);
+    }
+
+    // Load the roll macros
+    let mut macros = match config::macros_file() {
+        Some(path) => Macros::load_or_default(&path)?,
+        None => Macros::new(),
+    };
+
+    // Answer JSON-RPC requests until stdin is closed
+    if matches.is_present("rpc") {
+        let stdin = io::stdin();
+        return rpc::serve(&data, stdin.lock(), io::stdout());
+    }
 
     // Execute single command if specified
-    let mut subcommand_given = true;
     if let Some(matches) = matches.subcommand_matches("item") {
         search_item(&data, matches.value_of("REGEX").unwrap());
     } else if let Some(matches) = matches.subcommand_matches("monster") {
         search_monster(&data, matches.value_of("REGEX").unwrap());
     } else if let Some(matches) = matches.subcommand_matches("move") {
         search_move(&data, matches.value_of("REGEX").unwrap());
+    } else if let Some(matches) = matches.subcommand_matches("gm") {
+        search_gm_move(&data, matches.value_of("REGEX").unwrap());
     } else if let Some(matches) = matches.subcommand_matches("tag") {
         search_tag(&data, matches.value_of("REGEX").unwrap());
+    } else if let Some(matches) = matches.subcommand_matches("class") {
+        search_class(&data, matches.value_of("REGEX").unwrap());
     } else if let Some(matches) = matches.subcommand_matches("roll") {
-        roll_dice(matches.value_of("D20_EXPR").unwrap());
+        let label = matches
+            .values_of("LABEL")
+            .map(|label| label.collect::<Vec<_>>().join(" "));
+        let vars = campaign
+            .as_ref()
+            .map(Campaign::variables)
+            .unwrap_or_default();
+        let expr = matches.value_of("D20_EXPR").unwrap();
+        if let Some(roll) = roll_dice(&macros, &vars, expr, label.as_deref()) {
+            if let Some(ref mut campaign) = campaign {
+                campaign.rolls.push(roll);
+                campaign.save()?;
+            }
+        }
+    } else if let Some(matches) = matches.subcommand_matches("macro") {
+        edit_macros(&mut macros, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("odds") {
+        die::odds(&matches.values_of("EXPR").unwrap().collect::<String>());
     } else if let Some(matches) = matches.subcommand_matches("list") {
         list(
             &data,
             matches.value_of("CATEGORY").unwrap(),
             matches.value_of("REGEX").unwrap(),
         );
-    } else {
-        subcommand_given = false;
+    } else if matches.subcommand_matches("tui").is_some() {
+        tui::run(&data)?;
+    } else if let Some(matches) = matches.subcommand_matches("serve") {
+        serve(
+            &data,
+            matches.value_of("host").unwrap(),
+            matches.value_of("port").unwrap(),
+        )?;
     }
 
-    if !subcommand_given || matches.is_present("interactive") {
-        interactive(data)?;
+    if let (true, Some(campaign)) = (play, campaign) {
+        interactive(data, &paths, macros, campaign)?;
     }
 
     Ok(())
 }
 
+/// Load the data from the files at `paths`, add the homebrew monsters and the random tables
+/// and apply the data overlay and the tables of the `campaign`, if one is open.
+fn load_data(paths: &DataPaths, campaign: Option<&Campaign>) -> io::Result<Data> {
+    let mut data = Data::from(paths)?;
+
+    // Add the homebrew monsters
+    if let Some(path) = config::monsters_file() {
+        if path.exists() {
+            for monster in Monsters::parse(File::open(path)?)?.drain(..) {
+                data.monsters.insert(monster);
+            }
+        }
+    }
+
+    // Add the random tables
+    if let Some(dir) = config::tables_dir() {
+        data.tables = Tables::load(&dir)?;
+    }
+
+    // Add the data of the campaign
+    if let Some(campaign) = campaign {
+        campaign.overlay().apply(campaign.dir(), &mut data)?;
+        for table in Tables::load(&campaign.dir().join("tables"))?.drain(..) {
+            data.tables.insert(table);
+        }
+    }
+    Ok(data)
+}
+
+/// Completes the input of the interactive mode with the data,
+/// which is shared as monsters can be created in between.
+struct ReplCompleter {
+    data: Rc<RefCell<Data>>,
+    /// Names of the roll macros.
+    macros: Vec<String>,
+}
+
+impl ReplCompleter {
+    fn new(data: &Rc<RefCell<Data>>, macros: Vec<String>) -> Self {
+        ReplCompleter {
+            data: Rc::clone(data),
+            macros,
+        }
+    }
+}
+
+impl Completer for ReplCompleter {
+    fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
+        let data = self.data.borrow();
+        HeroCompleter::new(&data)
+            .with_macros(self.macros.clone())
+            .complete(line, pos)
+    }
+}
+
 /// Interactive mode.
-fn interactive(data: Data) -> io::Result<()> {
+fn interactive(
+    data: Data,
+    paths: &DataPaths,
+    mut macros: Macros,
+    mut campaign: Campaign,
+) -> io::Result<()> {
     // Initialize clap
     let yaml_config = load_yaml!("../interactive.yml");
     let mut app = App::from_yaml(yaml_config).version(version!());
@@ -78,9 +222,16 @@ fn interactive(data: Data) -> io::Result<()> {
     let mut rl = rustyline::Editor::new()
         .history_ignore_dups(true)
         .history_ignore_space(true);
-    let compl = HeroCompleter::new(&data);
+    let shared = Rc::new(RefCell::new(data));
+    let compl = ReplCompleter::new(&shared, macros.names());
     rl.set_completer(Some(compl));
 
+    println!(
+        "Campaign {}, session {}",
+        campaign.dir().display(),
+        campaign.session()
+    );
+
     // Loop until the user wants to exit
     loop {
         // Read the next input line
@@ -88,7 +239,7 @@ fn interactive(data: Data) -> io::Result<()> {
             Ok(line) => {
                 rl.add_history_entry(&line);
                 let mut args = vec!["console_hero"];
-                args.extend(line.split(" "));
+                args.extend(line.trim_end().split(" "));
                 match app.clone().get_matches_from_safe(args) {
                     Ok(matches) => matches,
                     Err(_) => {
@@ -105,9 +256,16 @@ fn interactive(data: Data) -> io::Result<()> {
             }
         };
 
-        let concat = |s, arg| {
+        // Whether the command changed the campaign
+        let mut changed = false;
+        // A monster created by the user
+        let mut created = None;
+        // The data of an opened campaign
+        let mut reloaded = None;
+        let data = shared.borrow();
+        let concat = |s, arg: &str| {
             if s == String::new() {
-                format!("{}", arg)
+                arg.to_string()
             } else {
                 format!("{} {}", s, arg)
             }
@@ -132,29 +290,256 @@ fn interactive(data: Data) -> io::Result<()> {
                 .unwrap()
                 .fold(String::new(), concat);
             search_move(&data, &re);
+        } else if let Some(matches) = matches.subcommand_matches("gm") {
+            if let Some(matches) = matches.subcommand_matches("suggest") {
+                suggest_gm_move(&data, &campaign, matches.is_present("encounter"));
+            } else if let Some(re) = matches.values_of("REGEX") {
+                search_gm_move(&data, &re.fold(String::new(), concat));
+            } else {
+                data.gm_moves.list(".*");
+            }
         } else if let Some(matches) = matches.subcommand_matches("tag") {
             let re = matches
                 .values_of("REGEX")
                 .unwrap()
                 .fold(String::new(), concat);
             search_tag(&data, &re);
+        } else if let Some(matches) = matches.subcommand_matches("class") {
+            let re = matches
+                .values_of("REGEX")
+                .unwrap()
+                .fold(String::new(), concat);
+            search_class(&data, &re);
         } else if let Some(matches) = matches.subcommand_matches("roll") {
-            roll_dice(matches.value_of("D20_EXPR").unwrap());
+            let label = matches
+                .values_of("LABEL")
+                .map(|label| label.fold(String::new(), concat));
+            let label = label.as_ref().map(|label| label.trim_matches('"'));
+            let expr = matches.value_of("D20_EXPR").unwrap();
+            if let Some(roll) = roll_dice(&macros, &campaign.variables(), expr, label) {
+                campaign.rolls.push(roll);
+                changed = true;
+            }
+        } else if let Some(matches) = matches.subcommand_matches("macro") {
+            match edit_macros(&mut macros, matches) {
+                Ok(()) => {
+                    let compl = ReplCompleter::new(&shared, macros.names());
+                    rl.set_completer(Some(compl));
+                }
+                Err(e) => println!("Error: {}", e),
+            }
+        } else if let Some(matches) = matches.subcommand_matches("seed") {
+            match value_t!(matches, "N", u64) {
+                Ok(seed) => {
+                    die::seed(seed);
+                    println!("Seeded the dice with {}", seed);
+                }
+                Err(e) => println!("{}", e.message),
+            }
+        } else if matches.subcommand_matches("reroll").is_some() {
+            let last = campaign.rolls.last().cloned();
+            match last {
+                Some(last) => {
+                    if let Some(roll) = die::roll(last.expr(), last.label()) {
+                        campaign.rolls.push(roll);
+                        changed = true;
+                    }
+                }
+                None => println!("Nothing rolled yet"),
+            }
+        } else if let Some(matches) = matches.subcommand_matches("rolls") {
+            let rolls = &campaign.rolls;
+            match matches.value_of("export") {
+                Some(file) => match rolls.export(file) {
+                    Ok(()) => println!("Exported {} rolls to {}", rolls.len(), file),
+                    Err(e) => println!("Error: {}", e),
+                },
+                None => print!("{}", rolls),
+            }
+        } else if let Some(matches) = matches.subcommand_matches("npc") {
+            let query = matches
+                .values_of("QUERY")
+                .map(|query| query.fold(String::new(), concat));
+            let npc = die::with_rng(|rng| {
+                Npc::generate(&data.classes, &data.monsters, query.as_deref(), rng)
+            });
+            match npc {
+                Some(npc) => {
+                    println!("{}", npc);
+                    if matches.is_present("save") {
+                        let session = campaign.session();
+                        campaign.journal.add(session, &npc.note());
+                        changed = true;
+                    }
+                }
+                None => println!("No match"),
+            }
+        } else if let Some(matches) = matches.subcommand_matches("note") {
+            let text = matches.values_of("TEXT").unwrap().fold(String::new(), concat);
+            let session = campaign.session();
+            campaign.journal.add(session, &text);
+            changed = true;
+        } else if let Some(matches) = matches.subcommand_matches("notes") {
+            if let Err(e) = show_notes(&data, &campaign, matches) {
+                println!("Error: {}", e);
+            }
+        } else if let Some(matches) = matches.subcommand_matches("odds") {
+            die::odds(&matches.values_of("EXPR").unwrap().fold(String::new(), concat));
         } else if let Some(matches) = matches.subcommand_matches("list") {
             list(
                 &data,
                 matches.value_of("CATEGORY").unwrap(),
                 matches.value_of("REGEX").unwrap(),
             );
-        } else if let Some(_) = matches.subcommand_matches("quit") {
+        } else if let Some(matches) = matches.subcommand_matches("front") {
+            changed = edit_fronts(&mut rl, &mut campaign.fronts, matches);
+        } else if let Some(matches) = matches.subcommand_matches("advance") {
+            let re = matches
+                .values_of("PORTENT")
+                .unwrap()
+                .fold(String::new(), concat);
+            changed = advance(&mut campaign.fronts, &re);
+        } else if let Some(matches) = matches.subcommand_matches("clock") {
+            changed = report(edit_clocks(&mut campaign.clocks, matches));
+        } else if let Some(matches) = matches.subcommand_matches("character") {
+            changed = report(edit_characters(&mut rl, &data, &mut campaign, matches));
+        } else if matches.subcommand_matches("party").is_some() {
+            if campaign.characters.is_empty() {
+                println!("No characters, use `character new NAME`");
+            } else {
+                println!("{}", campaign.characters.party(&data.classes));
+            }
+        } else if let Some(matches) = matches.subcommand_matches("bonds") {
+            changed = edit_bonds(&mut rl, &data, &mut campaign, matches);
+        } else if let Some(matches) = matches.subcommand_matches("damage") {
+            changed = report(damage(&mut rl, &data, &mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("heal") {
+            changed = report(heal(&mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("debility") {
+            changed = edit_debilities(&mut campaign, matches);
+        } else if let Some(matches) = matches.subcommand_matches("steading") {
+            changed = report(edit_steadings(&mut rl, &mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("shop") {
+            changed = report(shop(&data, &mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("buy") {
+            changed = report(buy(&mut rl, &data, &mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("sell") {
+            changed = sell(&data, &mut campaign, matches);
+        } else if let Some(matches) = matches.subcommand_matches("coins") {
+            changed = report(give_coins(&mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("xp") {
+            changed = report(mark_xp(&mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("end") {
+            if matches.subcommand_matches("session").is_some() {
+                changed = end_session(&mut rl, &data, &mut campaign);
+                if !changed {
+                    println!("Session {} goes on, no XP marked", campaign.session());
+                }
+            }
+        } else if let Some(matches) = matches.subcommand_matches("level") {
+            if let Some(matches) = matches.subcommand_matches("up") {
+                changed = report(level_up(&mut rl, &data, &mut campaign, matches));
+            }
+        } else if let Some(matches) = matches.subcommand_matches("spell") {
+            changed = report(edit_spells(&mut rl, &data, &mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("cast") {
+            let spell = matches.values_of("SPELL").unwrap().fold(String::new(), concat);
+            changed = report(cast(&mut rl, &data, &mut campaign, &spell));
+        } else if let Some(matches) = matches.subcommand_matches("encounter") {
+            changed = report(edit_encounter(&data, &mut campaign, matches));
+        } else if let Some(matches) = matches.subcommand_matches("table") {
+            if let Err(e) = roll_table(&data, matches) {
+                println!("Error: {}", e);
+            }
+        } else if let Some(matches) = matches.subcommand_matches("loot") {
+            changed = loot(&data, &mut campaign, matches);
+        } else if let Some(matches) = matches.subcommand_matches("campaign") {
+            match matches.subcommand_matches("open") {
+                Some(matches) => {
+                    let opened = campaign
+                        .save()
+                        .and_then(|()| Campaign::open(matches.value_of("DIR").unwrap()))
+                        .and_then(|opened| Ok((load_data(paths, Some(&opened))?, opened)));
+                    match opened {
+                        Ok((data, opened)) => {
+                            campaign = opened;
+                            print_campaign(&campaign);
+                            reloaded = Some(data);
+                        }
+                        Err(e) => println!("Error: {}", e),
+                    }
+                }
+                None => print_campaign(&campaign),
+            }
+        } else if let Some(matches) = matches.subcommand_matches("create") {
+            if matches.subcommand_matches("monster").is_some() {
+                if let Some(monster) = create_monster(&mut rl) {
+                    if let Err(e) = save_monster(&monster) {
+                        println!("Error: {}", e);
+                    }
+                    created = Some(monster);
+                }
+            }
+        } else if matches.subcommand_matches("quit").is_some() {
             break;
-        } else if let Some(_) = matches.subcommand_matches("info") {
+        } else if matches.subcommand_matches("info").is_some() {
             app.print_long_help()
                 .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
         }
+
+        // Add the created monster or replace the data, it is borrowed until here
+        drop(data);
+        if let Some(monster) = created {
+            shared.borrow_mut().monsters.insert(monster);
+        }
+        if let Some(data) = reloaded {
+            *shared.borrow_mut() = data;
+        }
+
+        // Save on every change, so a session can resume where it stopped
+        if changed {
+            if let Err(e) = campaign.save() {
+                println!("Error: Could not save the campaign: {}", e);
+            }
+        }
     }
 
-    Ok(())
+    // Autosave on exit
+    campaign.save()
+}
+
+/// Print the error of a command, returns whether the command changed the campaign.
+fn report(result: io::Result<bool>) -> bool {
+    result.unwrap_or_else(|e| {
+        println!("Error: {}", e);
+        false
+    })
+}
+
+/// Print the directory, session and active character of the campaign.
+fn print_campaign(campaign: &Campaign) {
+    println!(">> {}", "Campaign".bold());
+    println!("   Directory   {}", campaign.dir().display());
+    println!("   Session     {}", campaign.session());
+    match campaign.active() {
+        Some(character) => println!("   Playing     {}", character.name()),
+        None => println!("   Playing     nobody"),
+    }
+    println!(
+        "   {} characters, {} foes, {} fronts, {} clocks, {} rolls",
+        campaign.characters.len(),
+        campaign.encounter.len(),
+        campaign.fronts.len(),
+        campaign.clocks.len(),
+        campaign.rolls.len()
+    );
+}
+
+/// Serve the data over HTTP on the given `host` and `port`.
+fn serve(data: &Data, host: &str, port: &str) -> io::Result<()> {
+    let server = Server::bind(data, &format!("{}:{}", host, port))?;
+    println!("Listening on http://{}:{}/", host, port);
+    server.run()
 }
 
 /// Lists items of the given `category` that match the given `regex`.
@@ -162,13 +547,19 @@ fn list(data: &Data, category: &str, regex: &str) {
     match category {
         "monsters" => data.monsters.list(regex),
         "moves" => data.moves.list(regex),
+        "gm" => data.gm_moves.list(regex),
         "items" => data.items.list(regex),
         "tags" => data.tags.list(regex),
+        "classes" => data.classes.list(regex),
+        "tables" => data.tables.list(regex),
         "all" => {
             data.monsters.list(regex);
             data.moves.list(regex);
+            data.gm_moves.list(regex);
             data.items.list(regex);
             data.tags.list(regex);
+            data.classes.list(regex);
+            data.tables.list(regex);
         }
         re if regex == ".*" => {
             // If category is nothing of the above assume it's a regex
@@ -188,30 +579,1610 @@ fn print_help() {
 COMMANDS:
     help | info            Print this usage information
     quit                   Exit interactive mode
+    create monster         Create a monster by answering the questions of the rules
+    campaign               Show the directory, session and active character of the campaign
+    campaign open DIR      Save the campaign and open the one in DIR
+    character              Show the sheet of the active character
+    character new NAME     Create the character NAME
+    character play NAME    Make the character matching NAME the active one
+    character show NAME    Show the sheet of the character matching NAME
+    character remove NAME  Remove the character matching NAME
+    character list         List all characters
+    character equip ITEM   Wear or wield the item matching ITEM
+    character unequip ITEM Take off the item matching ITEM
+    party                  Show the HP, armor, XP, load and debilities of all characters
+    bonds [CHARACTER] [-w] Show the bonds of the character with everyone, -w writes them
+    damage NAME N [-i]     Deal N damage to the character matching NAME, -i ignores armor
+    heal NAME N            Heal N HP of the character matching NAME
+    debility               Show the debilities of all characters
+    debility add D [CHARACTER]     The character suffers the debility D, i.e. weak
+    debility remove D [CHARACTER]  The character recovers from the debility D
+    steading               Show the cards of all steadings
+    steading new KIND NAME [-r]    Create a village, town, keep or city, -r rolls its stats and tags
+    steading show NAME     Show the card of the steading matching NAME
+    steading prosperity NAME N     Raise the Prosperity of the steading by N, also population and defenses
+    steading tag NAME TAG [PARAMETER]  Add a tag like `trade Riverton` to the steading matching NAME
+    steading untag NAME TAG        Remove the tag matching TAG from the steading
+    steading remove NAME   Remove the steading matching NAME
+    shop [REGEX]           Show the items matching REGEX for sale with their prices
+    shop at [SETTLEMENT]   Shop in a village, town, keep, city or steading, without one everything is for sale
+    shop price ITEM [N]    Set the price of the item matching ITEM for this campaign
+    buy [-s] ITEM [for CHARACTER]  Buy the item matching ITEM, -s rolls Supply to haggle
+    sell ITEM [for CHARACTER]      Sell the item matching ITEM for half its price
+    coins N [CHARACTER]    Give N coins to the active character or the one matching CHARACTER
+    xp N [CHARACTER]       Mark N XP of the active character or the one matching CHARACTER
+    end session            Mark the XP of the end of session questions and start the next session
+    level up [CHARACTER]   Level up the active character or the one matching CHARACTER
+    spell                  Show the spells of the active character
+    spell show SPELL       Show the card of the spell matching SPELL
+    spell learn SPELL      Write the spell matching SPELL into the spellbook
+    spell prepare          Prepare spells up to level + 1
+    spell end SPELL        End the ongoing spell matching SPELL
+    cast SPELL             Cast the prepared spell matching SPELL
+    encounter              Show the foes of the encounter
+    encounter add MONSTER  Add the monster matching MONSTER to the encounter
+    encounter hit N FOE    Deal N damage to the foe matching FOE
+    encounter remove FOE   Remove the foe matching FOE
+    encounter clear        Remove all foes
+    loot MONSTER [-f|-l|-a|-g]  Roll the treasure of the monster matching MONSTER
+    table                  List the random tables
+    table roll NAME [-n N] [-u]  Roll N times on the table NAME, -u never draws an entry twice
+    table show NAME        Show the entries of the table NAME
+    front                  Show the cards of all fronts
+    front new NAME         Create the front NAME
+    front danger FRONT     Add a danger with its grim portents to the front matching FRONT
+    front stake FRONT      Add a stakes question to the front matching FRONT
+    front check REGEX      Check off the first open stakes question matching REGEX
+    front list [REGEX]     List all fronts matching REGEX
+    advance PORTENT        Let the next grim portent matching PORTENT happen
+    clock                  Show all clocks
+    clock new NAME N       Create the clock NAME with N segments
+    clock tick NAME [N]    Fill N more segments of the clock matching NAME
+    clock remove NAME      Remove the clock matching NAME
     item    REGEX          Find the first item matching the given REGEX
     monster REGEX          Find the first monster matching the given REGEX
     move    REGEX          Find the first move matching the given REGEX
+    gm [REGEX]             Find the first move of the GM or the dungeon matching REGEX
+    gm suggest [-e]        Suggest a soft move, -e one fitting the foes of the encounter
     tag     REGEX          Find the first tag matching the given REGEX
+    class   REGEX          Find the first class matching the given REGEX
+    roll    EXPR [LABEL]   Roll the dice of the given d20 EXPR
+    reroll                 Repeat the last roll
+    macro set NAME EXPR    Define a roll macro, i.e. `macro set hack 2d6+$STR`
+    macro list             List all roll macros
+    macro remove NAME      Remove a roll macro
+    seed    N              Seed the dice to make the following rolls reproducible
+    rolls [-e FILE]        Show all rolls of the campaign, or export them to FILE
+    npc [QUERY] [-s]       Generate an NPC of the race or class QUERY, -s saves it as a note
+    note TEXT              Take a note, [[monster:goblin]] links to the goblin
+    notes [REGEX] [-c]     Show the notes matching REGEX, with the cards of their links
+    notes -e FILE [-s N]   Export the notes of the current session or session N to Markdown
+    odds    EXPR           Show the exact odds of the given dice EXPR
     list CATEGORY [REGEX]  List all elements of the given CATEGORY matching REGEX
 
 CATEGORY: One of
   - mon[sters]
   - mov[es]
+  - gm
   - t[ags]
   - i[tems]
+  - c[lasses]
 "#
     );
 }
 
-/// Try to parse the given string into a dice roll
-fn roll_dice<'a>(s: &'a str) {
-    let s = s.trim_left_matches("roll ");
-    die::roll(s);
+/// Roll the d20 expression or macro `expr` and print the result.
+/// Macros are labeled with their name, unless a `label` is given.
+fn roll_dice(macros: &Macros, vars: &Variables, expr: &str, label: Option<&str>) -> Option<Roll> {
+    match macros.expand(expr, vars) {
+        Ok(expanded) => {
+            let label = label.or_else(|| macros.get(expr).map(|_| expr));
+            die::roll(&expanded, label)
+        }
+        Err(fail) => {
+            println!("Error: {}", fail);
+            None
+        }
+    }
+}
+
+/// Set, list or remove roll macros and save them.
+fn edit_macros(macros: &mut Macros, matches: &ArgMatches) -> io::Result<()> {
+    if let Some(matches) = matches.subcommand_matches("set") {
+        let name = matches.value_of("NAME").unwrap();
+        let expr: String = matches.values_of("EXPR").unwrap().collect();
+        macros.set(name, &expr)?;
+    } else if let Some(matches) = matches.subcommand_matches("remove") {
+        let name = matches.value_of("NAME").unwrap();
+        if macros.remove(name).is_none() {
+            println!("No macro {}", name);
+            return Ok(());
+        }
+    } else {
+        macros.list();
+        return Ok(());
+    }
+    match config::macros_file() {
+        Some(path) => macros.save(&path),
+        None => Ok(()),
+    }
+}
+
+/// Show the fronts or create fronts, dangers and stakes.
+/// Returns whether the fronts changed.
+fn edit_fronts<C: Completer>(
+    rl: &mut Editor<C>,
+    fronts: &mut Fronts,
+    matches: &ArgMatches,
+) -> bool {
+    let concat = |args: clap::Values| args.collect::<Vec<_>>().join(" ");
+    if let Some(matches) = matches.subcommand_matches("new") {
+        let name = concat(matches.values_of("NAME").unwrap());
+        fronts.insert(Front::new(&name));
+        return true;
+    } else if let Some(matches) = matches.subcommand_matches("danger") {
+        let re = concat(matches.values_of("FRONT").unwrap());
+        if fronts.find(&re).is_none() {
+            println!("No match");
+            return false;
+        }
+        let danger = match ask_danger(rl) {
+            Some(danger) => danger,
+            None => return false,
+        };
+        if let Some(front) = fronts.find_mut(&re) {
+            front.add_danger(danger);
+            return true;
+        }
+    } else if let Some(matches) = matches.subcommand_matches("stake") {
+        let re = concat(matches.values_of("FRONT").unwrap());
+        if fronts.find(&re).is_none() {
+            println!("No match");
+            return false;
+        }
+        let question = match ask(rl, "What do you want to know?") {
+            Some(ref question) if question.is_empty() => return false,
+            Some(question) => question,
+            None => return false,
+        };
+        if let Some(front) = fronts.find_mut(&re) {
+            front.add_stake(&question);
+            return true;
+        }
+    } else if let Some(matches) = matches.subcommand_matches("check") {
+        let re = concat(matches.values_of("REGEX").unwrap());
+        match fronts.check(&re) {
+            Some(stake) => {
+                println!("Checked off: {}", stake.question());
+                return true;
+            }
+            None => println!("No match"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("list") {
+        fronts.list(matches.value_of("REGEX").unwrap());
+    } else {
+        fronts.iter().for_each(|front| println!("{}", front));
+    }
+    false
+}
+
+/// Let the next grim portent matching `re` happen, false if none matches.
+fn advance(fronts: &mut Fronts, re: &str) -> bool {
+    match fronts.advance(re) {
+        Some(danger) => {
+            if let Some(portent) = danger.last_happened() {
+                println!("{}: {}", danger.name().bold(), portent.text());
+            }
+            if danger.is_doomed() {
+                let doom = format!(" Impending doom: {} ", danger.impending_doom());
+                println!("{}", doom.bold().white().on_red());
+            }
+            true
+        }
+        None => {
+            println!("No match");
+            false
+        }
+    }
+}
+
+/// Show the notes matching the regex with the cards of their links
+/// or export the notes of a session.
+fn show_notes(data: &Data, campaign: &Campaign, matches: &ArgMatches) -> io::Result<()> {
+    let journal = &campaign.journal;
+    if let Some(file) = matches.value_of("export") {
+        let session = match matches.value_of("session") {
+            Some(_) => value_t!(matches, "session", u32)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?,
+            None => campaign.session(),
+        };
+        journal.export(session, file)?;
+        let count = journal.session(session).len();
+        println!("Exported {} notes of session {} to {}", count, session, file);
+        return Ok(());
+    }
+    let re = matches
+        .values_of("REGEX")
+        .map(|re| re.collect::<Vec<_>>().join(" "))
+        .unwrap_or_else(|| String::from(".*"));
+    journal.list(&re);
+    if matches.is_present("cards") {
+        let mut links: Vec<Link> = Vec::new();
+        for (_, note) in journal.search(&re) {
+            for link in note.links() {
+                if !links.contains(&link) {
+                    links.push(link);
+                }
+            }
+        }
+        for link in links {
+            match campaign.card(&link, data) {
+                Some(card) => println!("{}", card),
+                None => println!("No match for [[{}:{}]]", link.category, link.query),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Show the clocks or create, tick and remove them.
+/// Returns whether the clocks changed.
+fn edit_clocks(clocks: &mut Clocks, matches: &ArgMatches) -> io::Result<bool> {
+    let invalid = |e: clap::Error| io::Error::new(io::ErrorKind::InvalidInput, e.message);
+    if let Some(matches) = matches.subcommand_matches("new") {
+        let segments = value_t!(matches, "SEGMENTS", u8).map_err(invalid)?;
+        clocks.insert(Clock::new(matches.value_of("NAME").unwrap(), segments)?);
+        return Ok(true);
+    } else if let Some(matches) = matches.subcommand_matches("tick") {
+        let n = value_t!(matches, "N", u8).map_err(invalid)?;
+        match clocks.tick(matches.value_of("NAME").unwrap(), n) {
+            Some((clock, filled)) => {
+                println!("{}  {}", clock.name().bold(), clock.dial());
+                if filled {
+                    let alert = format!(" The clock {} is full! ", clock.name());
+                    println!("{}", alert.bold().white().on_red());
+                }
+                return Ok(true);
+            }
+            None => println!("No match"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("remove") {
+        if clocks.remove(matches.value_of("NAME").unwrap()).is_some() {
+            return Ok(true);
+        }
+        println!("No match");
+    } else {
+        println!("{}", clocks);
+    }
+    Ok(false)
+}
+
+/// Create, show, play or remove characters.
+/// Returns whether the characters changed.
+fn edit_characters<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    campaign: &mut Campaign,
+    matches: &ArgMatches,
+) -> io::Result<bool> {
+    let concat = |args: clap::Values| args.collect::<Vec<_>>().join(" ");
+    if let Some(matches) = matches.subcommand_matches("new") {
+        let name = concat(matches.values_of("NAME").unwrap());
+        let others = campaign.characters.others(&name);
+        let character = match ask_character(rl, data, &name, &others)? {
+            Some(character) => character,
+            None => return Ok(false),
+        };
+        println!("{}", character);
+        campaign.characters.insert(character);
+        if campaign.active().is_none() {
+            campaign.set_active(&name);
+            println!("Playing {}", name);
+        }
+        return Ok(true);
+    } else if let Some(matches) = matches.subcommand_matches("play") {
+        match campaign.play(&concat(matches.values_of("NAME").unwrap())) {
+            Some(character) => {
+                println!("Playing {}", character.name());
+                return Ok(true);
+            }
+            None => println!("No match"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("show") {
+        match campaign.characters.find(&concat(matches.values_of("NAME").unwrap())) {
+            Some(character) => println!("{}", character),
+            None => println!("No match"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("remove") {
+        match campaign.characters.remove(&concat(matches.values_of("NAME").unwrap())) {
+            Some(character) => {
+                println!("Removed {}", character.name());
+                return Ok(true);
+            }
+            None => println!("No match"),
+        }
+    } else if matches.subcommand_matches("list").is_some() {
+        campaign.characters.list();
+    } else if let Some(matches) = matches.subcommand_matches("equip") {
+        let re = concat(matches.values_of("ITEM").unwrap());
+        let character = match campaign.active_mut() {
+            Some(character) => character,
+            None => {
+                println!("No character is active, use `character play NAME`");
+                return Ok(false);
+            }
+        };
+        if character.equip(&re).is_none() {
+            match data.items.find(&re) {
+                Some(item) => character.add_gear(Gear::new(item).with_equipped(true)),
+                None => {
+                    println!("No match");
+                    return Ok(false);
+                }
+            }
+        }
+        println!("{} has {} Armor", character.name(), character.armor());
+        return Ok(true);
+    } else if let Some(matches) = matches.subcommand_matches("unequip") {
+        let re = concat(matches.values_of("ITEM").unwrap());
+        match campaign.active_mut() {
+            Some(character) => match character.unequip(&re) {
+                Some(gear) => {
+                    println!("Took off {}", gear.name());
+                    return Ok(true);
+                }
+                None => println!("No match"),
+            },
+            None => println!("No character is active, use `character play NAME`"),
+        }
+    } else {
+        match campaign.active() {
+            Some(character) => println!("{}", character),
+            None => println!("No character is active, use `character play NAME`"),
+        }
+    }
+    Ok(false)
+}
+
+/// Ask for the class and scores of the new character `name`,
+/// [None](Option::None) if the user aborts.
+fn ask_character<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    name: &str,
+    others: &[String],
+) -> io::Result<Option<Character>> {
+    println!("\n>> Create {}, Ctrl-D aborts\n", name);
+    let answers: Vec<_> = data.classes.iter().map(|class| class.name()).collect();
+    let class = match choose(rl, "Which class?", &answers) {
+        Some(class) => &data.classes[class],
+        None => return Ok(None),
+    };
+    let alignments: Vec<_> = class.alignments().values().collect();
+    let answers: Vec<String> = alignments
+        .iter()
+        .map(|alignment| format!("{}: {}", alignment.name(), alignment.description()))
+        .collect();
+    let answers: Vec<&str> = answers.iter().map(|answer| answer.as_str()).collect();
+    let alignment = match choose(rl, "Which alignment?", &answers) {
+        Some(alignment) => alignments[alignment].name(),
+        None => return Ok(None),
+    };
+    let stats: Vec<_> = Stat::ALL.iter().map(|stat| stat.abbreviation()).collect();
+    let question = format!(
+        "Assign the scores 16 15 13 12 9 8 to {}",
+        stats.join(" ")
+    );
+    let mut character = loop {
+        let answer = match ask(rl, &question) {
+            Some(answer) => answer,
+            None => return Ok(None),
+        };
+        match Stat::parse_scores(&answer) {
+            Some(scores) => match Character::new(name, class, scores) {
+                Ok(character) => break character.with_alignment(alignment),
+                Err(e) => println!("{}", e),
+            },
+            None => println!("Answer with six scores"),
+        }
+    };
+    if character.knows("Spellbook") && !ask_spellbook(rl, class, &mut character) {
+        return Ok(None);
+    }
+    if others.is_empty() {
+        println!(
+            "Write the bonds once the party is complete with `bonds {} --write`",
+            name
+        );
+    } else if !ask_bonds(rl, class, &mut character, others) {
+        return Ok(None);
+    }
+    Ok(Some(character))
+}
+
+/// The questions the whole party answers at the end of every session.
+const SESSION_QUESTIONS: [&str; 3] = [
+    "Did we learn something new and important about the world?",
+    "Did we overcome a notable monster or enemy?",
+    "Did we loot a memorable treasure?",
+];
+
+/// Get the character matching the optional CHARACTER, the active one without it.
+fn character_arg<'a>(
+    campaign: &'a mut Campaign,
+    matches: &ArgMatches,
+) -> Option<&'a mut Character> {
+    let re = matches
+        .values_of("CHARACTER")
+        .map(|re| re.collect::<Vec<_>>().join(" "));
+    find_character(campaign, re.as_deref())
+}
+
+/// Get the character matching `re`, the active one without it.
+fn find_character<'a>(campaign: &'a mut Campaign, re: Option<&str>) -> Option<&'a mut Character> {
+    let character = campaign.character_mut(re);
+    if character.is_none() {
+        match re {
+            Some(_) => println!("No match"),
+            None => println!("No character is active, use `character play NAME`"),
+        }
+    }
+    character
+}
+
+/// Deal damage to the character matching NAME, who takes the Last Breath at 0 HP.
+/// Returns false if nobody matches.
+fn damage<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    campaign: &mut Campaign,
+    matches: &ArgMatches,
+) -> io::Result<bool> {
+    let damage = value_t!(matches, "N", u8)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?;
+    let name = match campaign.characters.find_mut(matches.value_of("NAME").unwrap()) {
+        Some(character) => {
+            let dealt = character.take_damage(damage, matches.is_present("ignore-armor"));
+            println!(
+                "{} takes {} damage and has {}/{} HP",
+                character.name(),
+                dealt,
+                character.hp(),
+                character.max_hp()
+            );
+            if !character.is_dying() {
+                return Ok(true);
+            }
+            character.name().to_string()
+        }
+        None => {
+            println!("No match");
+            return Ok(false);
+        }
+    };
+    println!("\n >> {} takes the Last Breath", name.bold());
+    if let Some(last_breath) = data.moves.get("last_breath") {
+        println!("{}", last_breath);
+    }
+    if choose(rl, "Roll the Last Breath?", &["Yes", "No"]) != Some(0) {
+        return Ok(true);
+    }
+    if let Some(roll) = die::roll("2d6", Some("Last Breath")) {
+        match roll.total() {
+            10..=12 => println!("{} cheated death", name),
+            7..=9 => println!("Death offers {} a bargain", name),
+            _ => println!("The fate of {} is sealed", name),
+        }
+        campaign.rolls.push(roll);
+    }
+    Ok(true)
+}
+
+/// Heal the character matching NAME, false if nobody matches.
+fn heal(campaign: &mut Campaign, matches: &ArgMatches) -> io::Result<bool> {
+    let hp = value_t!(matches, "N", u8)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?;
+    match campaign.characters.find_mut(matches.value_of("NAME").unwrap()) {
+        Some(character) => {
+            let healed = character.heal(hp);
+            println!(
+                "{} heals {} and has {}/{} HP",
+                character.name(),
+                healed,
+                character.hp(),
+                character.max_hp()
+            );
+            Ok(true)
+        }
+        None => {
+            println!("No match");
+            Ok(false)
+        }
+    }
+}
+
+/// Show the debilities of all characters or add and remove them.
+/// Returns whether a debility was added or removed.
+fn edit_debilities(campaign: &mut Campaign, matches: &ArgMatches) -> bool {
+    let (add, matches) = match matches.subcommand() {
+        ("add", Some(matches)) => (true, matches),
+        ("remove", Some(matches)) => (false, matches),
+        _ => {
+            println!(">> {}", "Debilities".bold());
+            for character in campaign.characters.iter() {
+                let debilities: Vec<_> = character
+                    .debilities()
+                    .iter()
+                    .map(|debility| debility.name())
+                    .collect();
+                println!("   {:<16} {}", character.name(), debilities.join(", "));
+            }
+            return false;
+        }
+    };
+    // The possible values are checked by clap
+    let debility = Debility::from_name(matches.value_of("DEBILITY").unwrap()).unwrap();
+    let character = match character_arg(campaign, matches) {
+        Some(character) => character,
+        None => return false,
+    };
+    let name = debility.name().to_lowercase();
+    let stat = debility.stat().abbreviation();
+    if add {
+        if character.add_debility(debility) {
+            println!("{} is {}, -1 {}", character.name(), name, stat);
+            return true;
+        }
+        println!("{} is already {}", character.name(), name);
+    } else if character.remove_debility(debility) {
+        println!("{} is no longer {}", character.name(), name);
+        return true;
+    } else {
+        println!("{} isn't {}", character.name(), name);
+    }
+    false
+}
+
+/// Create, show, change or remove the steadings.
+/// Returns whether the steadings changed.
+fn edit_steadings<C: Completer>(
+    rl: &mut Editor<C>,
+    campaign: &mut Campaign,
+    matches: &ArgMatches,
+) -> io::Result<bool> {
+    let invalid = |e: clap::Error| io::Error::new(io::ErrorKind::InvalidInput, e.message);
+    let steadings = &mut campaign.steadings;
+    match matches.subcommand() {
+        ("new", Some(matches)) => {
+            let kind = Settlement::from_name(matches.value_of("KIND").unwrap()).unwrap();
+            let name = matches.values_of("NAME").unwrap().collect::<Vec<_>>().join(" ");
+            let others: Vec<String> = steadings
+                .names()
+                .into_iter()
+                .filter(|other| *other != name)
+                .collect();
+            let steading = if matches.is_present("roll") {
+                die::with_rng(|rng| Steading::roll(&name, kind, &others, rng))
+            } else {
+                match ask_steading(rl, Steading::new(&name, kind), &others) {
+                    Some(steading) => steading,
+                    None => return Ok(false),
+                }
+            };
+            println!("{}", steading);
+            steadings.insert(steading);
+            Ok(true)
+        }
+        ("show", Some(matches)) => {
+            match steadings.find(matches.value_of("NAME").unwrap()) {
+                Some(steading) => println!("{}", steading),
+                None => println!("No match"),
+            }
+            Ok(false)
+        }
+        (stat, Some(matches)) if ["prosperity", "population", "defenses"].contains(&stat) => {
+            let n = value_t!(matches, "N", i32).map_err(invalid)?;
+            let steading = match steadings.find_mut(matches.value_of("NAME").unwrap()) {
+                Some(steading) => steading,
+                None => {
+                    println!("No match");
+                    return Ok(false);
+                }
+            };
+            let (stat, value) = match stat {
+                "prosperity" => ("Prosperity", steading.shift_prosperity(n).name()),
+                "population" => ("Population", steading.shift_population(n).name()),
+                _ => ("Defenses", steading.shift_defenses(n).name()),
+            };
+            println!("{} now has {} {}", steading.name(), stat, value.bold());
+            Ok(true)
+        }
+        ("tag", Some(matches)) => {
+            let steading = match steadings.find_mut(matches.value_of("NAME").unwrap()) {
+                Some(steading) => steading,
+                None => {
+                    println!("No match");
+                    return Ok(false);
+                }
+            };
+            let parameter = matches
+                .values_of("PARAMETER")
+                .map(|parameter| parameter.collect::<Vec<_>>().join(" "));
+            let tag = steading.add_tag(matches.value_of("TAG").unwrap(), parameter.as_deref())?;
+            println!("{} is tagged {}", steading.name(), tag);
+            Ok(true)
+        }
+        ("untag", Some(matches)) => {
+            let steading = steadings.find_mut(matches.value_of("NAME").unwrap());
+            match steading.and_then(|s| s.remove_tag(matches.value_of("TAG").unwrap())) {
+                Some(tag) => {
+                    println!("Removed the tag {}", tag);
+                    Ok(true)
+                }
+                None => {
+                    println!("No match");
+                    Ok(false)
+                }
+            }
+        }
+        ("remove", Some(matches)) => {
+            let removed = steadings.remove(matches.value_of("NAME").unwrap()).is_some();
+            if !removed {
+                println!("No match");
+            }
+            Ok(removed)
+        }
+        _ => {
+            if steadings.is_empty() {
+                println!("No steadings, use `steading new KIND NAME`");
+            } else {
+                println!("{}", steadings);
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// Ask for the stats and tags of the `steading`, its stats are the defaults.
+/// Oaths and trade may be with the `others` steadings.
+fn ask_steading<C: Completer>(
+    rl: &mut Editor<C>,
+    steading: Steading,
+    others: &[String],
+) -> Option<Steading> {
+    let prosperity = Prosperity::ALL[choose_or(
+        rl,
+        &format!("Prosperity? Enter for {}", steading.prosperity().name()),
+        &Prosperity::ALL.iter().map(|p| p.name()).collect::<Vec<_>>(),
+        Prosperity::ALL.iter().position(|p| *p == steading.prosperity())?,
+    )?];
+    let population = Population::ALL[choose_or(
+        rl,
+        &format!("Population? Enter for {}", steading.population().name()),
+        &Population::ALL.iter().map(|p| p.name()).collect::<Vec<_>>(),
+        Population::ALL.iter().position(|p| *p == steading.population())?,
+    )?];
+    let defenses = Defenses::ALL[choose_or(
+        rl,
+        &format!("Defenses? Enter for {}", steading.defenses().name()),
+        &Defenses::ALL.iter().map(|d| d.name()).collect::<Vec<_>>(),
+        Defenses::ALL.iter().position(|d| *d == steading.defenses())?,
+    )?];
+    let mut steading = steading.with_stats(prosperity, population, defenses);
+    let tags: Vec<&str> = TAGS.iter().map(|(tag, _)| *tag).collect();
+    let question = format!(
+        "Which tags? A {} usually has {}, Enter for none",
+        steading.kind().name().to_lowercase(),
+        Steading::usual_tags(steading.kind()).join(", ")
+    );
+    for i in choose_many(rl, &question, &tags)? {
+        let (tag, question) = TAGS[i];
+        let parameter = match question {
+            Some(question) if question.contains("steading") && !others.is_empty() => {
+                Some(ask(rl, &format!("{} {}", question, others.join(", ")))?)
+            }
+            Some(question) => Some(ask(rl, question)?),
+            None => None,
+        };
+        if let Err(e) = steading.add_tag(tag, parameter.as_deref()) {
+            println!("Error: {}", e);
+        }
+    }
+    Some(steading)
+}
+
+/// Split `ITEM for CHARACTER` into the item and the optional character.
+fn split_for(args: &str) -> (&str, Option<&str>) {
+    match args.rfind(" for ") {
+        Some(i) => (&args[..i], Some(&args[i + 5..])),
+        None => (args, None),
+    }
+}
+
+/// Show the items for sale or change the settlement and prices of the shop.
+/// Returns whether the shop changed.
+fn shop(data: &Data, campaign: &mut Campaign, matches: &ArgMatches) -> io::Result<bool> {
+    if let Some(matches) = matches.subcommand_matches("at") {
+        let at = matches
+            .values_of("SETTLEMENT")
+            .map(|at| at.collect::<Vec<_>>().join(" "));
+        if !campaign.shop_at(at.as_deref()) {
+            println!("No match");
+            return Ok(false);
+        }
+        let shop = campaign.shop();
+        let steading = shop.steading().and_then(|name| campaign.steadings.get(name));
+        match (steading, shop.settlement()) {
+            (Some(steading), _) => println!(
+                "Shopping in {}, a {} {}",
+                steading.name(),
+                steading.prosperity().name().to_lowercase(),
+                steading.kind().name().to_lowercase()
+            ),
+            (None, Some(settlement)) => {
+                println!("Shopping in the {}", settlement.name().to_lowercase())
+            }
+            (None, None) => println!("Everything is for sale"),
+        }
+        Ok(true)
+    } else if let Some(matches) = matches.subcommand_matches("price") {
+        let item = match data.items.find(matches.value_of("ITEM").unwrap()) {
+            Some(item) => item,
+            None => {
+                println!("No match");
+                return Ok(false);
+            }
+        };
+        let price = match matches.value_of("N") {
+            Some(_) => Some(
+                value_t!(matches, "N", u32)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?,
+            ),
+            None => None,
+        };
+        campaign.shop_mut().set_price(item.key(), price);
+        match campaign.shop().price(item) {
+            Some(price) => println!("{} costs {} coins", item.name(), price),
+            None => println!("{} is not for sale", item.name()),
+        }
+        Ok(true)
+    } else {
+        let re = matches
+            .values_of("REGEX")
+            .map(|re| re.collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+        println!("{}", campaign.shop().card(&data.items.filter(&re)));
+        Ok(false)
+    }
+}
+
+/// Buy the item matching ITEM for the active character or the one after `for`,
+/// rolling Supply for items which aren't readily available or to haggle.
+/// Returns whether the item was bought or Supply was rolled.
+fn buy<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    campaign: &mut Campaign,
+    matches: &ArgMatches,
+) -> io::Result<bool> {
+    let args = matches.values_of("ITEM").unwrap().collect::<Vec<_>>().join(" ");
+    let (re, who) = split_for(&args);
+    let item = match data.items.find(re) {
+        Some(item) => item,
+        None => {
+            println!("No match");
+            return Ok(false);
+        }
+    };
+    let price = match campaign.shop().price(item) {
+        Some(price) => price,
+        None => {
+            println!("{} is not for sale", item.name());
+            return Ok(false);
+        }
+    };
+    let (name, modifier) = match find_character(campaign, who) {
+        Some(character) => (character.name().to_string(), character.modifier(Stat::Cha)),
+        None => return Ok(false),
+    };
+    let sells = campaign.shop().sells(item);
+    let price = if sells && !matches.is_present("supply") {
+        price
+    } else {
+        if !sells {
+            println!("{} is hard to find here, roll Supply", item.name());
+        }
+        let label = format!("Supply: {}", item.name());
+        let roll = match die::roll(&format!("2d6{:+}", modifier), Some(&label)) {
+            Some(roll) => roll,
+            None => return Ok(false),
+        };
+        let total = roll.total();
+        campaign.rolls.push(roll);
+        let price = match campaign.shop().supply(item, total) {
+            Some(price) => price,
+            None => {
+                println!("Miss, there is no {} to be found", item.name());
+                return Ok(true);
+            }
+        };
+        let question = format!("Buy {} for {} coins?", item.name(), price);
+        if choose(rl, &question, &["Yes", "No"]) != Some(0) {
+            return Ok(true);
+        }
+        price
+    };
+    let coins = campaign.characters.get_mut(&name).unwrap().buy(item, price)?;
+    println!(
+        "{} buys {} for {} coins and has {} coins left",
+        name,
+        item.name(),
+        price,
+        coins
+    );
+    Ok(true)
+}
+
+/// Sell the item matching ITEM of the active character or the one after `for`
+/// for half its price. Returns false if nothing was sold.
+fn sell(data: &Data, campaign: &mut Campaign, matches: &ArgMatches) -> bool {
+    let args = matches.values_of("ITEM").unwrap().collect::<Vec<_>>().join(" ");
+    let (re, who) = split_for(&args);
+    let shop = campaign.shop();
+    let character = match find_character(campaign, who) {
+        Some(character) => character,
+        None => return false,
+    };
+    match shop.sell(character, &data.items, re) {
+        Some((gear, price)) => {
+            println!(
+                "{} sells {} for {} coins and has {} coins",
+                character.name(),
+                gear.name(),
+                price,
+                character.coins()
+            );
+            true
+        }
+        None => {
+            println!("{} carries no such item", character.name());
+            false
+        }
+    }
+}
+
+/// Give coins to the active character or the one matching CHARACTER,
+/// false if there is no such character.
+fn give_coins(campaign: &mut Campaign, matches: &ArgMatches) -> io::Result<bool> {
+    let coins = value_t!(matches, "N", i32)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?;
+    match character_arg(campaign, matches) {
+        Some(character) => {
+            character.add_coins(coins);
+            println!("{} has {} coins", character.name(), character.coins());
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Mark the XP of the active character or the one matching CHARACTER,
+/// false if there is no such character.
+fn mark_xp(campaign: &mut Campaign, matches: &ArgMatches) -> io::Result<bool> {
+    let xp = value_t!(matches, "N", i32)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?;
+    let character = match character_arg(campaign, matches) {
+        Some(character) => character,
+        None => return Ok(false),
+    };
+    character.mark_xp(xp);
+    println!(
+        "{} has {}/{} XP",
+        character.name(),
+        character.xp(),
+        character.xp_needed()
+    );
+    if character.can_level_up() {
+        println!("{} can level up", character.name().bold());
+    }
+    Ok(true)
+}
+
+/// Ask the end of session questions, mark the XP of every character
+/// and start the next session. Returns false if the questions were aborted.
+fn end_session<C: Completer>(rl: &mut Editor<C>, data: &Data, campaign: &mut Campaign) -> bool {
+    println!("\n>> End session {}, Ctrl-D aborts\n", campaign.session());
+    let mut party = 0;
+    for question in SESSION_QUESTIONS.iter() {
+        match choose(rl, question, &["Yes", "No"]) {
+            Some(0) => party += 1,
+            Some(_) => (),
+            None => return false,
+        }
+    }
+    let mut marks = Vec::new();
+    let mut resolved = Vec::new();
+    for character in campaign.characters.iter() {
+        let mut xp = party;
+        let alignment = character
+            .find_class(&data.classes)
+            .and_then(|class| character.find_alignment(class));
+        let question = match alignment {
+            Some(alignment) => format!(
+                "Did {} fulfill their alignment? {}: {}",
+                character.name(),
+                alignment.name(),
+                alignment.description()
+            ),
+            None => format!("Did {} fulfill their alignment?", character.name()),
+        };
+        match choose(rl, &question, &["Yes", "No"]) {
+            Some(0) => xp += 1,
+            Some(_) => (),
+            None => return false,
+        }
+        if character.bonds().is_empty() {
+            let question = format!("How many bonds did {} resolve?", character.name());
+            loop {
+                let answer = match ask(rl, &question) {
+                    Some(answer) => answer,
+                    None => return false,
+                };
+                match answer.parse::<i32>() {
+                    Ok(bonds) if bonds >= 0 => {
+                        xp += bonds;
+                        break;
+                    }
+                    _ if answer.is_empty() => break,
+                    _ => println!("Answer with a number"),
+                }
+            }
+            resolved.push(Vec::new());
+        } else {
+            let bonds: Vec<_> = character.bonds().iter().map(|bond| bond.text()).collect();
+            let question = format!("Which bonds did {} resolve? Enter for none", character.name());
+            match choose_many(rl, &question, &bonds) {
+                Some(indices) => resolved.push(indices),
+                None => return false,
+            }
+        }
+        marks.push(xp);
+    }
+    let others: Vec<Vec<String>> = campaign
+        .characters
+        .iter()
+        .map(|character| campaign.characters.others(character.name()))
+        .collect();
+    let characters = campaign.characters.iter_mut().zip(marks).zip(resolved);
+    for (((character, xp), resolved), others) in characters.zip(others) {
+        let resolved = character.resolve_bonds(&resolved);
+        let xp = xp + resolved.len() as i32;
+        character.mark_xp(xp);
+        if character.can_level_up() {
+            println!("{} marks {} XP and can level up", character.name().bold(), xp);
+        } else {
+            println!("{} marks {} XP", character.name(), xp);
+        }
+        if others.is_empty() {
+            continue;
+        }
+        for _ in resolved {
+            if !ask_new_bond(rl, character, &others) {
+                break;
+            }
+        }
+    }
+    println!("Session {} begins", campaign.next_session());
+    true
+}
+
+/// Level up the active character or the one matching CHARACTER
+/// by raising a stat and choosing an advanced move. Returns whether the character leveled up.
+fn level_up<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    campaign: &mut Campaign,
+    matches: &ArgMatches,
+) -> io::Result<bool> {
+    let character = match character_arg(campaign, matches) {
+        Some(character) => character,
+        None => return Ok(false),
+    };
+    if !character.can_level_up() {
+        println!(
+            "{} has {}/{} XP, not enough to level up",
+            character.name(),
+            character.xp(),
+            character.xp_needed()
+        );
+        return Ok(false);
+    }
+    let class = character.find_class(&data.classes).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unknown class {}", character.class()),
+        )
+    })?;
+    let choices = character.advanced_moves(class, &data.moves);
+    if choices.is_empty() {
+        println!("{} has no advanced moves left to choose", character.name());
+        return Ok(false);
+    }
+    println!(
+        "\n>> Level up {} to level {}, Ctrl-D aborts\n",
+        character.name(),
+        character.level() + 1
+    );
+    let stats = character.raisable_stats();
+    let answers: Vec<String> = stats
+        .iter()
+        .map(|stat| format!("{} {}", stat.abbreviation(), character.score(*stat)))
+        .collect();
+    let answers: Vec<&str> = answers.iter().map(|answer| answer.as_str()).collect();
+    let stat = match choose(rl, "Which stat increases by one?", &answers) {
+        Some(stat) => stats[stat],
+        None => return Ok(false),
+    };
+    let answers: Vec<String> = choices
+        .iter()
+        .map(|mv| match mv.replaces() {
+            "" => mv.name().to_string(),
+            replaces => format!("{} (replaces {})", mv.name(), replaces),
+        })
+        .collect();
+    let answers: Vec<&str> = answers.iter().map(|answer| answer.as_str()).collect();
+    let mv = match choose(rl, "Which move? Show one with `move NAME`", &answers) {
+        Some(mv) => choices[mv],
+        None => return Ok(false),
+    };
+    character.level_up(stat, mv)?;
+    if character.knows("Spellbook") {
+        let spells = character.learnable_spells(class);
+        let answers: Vec<_> = spells.iter().map(|spell| spell.name()).collect();
+        if let Some(spell) = choose(rl, "Which spell is new in the spellbook?", &answers) {
+            if let Err(e) = character.learn_spell(spells[spell]) {
+                println!("Error: {}", e);
+            }
+        }
+    }
+    println!("{}", character);
+    Ok(true)
+}
+
+/// Show, learn, prepare or end the spells of the active character.
+/// Returns whether the spells changed.
+fn edit_spells<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    campaign: &mut Campaign,
+    matches: &ArgMatches,
+) -> io::Result<bool> {
+    let concat = |args: clap::Values| args.collect::<Vec<_>>().join(" ");
+    let character = match campaign.active_mut() {
+        Some(character) => character,
+        None => {
+            println!("No character is active, use `character play NAME`");
+            return Ok(false);
+        }
+    };
+    let class = character.find_class(&data.classes).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unknown class {}", character.class()),
+        )
+    })?;
+    if let Some(matches) = matches.subcommand_matches("show") {
+        match class.find_spell(&concat(matches.values_of("SPELL").unwrap())) {
+            Some(spell) => println!("{}", spell),
+            None => println!("No match"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("learn") {
+        match class.find_spell(&concat(matches.values_of("SPELL").unwrap())) {
+            Some(spell) => {
+                character.learn_spell(spell)?;
+                println!("{} wrote {} into the spellbook", character.name(), spell.name());
+                return Ok(true);
+            }
+            None => println!("No match"),
+        }
+    } else if matches.subcommand_matches("prepare").is_some() {
+        let available: Vec<_> = character
+            .available_spells(class)
+            .into_iter()
+            .filter(|spell| spell.level() > 0)
+            .collect();
+        let answers: Vec<String> = available
+            .iter()
+            .map(|spell| format!("{} (level {})", spell.name(), spell.level()))
+            .collect();
+        let answers: Vec<&str> = answers.iter().map(|answer| answer.as_str()).collect();
+        let question = format!(
+            "Which spells, up to {} levels?",
+            character.level() + 1
+        );
+        loop {
+            let chosen = match choose_many(rl, &question, &answers) {
+                Some(chosen) => chosen,
+                None => return Ok(false),
+            };
+            let spells: Vec<_> = chosen.iter().map(|i| available[*i]).collect();
+            match character.prepare(class, &spells) {
+                Ok(()) => break,
+                Err(e) => println!("{}", e),
+            }
+        }
+        println!("{}", character);
+        return Ok(true);
+    } else if let Some(matches) = matches.subcommand_matches("end") {
+        match character.end_ongoing(&concat(matches.values_of("SPELL").unwrap())) {
+            Some(spell) => {
+                println!("{} ends", spell);
+                return Ok(true);
+            }
+            None => println!("No ongoing spell matches"),
+        }
+    } else {
+        println!(">> {}", format!("Spells of {}", character.name()).bold());
+        for spell in character.available_spells(class) {
+            let state = if character.ongoing().iter().any(|s| s == spell.name()) {
+                "ongoing"
+            } else if character.is_prepared(spell.name()) {
+                "prepared"
+            } else {
+                ""
+            };
+            println!("   {:<24} {:>2}  {}", spell.name(), spell.level(), state);
+        }
+        if character.spell_penalty() > 0 {
+            println!("   -{} to cast a spell", character.spell_penalty());
+        }
+    }
+    Ok(false)
+}
+
+/// Cast the prepared spell matching `re` with the Cast a Spell move of the active character
+/// and log the roll. Returns whether the spell was cast.
+fn cast<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    campaign: &mut Campaign,
+    re: &str,
+) -> io::Result<bool> {
+    let (spell, modifier) = {
+        let character = match campaign.active() {
+            Some(character) => character,
+            None => {
+                println!("No character is active, use `character play NAME`");
+                return Ok(false);
+            }
+        };
+        let class = character.find_class(&data.classes).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unknown class {}", character.class()),
+            )
+        })?;
+        let modifier = character.cast_modifier(class).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} can't cast spells", character.name()),
+            )
+        })?;
+        match character.find_prepared(class, re) {
+            Some(spell) => (spell.clone(), modifier),
+            None => {
+                println!("No prepared spell matches");
+                return Ok(false);
+            }
+        }
+    };
+    println!("{}", spell);
+    let label = format!("Cast a Spell: {}", spell.name());
+    let roll = match die::roll(&format!("2d6{:+}", modifier), Some(&label)) {
+        Some(roll) => roll,
+        None => return Ok(false),
+    };
+    let total = roll.total();
+    campaign.rolls.push(roll);
+    let cost = if total >= 10 {
+        println!("{} is cast", spell.name());
+        None
+    } else if total >= 7 {
+        println!("{} is cast, but choose one", spell.name());
+        let answers: Vec<_> = CastCost::ALL.iter().map(|cost| cost.description()).collect();
+        let cost = choose(rl, "Which one?", &answers).map(|i| CastCost::ALL[i]);
+        if let None | Some(CastCost::Attention) = cost {
+            println!("The GM will tell you how");
+        }
+        cost
+    } else {
+        println!("Miss, the GM makes a move");
+        return Ok(true);
+    };
+    let character = campaign.active_mut().unwrap();
+    character.cast(&spell, cost);
+    if spell.is_ongoing() {
+        println!(
+            "{} is ongoing, -{} to cast a spell",
+            spell.name(),
+            character.spell_penalty()
+        );
+    }
+    Ok(true)
+}
+
+/// Ask for the three first level spells in the spellbook of a new wizard,
+/// false if the user aborts.
+fn ask_spellbook<C: Completer>(
+    rl: &mut Editor<C>,
+    class: &Class,
+    character: &mut Character,
+) -> bool {
+    let spells = character.learnable_spells(class);
+    let answers: Vec<_> = spells.iter().map(|spell| spell.name()).collect();
+    loop {
+        match choose_many(rl, "Which three spells are in the spellbook?", &answers) {
+            Some(ref chosen) if chosen.len() == 3 => {
+                // Write all three or none, i.e. if a spell is chosen twice
+                let chosen: Vec<_> = chosen.iter().map(|i| spells[*i]).collect();
+                match character.learn_spells(&chosen) {
+                    Ok(()) => return true,
+                    Err(e) => println!("Error: {}", e),
+                }
+            }
+            Some(_) => println!("Choose three spells"),
+            None => return false,
+        }
+    }
+}
+
+/// Ask with whom the character fills in each bond template of the `class`,
+/// false if the user aborts.
+fn ask_bonds<C: Completer>(
+    rl: &mut Editor<C>,
+    class: &Class,
+    character: &mut Character,
+    others: &[String],
+) -> bool {
+    let mut answers: Vec<_> = others.iter().map(|other| other.as_str()).collect();
+    answers.push("Nobody");
+    for template in class.bonds() {
+        match choose(rl, template, &answers) {
+            Some(i) if i < others.len() => character.add_bond(Bond::new(template, &others[i])),
+            Some(_) => (),
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Ask for a new bond of the character with one of the `others`,
+/// false if the user aborts.
+fn ask_new_bond<C: Completer>(
+    rl: &mut Editor<C>,
+    character: &mut Character,
+    others: &[String],
+) -> bool {
+    let answers: Vec<_> = others.iter().map(|other| other.as_str()).collect();
+    let question = format!("With whom does {} write a new bond?", character.name());
+    let other = match choose(rl, &question, &answers) {
+        Some(i) => &others[i],
+        None => return false,
+    };
+    match ask(rl, "Write the bond, __________ is replaced by the name") {
+        Some(ref text) if !text.is_empty() => {
+            character.add_bond(Bond::new(text, other));
+            true
+        }
+        Some(_) => true,
+        None => false,
+    }
+}
+
+/// Show the bonds of the active character or the one matching CHARACTER,
+/// or write them with the templates of the class. Returns whether bonds were written.
+fn edit_bonds<C: Completer>(
+    rl: &mut Editor<C>,
+    data: &Data,
+    campaign: &mut Campaign,
+    matches: &ArgMatches,
+) -> bool {
+    let name = match character_arg(campaign, matches) {
+        Some(character) => character.name().to_string(),
+        None => return false,
+    };
+    let mut written = false;
+    if matches.is_present("write") {
+        let others = campaign.characters.others(&name);
+        let character = campaign.characters.get_mut(&name).unwrap();
+        match character.find_class(&data.classes) {
+            _ if others.is_empty() => println!("There is nobody to bond with yet"),
+            Some(class) => {
+                // Bonds written before an abort are kept
+                ask_bonds(rl, class, character, &others);
+                written = true;
+            }
+            None => println!("Unknown class {}", character.class()),
+        }
+    }
+    if let Some(web) = campaign.characters.web(&name) {
+        println!("{}", web);
+    }
+    written
+}
+
+/// Roll the treasure of the monster matching MONSTER and log the rolls.
+/// With `--give` the active character or the one after `for` takes the coins and items.
+/// Returns false if no monster matches.
+fn loot(data: &Data, campaign: &mut Campaign, matches: &ArgMatches) -> bool {
+    let args = matches.values_of("MONSTER").unwrap().collect::<Vec<_>>().join(" ");
+    let (re, who) = if matches.is_present("give") {
+        split_for(&args)
+    } else {
+        (args.as_str(), None)
+    };
+    let monster = match data.monsters.find(re) {
+        Some(monster) => monster,
+        None => {
+            println!("No match");
+            return false;
+        }
+    };
+    let hoard = Hoard::of(monster)
+        .with_far(matches.is_present("far"))
+        .with_lord(matches.is_present("lord"))
+        .with_ancient(matches.is_present("ancient"));
+    let treasure = die::with_rng(|rng| Treasure::roll(monster, hoard, &data.items, rng));
+    println!("{}", treasure);
+    for roll in treasure.labeled_rolls() {
+        campaign.rolls.push(roll);
+    }
+    if !matches.is_present("give") {
+        return true;
+    }
+    let character = match find_character(campaign, who) {
+        Some(character) => character,
+        None => return true,
+    };
+    for item in character.take_treasure(&treasure, &data.items) {
+        println!("{} takes {}", character.name(), item.name());
+    }
+    println!("{} has {} coins", character.name(), character.coins());
+    true
+}
+
+/// List the random tables, roll on the one matching NAME or show its entries.
+fn roll_table(data: &Data, matches: &ArgMatches) -> io::Result<()> {
+    let (name, matches) = match matches.subcommand() {
+        (name, Some(matches)) => (name, matches),
+        _ => {
+            data.tables.list(".*");
+            return Ok(());
+        }
+    };
+    let table = match data.tables.find(matches.value_of("NAME").unwrap()) {
+        Some(table) => table,
+        None => {
+            println!("No match");
+            return Ok(());
+        }
+    };
+    if name == "show" {
+        println!("{}", table);
+    } else {
+        let times = value_t!(matches, "times", usize)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?;
+        let unique = matches.is_present("unique");
+        let roll = die::with_rng(|rng| table.roll(&data.tables, times, unique, rng))?;
+        println!("{}", roll);
+    }
+    Ok(())
+}
+
+/// Add, hit or remove the foes of the encounter.
+/// Returns whether the encounter changed.
+fn edit_encounter(data: &Data, campaign: &mut Campaign, matches: &ArgMatches) -> io::Result<bool> {
+    let concat = |args: clap::Values| args.collect::<Vec<_>>().join(" ");
+    let encounter = &mut campaign.encounter;
+    if let Some(matches) = matches.subcommand_matches("add") {
+        match data.monsters.find(&concat(matches.values_of("MONSTER").unwrap())) {
+            Some(monster) => {
+                println!("{} joins the fight", encounter.add(monster).name());
+                return Ok(true);
+            }
+            None => println!("No match"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("hit") {
+        let damage = value_t!(matches, "DAMAGE", u8)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.message))?;
+        match encounter.damage(&concat(matches.values_of("FOE").unwrap()), damage) {
+            Some(foe) => {
+                if foe.is_defeated() {
+                    println!("{} is defeated", foe.name().bold());
+                } else {
+                    println!("{} has {}/{} HP", foe.name(), foe.hp(), foe.max_hp());
+                }
+                return Ok(true);
+            }
+            None => println!("No match"),
+        }
+    } else if let Some(matches) = matches.subcommand_matches("remove") {
+        match encounter.remove(&concat(matches.values_of("FOE").unwrap())) {
+            Some(foe) => {
+                println!("{} left the fight", foe.name());
+                return Ok(true);
+            }
+            None => println!("No match"),
+        }
+    } else if matches.subcommand_matches("clear").is_some() {
+        encounter.clear();
+        return Ok(true);
+    } else {
+        println!("{}", encounter);
+    }
+    Ok(false)
+}
+
+/// Ask for a danger with its grim portents, [None](Option::None) if the user aborts.
+fn ask_danger<C: Completer>(rl: &mut Editor<C>) -> Option<Danger> {
+    let name = ask(rl, "What is the name of the danger?")?;
+    if name.is_empty() {
+        return None;
+    }
+    let kind = ask(
+        rl,
+        "What kind of danger is it? (i.e. Ambitious Organization)",
+    )?;
+    let impulse = ask(rl, "What is its impulse?")?;
+    let mut danger = Danger::new(&name, &kind, &impulse);
+    let portents = ask(rl, "What are its grim portents? (Separated by ';')")?;
+    for portent in portents.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        danger = danger.portent(portent);
+    }
+    Some(danger.doom(&ask(rl, "What is its impending doom?")?))
+}
+
+/// Create a monster by asking the questions of the rules,
+/// [None](Option::None) if the user aborts.
+fn create_monster<C: Completer>(rl: &mut Editor<C>) -> Option<Monster> {
+    println!("\n>> Create a monster, Ctrl-D aborts\n");
+    let name = ask(rl, "What is its name?")?;
+    if name.is_empty() {
+        return None;
+    }
+    let monster = ask_monster(rl, MonsterBuilder::new(&name))?.build();
+    println!("{}", monster);
+    Some(monster)
+}
+
+/// Save the `monster` to the homebrew monsters.
+fn save_monster(monster: &Monster) -> io::Result<()> {
+    match config::monsters_file() {
+        Some(path) => {
+            let mut monsters = if path.exists() {
+                Monsters::parse(File::open(&path)?)?
+            } else {
+                Monsters::default()
+            };
+            monsters.insert(monster.clone());
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            monsters.save(File::create(&path)?)?;
+            println!("Saved to {}", path.display());
+        }
+        None => println!("No configuration directory, the monster is only kept until you quit"),
+    }
+    Ok(())
+}
+
+/// Ask the questions of the rules to build the monster,
+/// [None](Option::None) if the user aborts.
+fn ask_monster<C: Completer>(
+    rl: &mut Editor<C>,
+    mut builder: MonsterBuilder,
+) -> Option<MonsterBuilder> {
+    let moves = ask(rl, "What is it known to do? (Moves separated by ';')")?;
+    for monster_move in moves.split(';').map(str::trim).filter(|m| !m.is_empty()) {
+        builder = builder.monster_move(monster_move);
+    }
+    builder = builder.instinct(&ask(rl, "What does it want that causes problems?")?);
+    let answers: Vec<_> = Organization::ALL.iter().map(|o| o.answer()).collect();
+    let organization = choose(rl, "How does it usually hunt or fight?", &answers)?;
+    let answers: Vec<_> = Size::ALL.iter().map(|s| s.answer()).collect();
+    let size = choose(rl, "How big is it?", &answers)?;
+    let answers: Vec<_> = Defense::ALL.iter().map(|d| d.answer()).collect();
+    let defense = choose(rl, "What is its most important defense?", &answers)?;
+    builder = builder
+        .organization(Organization::ALL[organization])
+        .size(Size::ALL[size])
+        .defense(Defense::ALL[defense]);
+    let answers: Vec<_> = KnownFor::ALL.iter().map(|k| k.answer()).collect();
+    for i in choose_many(rl, "What is it known for?", &answers)? {
+        builder = builder.known_for(KnownFor::ALL[i]);
+    }
+    let attack = ask(rl, "What is its most common form of attack?")?;
+    if !attack.is_empty() {
+        builder = builder.attack(&attack);
+    }
+    for i in choose_many(rl, "Can it attack from a distance?", &["Near", "Far"])? {
+        builder = builder.range(["near", "far"][i]);
+    }
+    let answers: Vec<_> = Trait::ALL.iter().map(|t| t.answer()).collect();
+    for i in choose_many(rl, "Which of these describe it?", &answers)? {
+        builder = builder.describe(Trait::ALL[i]);
+    }
+    let qualities = ask(rl, "Special qualities? (Separated by ',')")?;
+    for quality in qualities.split(',').map(str::trim).filter(|q| !q.is_empty()) {
+        builder = builder.quality(quality);
+    }
+    Some(builder.description(&ask(rl, "Describe it")?))
+}
+
+/// Ask the user `question` and return the answer, [None](Option::None) if the user aborts.
+fn ask<C: Completer>(rl: &mut Editor<C>, question: &str) -> Option<String> {
+    println!("{}", question);
+    rl.readline("   ").ok().map(|answer| answer.trim().to_string())
+}
+
+/// Ask the user to choose one of the `answers` and return its index.
+fn choose<C: Completer>(rl: &mut Editor<C>, question: &str, answers: &[&str]) -> Option<usize> {
+    loop {
+        let indices = choose_many(rl, question, answers)?;
+        if indices.len() == 1 {
+            return Some(indices[0]);
+        }
+        println!("Choose exactly one answer");
+    }
+}
+
+/// Ask the user to choose one of the `answers` and return its index,
+/// the index `default` if the user just hits Enter.
+fn choose_or<C: Completer>(
+    rl: &mut Editor<C>,
+    question: &str,
+    answers: &[&str],
+    default: usize,
+) -> Option<usize> {
+    loop {
+        let indices = choose_many(rl, question, answers)?;
+        match indices.len() {
+            0 => return Some(default),
+            1 => return Some(indices[0]),
+            _ => println!("Choose exactly one answer"),
+        }
+    }
+}
+
+/// Ask the user to choose any of the `answers` and return their indices.
+fn choose_many<C: Completer>(
+    rl: &mut Editor<C>,
+    question: &str,
+    answers: &[&str],
+) -> Option<Vec<usize>> {
+    loop {
+        println!("{}", question);
+        for (i, answer) in answers.iter().enumerate() {
+            println!("   {:>2}) {}", i + 1, answer);
+        }
+        let line = rl.readline("   ").ok()?;
+        let indices: Result<Vec<usize>, _> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|i| !i.is_empty())
+            .map(|i| i.parse::<usize>())
+            .collect();
+        match indices {
+            Ok(ref indices) if indices.iter().all(|i| *i >= 1 && *i <= answers.len()) => {
+                return Some(indices.iter().map(|i| i - 1).collect())
+            }
+            _ => println!("Answer with the numbers of the answers"),
+        }
+    }
 }
 
 /// Search for an item
 fn search_item(data: &Data, re: &str) {
-    let item = re.trim_left_matches("item ");
+    let item = re.trim_start_matches("item ");
     let item = data.items.find(item);
     match item {
         Some(item) => println!("{}", item),
@@ -221,7 +2192,7 @@ fn search_item(data: &Data, re: &str) {
 
 /// Search for a monster
 fn search_monster(data: &Data, re: &str) {
-    let monster = re.trim_left_matches("monster ");
+    let monster = re.trim_start_matches("monster ");
     let monster = data.monsters.find(monster);
     match monster {
         Some(monster) => println!("{}", monster),
@@ -231,7 +2202,7 @@ fn search_monster(data: &Data, re: &str) {
 
 /// Search for a move
 fn search_move(data: &Data, re: &str) {
-    let mv = re.trim_left_matches("move ");
+    let mv = re.trim_start_matches("move ");
     let mv = data.moves.find(mv);
     match mv {
         Some(mv) => println!("{}", mv),
@@ -239,12 +2210,52 @@ fn search_move(data: &Data, re: &str) {
     }
 }
 
+/// Search for a move of the GM or the dungeon
+fn search_gm_move(data: &Data, re: &str) {
+    match data.gm_moves.find(re) {
+        Some(mv) => println!("{}", mv),
+        None => println!("No match"),
+    }
+}
+
+/// Suggest a soft move, with `encounter` one fitting the foes still standing.
+fn suggest_gm_move(data: &Data, campaign: &Campaign, encounter: bool) {
+    let monsters: Vec<&Monster> = if encounter {
+        campaign
+            .encounter
+            .iter()
+            .filter(|foe| !foe.is_defeated())
+            .filter_map(|foe| data.monsters.get(foe.monster()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if encounter && monsters.is_empty() {
+        println!("No foes, use `encounter add MONSTER`");
+        return;
+    }
+    match die::with_rng(|rng| data.gm_moves.suggest(&monsters, rng)) {
+        Some(suggestion) => println!("{}", suggestion),
+        None => println!("No match"),
+    }
+}
+
 /// Search for a tag
 fn search_tag(data: &Data, re: &str) {
-    let tag = re.trim_left_matches("tag ");
+    let tag = re.trim_start_matches("tag ");
     let tag = data.tags.find(tag);
     match tag {
         Some(tag) => println!("{}", tag),
         None => println!("No match"),
     }
 }
+
+/// Search for a class
+fn search_class(data: &Data, re: &str) {
+    let class = re.trim_start_matches("class ");
+    let class = data.classes.find(class);
+    match class {
+        Some(class) => println!("{}", class),
+        None => println!("No match"),
+    }
+}
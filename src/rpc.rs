@@ -0,0 +1,313 @@
+//! JSON-RPC over stdio
+//!
+//! Reads newline-delimited [JSON-RPC 2.0](https://www.jsonrpc.org/specification) requests
+//! and writes one response per line. The [Data](Data) stays loaded for the whole session.
+//!
+//! | Method     | Params                                    | Result                                  |
+//! | ---        | ---                                       | ---                                     |
+//! | `search`   | `{"category": "monsters", "query": "RE"}` | First element matching `RE` or `null`   |
+//! | `get`      | `{"category": "moves", "key": "KEY"}`     | Element with the given key or `null`    |
+//! | `list`     | `{"category": "all", "query": "RE"}`      | Names of all matching elements          |
+//! | `roll`     | `{"expr": "2d6+1"}`                       | `{"expr": "2d6+1", "total": 9}`         |
+//! | `complete` | `{"line": "monster drag", "pos": 12}`     | `{"start": 8, "candidates": [...]}`     |
+//!
+//! The category is one of `monsters`, `moves`, `gm`, `items`, `tags`, `classes` and `tables`.
+//!
+//! ```text
+//! --> {"jsonrpc": "2.0", "id": 1, "method": "roll", "params": {"expr": "1d1+2"}}
+//! <-- {"id":1,"jsonrpc":"2.0","result":{"expr":"1d1+2","total":3}}
+//! ```
+
+use completion::HeroCompleter;
+use data::Data;
+use die;
+use regex::Regex;
+use rustyline::completion::Completer;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json;
+use serde_json::Value;
+use std::io;
+use std::io::{BufRead, Write};
+
+/// Categories that can be searched.
+const CATEGORIES: [&str; 7] = [
+    "monsters", "moves", "gm", "items", "tags", "classes", "tables",
+];
+
+/// Invalid JSON was received.
+const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request.
+const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters.
+const INVALID_PARAMS: i64 = -32602;
+
+/// A JSON-RPC request.
+#[derive(Deserialize, Debug)]
+struct Request {
+    /// Id of the request, `None` for notifications.
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A JSON-RPC error.
+#[derive(Serialize, Debug, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    category: String,
+    query: String,
+}
+
+#[derive(Deserialize)]
+struct GetParams {
+    category: String,
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    #[serde(default = "all")]
+    category: String,
+    #[serde(default = "match_all")]
+    query: String,
+}
+
+#[derive(Deserialize)]
+struct RollParams {
+    expr: String,
+}
+
+#[derive(Deserialize)]
+struct CompleteParams {
+    line: String,
+    pos: Option<usize>,
+}
+
+/// Answer requests read line by line from `input` until it ends.
+pub fn serve<R: BufRead, W: Write>(data: &Data, input: R, mut output: W) -> io::Result<()> {
+    let completer = HeroCompleter::new(data);
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(data, &completer, &line) {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle a single request `line` and return the response.
+/// Notifications, requests without `id`, are not answered.
+fn handle(data: &Data, completer: &HeroCompleter, line: &str) -> Option<String> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(response(Value::Null, Err(error(PARSE_ERROR, e)))),
+    };
+    let request: Request = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => return Some(response(Value::Null, Err(error(INVALID_REQUEST, e)))),
+    };
+    let result = match request.method.as_str() {
+        "search" => params(request.params).and_then(|p| search(data, p)),
+        "get" => params(request.params).and_then(|p| get(data, p)),
+        "list" => params(request.params).and_then(|p| list(data, p)),
+        "roll" => params(request.params).and_then(roll),
+        "complete" => params(request.params).and_then(|p| complete(completer, p)),
+        method => Err(error(
+            METHOD_NOT_FOUND,
+            format!("Unknown method {}", method),
+        )),
+    };
+    request.id.map(|id| response(id, result))
+}
+
+/// Find the first element of a category matching the query.
+fn search(data: &Data, p: SearchParams) -> Result<Value, RpcError> {
+    regex(&p.query)?;
+    match p.category.as_str() {
+        "monsters" => to_value(data.monsters.find(&p.query)),
+        "moves" => to_value(data.moves.find(&p.query)),
+        "gm" => to_value(data.gm_moves.find(&p.query)),
+        "items" => to_value(data.items.find(&p.query)),
+        "tags" => to_value(data.tags.find(&p.query)),
+        "classes" => to_value(data.classes.find(&p.query)),
+        "tables" => to_value(data.tables.find(&p.query)),
+        category => Err(unknown_category(category)),
+    }
+}
+
+/// Get the element of a category with the given key.
+fn get(data: &Data, p: GetParams) -> Result<Value, RpcError> {
+    match p.category.as_str() {
+        "monsters" => to_value(data.monsters.get(&p.key)),
+        "moves" => to_value(data.moves.get(&p.key)),
+        "gm" => to_value(data.gm_moves.get(&p.key)),
+        "items" => to_value(data.items.get(&p.key)),
+        "tags" => to_value(data.tags.get(&p.key)),
+        "classes" => to_value(data.classes.get(&p.key)),
+        "tables" => to_value(data.tables.get(&p.key)),
+        category => Err(unknown_category(category)),
+    }
+}
+
+/// List the names of all matching elements per category.
+fn list(data: &Data, p: ListParams) -> Result<Value, RpcError> {
+    regex(&p.query)?;
+    let categories = match p.category.as_str() {
+        "all" => CATEGORIES.to_vec(),
+        category if CATEGORIES.contains(&category) => vec![category],
+        category => return Err(unknown_category(category)),
+    };
+    let re = p.query.as_str();
+    let map = categories
+        .into_iter()
+        .map(|category| {
+            let names: Vec<&str> = match category {
+                "monsters" => data.monsters.filter(re).iter().map(|m| m.name()).collect(),
+                "moves" => data.moves.filter(re).iter().map(|m| m.name()).collect(),
+                "gm" => data.gm_moves.filter(re).iter().map(|m| m.name()).collect(),
+                "items" => data.items.filter(re).iter().map(|i| i.name()).collect(),
+                "tags" => data.tags.filter(re).iter().map(|t| t.name()).collect(),
+                "classes" => data.classes.filter(re).iter().map(|c| c.name()).collect(),
+                _ => data.tables.filter(re).iter().map(|t| t.name()).collect(),
+            };
+            (category.to_string(), json!(names))
+        })
+        .collect();
+    Ok(Value::Object(map))
+}
+
+/// Roll a d20 expression.
+fn roll(p: RollParams) -> Result<Value, RpcError> {
+    die::evaluate(&p.expr)
+        .map(|total| json!({ "expr": p.expr, "total": total }))
+        .map_err(|fail| error(INVALID_PARAMS, fail))
+}
+
+/// Complete a line of the interactive mode.
+fn complete(completer: &HeroCompleter, p: CompleteParams) -> Result<Value, RpcError> {
+    let pos = p.pos.unwrap_or(p.line.len()).min(p.line.len());
+    completer
+        .complete(&p.line, pos)
+        .map(|(start, candidates)| json!({ "start": start, "candidates": candidates }))
+        .map_err(|e| error(INVALID_PARAMS, format!("{:?}", e)))
+}
+
+/// Create the response to the request with the given `id`.
+fn response(id: Value, result: Result<Value, RpcError>) -> String {
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    };
+    response.to_string()
+}
+
+/// Deserialize the parameters of a method.
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| error(INVALID_PARAMS, e))
+}
+
+/// Serialize the result of a method.
+fn to_value<T: Serialize>(result: T) -> Result<Value, RpcError> {
+    Ok(serde_json::to_value(result).unwrap_or(Value::Null))
+}
+
+/// Make sure the `query` is a valid regex.
+fn regex(query: &str) -> Result<(), RpcError> {
+    Regex::new(query)
+        .map(|_| ())
+        .map_err(|e| error(INVALID_PARAMS, e))
+}
+
+fn unknown_category(category: &str) -> RpcError {
+    error(INVALID_PARAMS, format!("Unknown category {}", category))
+}
+
+fn error<S: ToString>(code: i64, message: S) -> RpcError {
+    RpcError {
+        code,
+        message: message.to_string(),
+    }
+}
+
+/// Simple helper function for Serde to return the String `all`.
+fn all() -> String {
+    String::from("all")
+}
+
+/// Simple helper function for Serde to return a regex matching everything.
+fn match_all() -> String {
+    String::from(".*")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::DataPaths;
+
+    fn rpc(input: &str) -> Vec<Value> {
+        let data = Data::from(&DataPaths::default()).unwrap();
+        let mut output = Vec::new();
+        serve(&data, input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn methods() {
+        let answers = rpc(r#"
+            {"jsonrpc": "2.0", "id": 1, "method": "search", "params": {"category": "monsters", "query": "apocalypse"}}
+            {"jsonrpc": "2.0", "id": 2, "method": "get", "params": {"category": "tags", "key": "ammo"}}
+            {"jsonrpc": "2.0", "id": 3, "method": "list", "params": {"category": "monsters", "query": "^dragon$"}}
+            {"jsonrpc": "2.0", "id": 4, "method": "roll", "params": {"expr": "3d1-1"}}
+            {"jsonrpc": "2.0", "id": 5, "method": "complete", "params": {"line": "monster dragon t"}}
+            {"jsonrpc": "2.0", "id": 6, "method": "search", "params": {"category": "gm", "query": "deal damage"}}
+            {"jsonrpc": "2.0", "id": 7, "method": "get", "params": {"category": "classes", "key": "cleric"}}
+            {"jsonrpc": "2.0", "method": "roll", "params": {"expr": "1d6"}}
+        "#);
+        assert_eq!(answers.len(), 7);
+        assert_eq!(answers[0]["result"]["name"], "Apocalypse Dragon");
+        assert_eq!(answers[1]["result"]["name"], "n ammo");
+        assert_eq!(answers[2]["result"], json!({ "monsters": ["Dragon"] }));
+        assert_eq!(answers[3]["result"], json!({ "expr": "3d1-1", "total": 2 }));
+        assert_eq!(
+            answers[4]["result"],
+            json!({ "start": 8, "candidates": ["Dragon Turtle"] })
+        );
+        assert_eq!(answers[5]["result"]["key"], "deal_damage");
+        assert_eq!(answers[6]["result"]["name"], "Cleric");
+    }
+
+    #[test]
+    fn errors() {
+        let answers = rpc(r#"
+            {"jsonrpc": "2.0", "id": 1, "method": "nope"}
+            {"jsonrpc": "2.0", "id": 2, "method": "search", "params": {"category": "spells", "query": "x"}}
+            {"jsonrpc": "2.0", "id": 3, "method": "list", "params": {"query": "("}}
+            {"jsonrpc": "2.0", "id": 4, "method": "roll"}
+            no json
+        "#);
+        assert_eq!(answers[0]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(answers[1]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(answers[2]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(answers[3]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(answers[4]["error"]["code"], PARSE_ERROR);
+        assert_eq!(answers[4]["id"], Value::Null);
+    }
+}
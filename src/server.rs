@@ -0,0 +1,431 @@
+//! HTTP server
+//!
+//! Serves the data as JSON and the cards as HTML, i.e. to phones in the local network.
+//!
+//! | Endpoint                     | Result                                                  |
+//! | ---                          | ---                                                     |
+//! | `/CATEGORY?q=REGEX`          | All elements of the CATEGORY whose name matches REGEX   |
+//! | `/CATEGORY/KEY`              | The element of the CATEGORY with the given KEY          |
+//! | `/list/CATEGORY?q=REGEX`     | Names of the matching elements, CATEGORY may be `all`   |
+//! | `/roll?expr=EXPR`            | The total of the d20 expression EXPR                    |
+//! | `/html/...`                  | Same as above, but rendered as HTML cards               |
+//!
+//! CATEGORY is one of `monsters`, `moves`, `gm`, `items`, `tags`, `classes` and `tables`.
+
+use data::card::{concat, escape_html, Card};
+use data::Data;
+use die;
+use regex::Regex;
+use serde::Serialize;
+use serde_json;
+use serde_json::Value;
+use std::io;
+use std::io::{Error, ErrorKind::Other};
+use std::net::SocketAddr;
+use tiny_http;
+use tiny_http::{Header, ListenAddr, Request, Response};
+
+/// All categories that can be queried.
+pub const CATEGORIES: [&str; 7] = [
+    "monsters", "moves", "gm", "items", "tags", "classes", "tables",
+];
+
+/// Style of the HTML pages.
+const STYLE: &str = "body { font-family: sans-serif; background: #eee; }
+.card { background: #fff; border: 2px solid #222; margin: 1em auto; max-width: 40em; padding: 0.2em 0.8em; }
+.line { display: flex; justify-content: space-between; }
+hr.heavy { border: 1px solid #222; }
+hr.light { border: 0; border-top: 1px solid #aaa; }";
+
+/// HTTP server answering requests about the wrapped [Data](Data).
+pub struct Server<'a> {
+    data: &'a Data,
+    http: tiny_http::Server,
+}
+
+/// The answer to a request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    /// HTTP status code.
+    pub status: u16,
+    /// Content type of the `body`.
+    pub content_type: &'static str,
+    /// Content of the reply.
+    pub body: String,
+}
+
+/// Format of the reply.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Html,
+}
+
+impl<'a> Server<'a> {
+    /// Create a server listening on the given `addr`, i.e. `0.0.0.0:8080`.
+    pub fn bind(data: &'a Data, addr: &str) -> io::Result<Self> {
+        let http = tiny_http::Server::http(addr).map_err(|e| Error::new(Other, e.to_string()))?;
+        Ok(Server { data, http })
+    }
+    /// Get the address the server is listening on.
+    pub fn addr(&self) -> Option<SocketAddr> {
+        match self.http.server_addr() {
+            ListenAddr::IP(addr) => Some(addr),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+    /// Answer requests forever.
+    /// A failed answer, i.e. to a client which disconnected, is logged and the server keeps serving.
+    pub fn run(&self) -> io::Result<()> {
+        loop {
+            let request = self.http.recv()?;
+            if let Err(e) = self.answer(request) {
+                eprintln!("Error: {}", e);
+            }
+        }
+    }
+    /// Wait for the next request and answer it.
+    pub fn handle(&self) -> io::Result<()> {
+        let request = self.http.recv()?;
+        self.answer(request)
+    }
+    /// Answer the given `request`.
+    fn answer(&self, request: Request) -> io::Result<()> {
+        let reply = route(self.data, request.url());
+        let content_type =
+            Header::from_bytes("Content-Type", reply.content_type).expect("Static header is valid");
+        let response = Response::from_string(reply.body)
+            .with_status_code(reply.status)
+            .with_header(content_type);
+        request.respond(response)
+    }
+}
+
+impl Reply {
+    /// Create a JSON reply.
+    fn json(status: u16, value: &Value) -> Self {
+        Reply {
+            status,
+            content_type: "application/json; charset=utf-8",
+            body: value.to_string(),
+        }
+    }
+    /// Create a HTML page showing the given `cards`.
+    fn html(status: u16, title: &str, cards: &[Card]) -> Self {
+        let cards = concat(cards.iter().map(|card| card.to_html()), "\n");
+        Reply {
+            status,
+            content_type: "text/html; charset=utf-8",
+            body: format!(
+                "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+                 <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+                 <title>{}</title><style>{}</style></head>\n<body>\n{}\n</body></html>",
+                escape_html(title),
+                STYLE,
+                cards
+            ),
+        }
+    }
+    /// Create an error reply in the given `format`.
+    fn error(format: Format, status: u16, msg: &str) -> Self {
+        match format {
+            Format::Json => Reply::json(status, &json!({ "error": msg })),
+            Format::Html => Reply::html(status, "Error", &[Card::new().line(msg)]),
+        }
+    }
+}
+
+/// Answer the request for the given `url`, i.e. `/monsters?q=dragon`.
+pub fn route(data: &Data, url: &str) -> Reply {
+    let mut url = url.splitn(2, '?');
+    let path = url.next().unwrap_or_default();
+    let query = url.next().unwrap_or_default();
+    let mut segments: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(decode)
+        .collect();
+    let format = if segments.first().map(|s| s == "html").unwrap_or(false) {
+        segments.remove(0);
+        Format::Html
+    } else {
+        Format::Json
+    };
+    let segments: Vec<&str> = segments.iter().map(|s| s.as_str()).collect();
+    match segments.as_slice() {
+        ["roll"] => roll(format, &param(query, "expr").unwrap_or_default()),
+        ["list", category] => list(data, format, category, &regex_param(query)),
+        [category] if CATEGORIES.contains(category) => {
+            search(data, format, category, &regex_param(query))
+        }
+        [category, key] if CATEGORIES.contains(category) => get(data, format, category, key),
+        _ => Reply::error(format, 404, "Not found"),
+    }
+}
+
+/// Roll the given d20 `expr`.
+fn roll(format: Format, expr: &str) -> Reply {
+    match die::evaluate(expr) {
+        Ok(total) => match format {
+            Format::Json => Reply::json(200, &json!({ "expr": expr, "total": total })),
+            Format::Html => {
+                let line = format!("{}{{}}{}", expr, total);
+                Reply::html(200, expr, &[Card::new().line(&line)])
+            }
+        },
+        Err(fail) => Reply::error(format, 400, &fail),
+    }
+}
+
+/// List the names of all elements of `category` matching `re`.
+fn list(data: &Data, format: Format, category: &str, re: &str) -> Reply {
+    if let Err(e) = Regex::new(re) {
+        return Reply::error(format, 400, &e.to_string());
+    }
+    let categories: Vec<&str> = if category == "all" {
+        CATEGORIES.to_vec()
+    } else if CATEGORIES.contains(&category) {
+        vec![category]
+    } else {
+        return Reply::error(format, 404, "Unknown category");
+    };
+    let names = |category: &str| -> Vec<String> {
+        entries(data, category, re)
+            .iter()
+            .filter_map(|(value, _)| value["name"].as_str().map(String::from))
+            .collect()
+    };
+    match format {
+        Format::Json => {
+            let map = categories
+                .iter()
+                .map(|category| (category.to_string(), json!(names(category))))
+                .collect();
+            Reply::json(200, &Value::Object(map))
+        }
+        Format::Html => {
+            let cards: Vec<Card> = categories
+                .iter()
+                .map(|category| {
+                    Card::new()
+                        .line(category)
+                        .heavy_line()
+                        .list(names(category))
+                })
+                .collect();
+            Reply::html(200, category, &cards)
+        }
+    }
+}
+
+/// Get all elements of `category` whose name matches `re`.
+fn search(data: &Data, format: Format, category: &str, re: &str) -> Reply {
+    if let Err(e) = Regex::new(re) {
+        return Reply::error(format, 400, &e.to_string());
+    }
+    let (values, cards): (Vec<Value>, Vec<Card>) = entries(data, category, re).into_iter().unzip();
+    match format {
+        Format::Json => Reply::json(200, &Value::Array(values)),
+        Format::Html => Reply::html(200, category, &cards),
+    }
+}
+
+/// Get the element of `category` with the given `key`.
+fn get(data: &Data, format: Format, category: &str, key: &str) -> Reply {
+    let entry = match category {
+        "monsters" => data.monsters.get(key).map(|m| entry(m, m.card())),
+        "moves" => data.moves.get(key).map(|m| entry(m, m.card())),
+        "gm" => data.gm_moves.get(key).map(|m| entry(m, m.card())),
+        "items" => data.items.get(key).map(|i| entry(i, i.card())),
+        "tags" => data.tags.get(key).map(|t| entry(t, t.card())),
+        "classes" => data.classes.get(key).map(|c| entry(c, c.card())),
+        "tables" => data.tables.get(key).map(|t| entry(t, t.card())),
+        _ => None,
+    };
+    match (entry, format) {
+        (Some((value, _)), Format::Json) => Reply::json(200, &value),
+        (Some((_, card)), Format::Html) => Reply::html(200, key, &[card]),
+        (None, _) => Reply::error(format, 404, "Not found"),
+    }
+}
+
+/// Get all elements of `category` whose name matches `re` as JSON and card.
+/// `re` must be a valid regex.
+fn entries(data: &Data, category: &str, re: &str) -> Vec<(Value, Card)> {
+    match category {
+        "monsters" => data
+            .monsters
+            .filter(re)
+            .into_iter()
+            .map(|m| entry(m, m.card()))
+            .collect(),
+        "moves" => data
+            .moves
+            .filter(re)
+            .into_iter()
+            .map(|m| entry(m, m.card()))
+            .collect(),
+        "gm" => data
+            .gm_moves
+            .filter(re)
+            .into_iter()
+            .map(|m| entry(m, m.card()))
+            .collect(),
+        "items" => data
+            .items
+            .filter(re)
+            .into_iter()
+            .map(|i| entry(i, i.card()))
+            .collect(),
+        "tags" => data
+            .tags
+            .filter(re)
+            .into_iter()
+            .map(|t| entry(t, t.card()))
+            .collect(),
+        "classes" => data
+            .classes
+            .filter(re)
+            .into_iter()
+            .map(|c| entry(c, c.card()))
+            .collect(),
+        "tables" => data
+            .tables
+            .filter(re)
+            .into_iter()
+            .map(|t| entry(t, t.card()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Pair the serialized `el` with its `card`.
+fn entry<T: Serialize>(el: &T, card: Card) -> (Value, Card) {
+    (serde_json::to_value(el).unwrap_or(Value::Null), card)
+}
+
+/// Get the regex given by the `q` parameter of the `query`, matching everything by default.
+fn regex_param(query: &str) -> String {
+    param(query, "q").unwrap_or_else(|| String::from(".*"))
+}
+
+/// Get the decoded value of the parameter `name` in the `query`.
+fn param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .map(|pair| pair.splitn(2, '='))
+        .filter_map(|mut pair| match (pair.next(), pair.next()) {
+            (Some(key), value) if decode(key) == name => Some(decode(value.unwrap_or_default())),
+            _ => None,
+        })
+        .next()
+}
+
+/// Decode a percent-encoded URL component.
+fn decode(s: &str) -> String {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex: Vec<u8> = iter.clone().take(2).collect();
+                match u8::from_str_radix(&String::from_utf8_lossy(&hex), 16) {
+                    Ok(value) if hex.len() == 2 => {
+                        bytes.push(value);
+                        iter.nth(1);
+                    }
+                    _ => bytes.push(b),
+                }
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::DataPaths;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread;
+
+    fn data() -> Data {
+        Data::from(&DataPaths::default()).unwrap()
+    }
+
+    /// Send a GET request for `url` to `addr` and return the response.
+    fn get(addr: SocketAddr, url: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            url
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn decode_test() {
+        assert_eq!(decode("a+b%20c"), "a b c");
+        assert_eq!(decode("%C3%9F%2"), "ß%2");
+        assert_eq!(
+            param("q=drag&expr=1d6%2B2", "expr"),
+            Some(String::from("1d6+2"))
+        );
+        assert_eq!(param("q=drag", "expr"), None);
+    }
+
+    #[test]
+    fn routes() {
+        let data = data();
+        let reply = route(&data, "/monsters?q=apocalypse");
+        assert_eq!(reply.status, 200);
+        let value: Value = serde_json::from_str(&reply.body).unwrap();
+        assert_eq!(value[0]["name"], "Apocalypse Dragon");
+        let reply = route(&data, "/moves/anointed");
+        assert_eq!(reply.status, 200);
+        assert!(reply.body.contains("Chosen One"));
+        let reply = route(&data, "/gm/make_them_backtrack");
+        assert_eq!(reply.status, 200);
+        let reply = route(&data, "/list/classes?q=^cleric$");
+        assert_eq!(reply.body, r#"{"classes":["Cleric"]}"#);
+        let reply = route(&data, "/roll?expr=2d1%2B3");
+        assert_eq!(reply.body, r#"{"expr":"2d1+3","total":5}"#);
+        let reply = route(&data, "/list/monsters?q=^dragon$");
+        assert_eq!(reply.body, r#"{"monsters":["Dragon"]}"#);
+        let reply = route(&data, "/html/monsters?q=^dragon$");
+        assert!(reply.content_type.starts_with("text/html"));
+        assert!(reply.body.contains("<div class=\"card\">"));
+        assert_eq!(route(&data, "/monsters?q=(").status, 400);
+        assert_eq!(route(&data, "/roll?expr=chickens").status, 400);
+        assert_eq!(route(&data, "/moves/nope").status, 404);
+        assert_eq!(route(&data, "/spells").status, 404);
+    }
+
+    #[test]
+    fn localhost() {
+        let data = data();
+        let server = Server::bind(&data, "127.0.0.1:0").unwrap();
+        let addr = server.addr().unwrap();
+        thread::scope(|s| {
+            s.spawn(|| {
+                server.handle().unwrap();
+                server.handle().unwrap();
+            });
+            let response = get(addr, "/tags/ammo");
+            assert!(response.starts_with("HTTP/1.1 200"));
+            assert!(response.contains("application/json"));
+            assert!(response.contains("\"name\":\"n ammo\""));
+            let response = get(addr, "/html/nope");
+            assert!(response.starts_with("HTTP/1.1 404"));
+            assert!(response.contains("text/html"));
+        });
+    }
+}
@@ -0,0 +1,329 @@
+//! State of the TUI browser and its keyboard handling.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use data::card::Card;
+use data::Data;
+use die;
+use regex::Regex;
+
+/// A category shown in the sidebar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Monsters,
+    Moves,
+    Items,
+    Tags,
+    Classes,
+}
+
+/// All categories in the order of the sidebar.
+pub const CATEGORIES: [Category; 5] = [
+    Category::Monsters,
+    Category::Moves,
+    Category::Items,
+    Category::Tags,
+    Category::Classes,
+];
+
+/// What the keyboard input is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Navigate categories and the list.
+    Browse,
+    /// Edit the filter of the list.
+    Filter,
+    /// Enter a d20 expression to roll.
+    Roll,
+}
+
+/// An element of the list, with its name and key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub key: String,
+}
+
+/// State of the TUI browser.
+pub struct App<'a> {
+    data: &'a Data,
+    /// Index of the selected category in [CATEGORIES](CATEGORIES).
+    pub category: usize,
+    /// Regex the list is filtered with.
+    pub filter: String,
+    /// Index of the selected entry in the list.
+    pub selected: usize,
+    /// Current input mode.
+    pub mode: Mode,
+    /// The d20 expression being entered.
+    pub input: String,
+    /// Pinned cards, by category and key.
+    pub pinned: Vec<(Category, String)>,
+    /// Results of the rolled dice, the latest last.
+    pub dice_log: Vec<String>,
+    /// Scroll offset of the preview.
+    pub scroll: u16,
+    /// Whether the user wants to quit.
+    pub quit: bool,
+}
+
+impl Category {
+    /// Get the name of the category.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Category::Monsters => "Monsters",
+            Category::Moves => "Moves",
+            Category::Items => "Items",
+            Category::Tags => "Tags",
+            Category::Classes => "Classes",
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    /// Create a new browser for the given `data`.
+    pub fn new(data: &'a Data) -> Self {
+        App {
+            data,
+            category: 0,
+            filter: String::new(),
+            selected: 0,
+            mode: Mode::Browse,
+            input: String::new(),
+            pinned: Vec::new(),
+            dice_log: Vec::new(),
+            scroll: 0,
+            quit: false,
+        }
+    }
+    /// Get the selected category.
+    pub fn category(&self) -> Category {
+        CATEGORIES[self.category]
+    }
+    /// Get the error of the filter, if it is no valid regex.
+    pub fn filter_error(&self) -> Option<String> {
+        Regex::new(&self.filter).err().map(|e| e.to_string())
+    }
+    /// Get all entries of the selected category matching the filter.
+    pub fn entries(&self) -> Vec<Entry> {
+        if self.filter_error().is_some() {
+            return vec![];
+        }
+        let re = &self.filter;
+        let entry = |name: &str, key: &str| Entry {
+            name: name.to_string(),
+            key: key.to_string(),
+        };
+        match self.category() {
+            Category::Monsters => self
+                .data
+                .monsters
+                .filter(re)
+                .iter()
+                .map(|m| entry(m.name(), m.key()))
+                .collect(),
+            Category::Moves => self
+                .data
+                .moves
+                .filter(re)
+                .iter()
+                .map(|m| entry(m.name(), m.key()))
+                .collect(),
+            Category::Items => self
+                .data
+                .items
+                .filter(re)
+                .iter()
+                .map(|i| entry(i.name(), i.key()))
+                .collect(),
+            Category::Tags => self
+                .data
+                .tags
+                .filter(re)
+                .iter()
+                .map(|t| entry(t.name(), t.key()))
+                .collect(),
+            Category::Classes => self
+                .data
+                .classes
+                .filter(re)
+                .iter()
+                .map(|c| entry(c.name(), c.key()))
+                .collect(),
+        }
+    }
+    /// Get the card of the element of `category` with the given `key`.
+    pub fn card(&self, category: Category, key: &str) -> Option<Card> {
+        match category {
+            Category::Monsters => self.data.monsters.get(key).map(|m| m.card()),
+            Category::Moves => self.data.moves.get(key).map(|m| m.card()),
+            Category::Items => self.data.items.get(key).map(|i| i.card()),
+            Category::Tags => self.data.tags.get(key).map(|t| t.card()),
+            Category::Classes => self.data.classes.get(key).map(|c| c.card()),
+        }
+    }
+    /// Get the card of the selected entry.
+    pub fn selected_card(&self) -> Option<Card> {
+        self.entries()
+            .get(self.selected)
+            .and_then(|entry| self.card(self.category(), &entry.key))
+    }
+    /// Get the cards of all pinned entries.
+    pub fn pinned_cards(&self) -> Vec<Card> {
+        self.pinned
+            .iter()
+            .filter_map(|(category, key)| self.card(*category, key))
+            .collect()
+    }
+    /// Handle a pressed key.
+    pub fn key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit = true;
+            return;
+        }
+        match self.mode {
+            Mode::Browse => self.browse_key(key.code),
+            Mode::Filter => self.filter_key(key.code),
+            Mode::Roll => self.roll_key(key.code),
+        }
+    }
+    fn browse_key(&mut self, code: KeyCode) {
+        let len = self.entries().len();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.select_category((self.category + 1) % CATEGORIES.len())
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.select_category((self.category + CATEGORIES.len() - 1) % CATEGORIES.len())
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < len => {
+                self.select(self.selected + 1)
+            }
+            KeyCode::Up | KeyCode::Char('k') if self.selected > 0 => self.select(self.selected - 1),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(len.saturating_sub(1)),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(5),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(5),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('r') => self.mode = Mode::Roll,
+            KeyCode::Char('p') => self.toggle_pin(),
+            KeyCode::Char('c') => self.pinned.clear(),
+            _ => {}
+        }
+    }
+    fn filter_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter | KeyCode::Down => self.mode = Mode::Browse,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.select(0);
+                self.mode = Mode::Browse;
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.select(0);
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.select(0);
+            }
+            _ => {}
+        }
+    }
+    fn roll_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => {
+                let expr = self.input.trim().to_string();
+                if !expr.is_empty() {
+                    let result = match die::evaluate(&expr) {
+                        Ok(total) => format!("{} = {}", expr, total),
+                        Err(fail) => format!("{}: {}", expr, fail),
+                    };
+                    self.dice_log.push(result);
+                }
+                self.input.clear();
+                self.mode = Mode::Browse;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.mode = Mode::Browse;
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) => self.input.push(c),
+            _ => {}
+        }
+    }
+    /// Pin the selected entry, or unpin it if it's already pinned.
+    fn toggle_pin(&mut self) {
+        if let Some(entry) = self.entries().get(self.selected) {
+            let pin = (self.category(), entry.key.clone());
+            match self.pinned.iter().position(|p| *p == pin) {
+                Some(index) => {
+                    self.pinned.remove(index);
+                }
+                None => self.pinned.push(pin),
+            }
+        }
+    }
+    fn select_category(&mut self, category: usize) {
+        self.category = category;
+        self.select(0);
+    }
+    fn select(&mut self, selected: usize) {
+        self.selected = selected;
+        self.scroll = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data::DataPaths;
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            app.key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn navigation() {
+        let data = Data::from(&DataPaths::default()).unwrap();
+        let mut app = App::new(&data);
+        assert_eq!(app.category(), Category::Monsters);
+        // Filter the list live
+        press(&mut app, "/^drag");
+        assert_eq!(app.mode, Mode::Filter);
+        assert_eq!(app.entries()[0].name, "Dragon");
+        press(&mut app, "on t\njp");
+        assert_eq!(app.entries().len(), 1);
+        assert_eq!(
+            app.pinned,
+            vec![(Category::Monsters, String::from("dragon_turtle"))]
+        );
+        // Switch to the classes
+        press(&mut app, "\t\t\t\t");
+        assert_eq!(app.category(), Category::Classes);
+        assert_eq!(app.selected, 0);
+        press(&mut app, "/(");
+        assert!(app.filter_error().is_some());
+        assert!(app.entries().is_empty());
+        app.key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(app.entries().len(), data.classes.len());
+        press(&mut app, "jp");
+        assert_eq!(app.pinned_cards().len(), 2);
+        // Roll some dice
+        press(&mut app, "r2d1+1\nrnope\n");
+        assert_eq!(app.dice_log[0], "2d1+1 = 3");
+        assert!(app.dice_log[1].starts_with("nope: "));
+        press(&mut app, "q");
+        assert!(app.quit);
+    }
+}
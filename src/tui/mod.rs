@@ -0,0 +1,177 @@
+//! Full-screen terminal browser
+//!
+//! ```text
+//! ┌Categories─┐┌Monsters /drag──────┐┌Preview─────────────────────┐┌Pinned──────────┐
+//! │ Monsters  ││ Apocalypse Dragon  ││ ┏━━━━━━━━━━━━━━━━━━━━━━━━┓ ││ ┏━━━━━━━━━━━━┓ │
+//! │ Moves     ││ Dragon             ││ ┃ Dragon   16 HP 5 Armor ┃ ││ ┃ Hack & ... ┃ │
+//! │ ...       ││ ...                ││ ...                        ││ ...            │
+//! └───────────┘└────────────────────┘└────────────────────────────┘└────────────────┘
+//! ┌Dice─────────────────────────────────────┐┌Keys──────────────────────────────────┐
+//! │ 2d6+1 = 9                               ││ ←→ category  ↑↓ select  / filter ... │
+//! └─────────────────────────────────────────┘└──────────────────────────────────────┘
+//! ```
+//!
+//! See [App](app::App) for the state and the key bindings.
+
+pub mod app;
+
+use self::app::{App, Mode, CATEGORIES};
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use data::card::{strip_colors, Card};
+use data::Data;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+use std::io;
+
+/// Help shown in browse mode.
+const HELP: &str =
+    "←→ category  ↑↓ select  / filter  p pin  c clear pins  r roll  PgUp/PgDn scroll  q quit";
+
+/// Run the browser until the user quits.
+pub fn run(data: &Data) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let result = event_loop(&mut terminal, App::new(data));
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+/// Draw the `app` and handle keys until the user quits.
+fn event_loop<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|f| draw(f, &app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.key(key);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Draw the whole screen.
+fn draw(f: &mut Frame, app: &App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(8)])
+        .split(f.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(13),
+            Constraint::Length(30),
+            Constraint::Min(30),
+            Constraint::Percentage(30),
+        ])
+        .split(rows[0]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    draw_categories(f, app, columns[0]);
+    draw_list(f, app, columns[1]);
+    let preview: Vec<Card> = app.selected_card().into_iter().collect();
+    draw_cards(f, "Preview", &preview, app.scroll, columns[2]);
+    draw_cards(f, "Pinned", &app.pinned_cards(), 0, columns[3]);
+    draw_dice(f, app, bottom[0]);
+    draw_keys(f, app, bottom[1]);
+}
+
+fn draw_categories(f: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = CATEGORIES
+        .iter()
+        .map(|category| ListItem::new(category.name()))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(app.category));
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Categories"))
+        .highlight_style(highlight());
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_list(f: &mut Frame, app: &App, area: Rect) {
+    let entries = app.entries();
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| ListItem::new(entry.name.clone()))
+        .collect();
+    let mut title = String::from(app.category().name());
+    if !app.filter.is_empty() || app.mode == Mode::Filter {
+        title += &format!(" /{}", app.filter);
+    }
+    let mut block = Block::default().borders(Borders::ALL).title(title);
+    if app.filter_error().is_some() {
+        block = block.border_style(Style::default().fg(Color::Red));
+    }
+    let mut state = ListState::default();
+    state.select(if entries.is_empty() {
+        None
+    } else {
+        Some(app.selected)
+    });
+    let list = List::new(items).block(block).highlight_style(highlight());
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Draw the `cards` below each other into a box with the given `title`.
+fn draw_cards(f: &mut Frame, title: &str, cards: &[Card], scroll: u16, area: Rect) {
+    // Two columns for the box and four for the border of the card
+    let width = (area.width as usize).saturating_sub(6).max(10);
+    let text = cards
+        .iter()
+        .map(|card| strip_colors(&card.clone().with_width(width).to_string()))
+        .collect::<Vec<_>>()
+        .join("");
+    let paragraph = Paragraph::new(text.trim_start_matches('\n').to_string())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title.to_string()),
+        )
+        .scroll((scroll, 0));
+    f.render_widget(paragraph, area);
+}
+
+fn draw_dice(f: &mut Frame, app: &App, area: Rect) {
+    let visible = (area.height as usize).saturating_sub(2);
+    let skip = app.dice_log.len().saturating_sub(visible);
+    let log = app.dice_log[skip..].join("\n");
+    let paragraph = Paragraph::new(log).block(Block::default().borders(Borders::ALL).title("Dice"));
+    f.render_widget(paragraph, area);
+}
+
+fn draw_keys(f: &mut Frame, app: &App, area: Rect) {
+    let (title, text) = match app.mode {
+        Mode::Browse => ("Keys", HELP.to_string()),
+        Mode::Filter => (
+            "Filter",
+            match app.filter_error() {
+                Some(e) => format!("/{}▏\n\n{}", app.filter, e),
+                None => format!("/{}▏\n\nEnter accept  Esc clear", app.filter),
+            },
+        ),
+        Mode::Roll => ("Roll", format!("{}▏\n\nEnter roll  Esc cancel", app.input)),
+    };
+    let paragraph = Paragraph::new(text)
+        .wrap(::ratatui::widgets::Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(paragraph, area);
+}
+
+/// Style of the selected entries.
+fn highlight() -> Style {
+    Style::default()
+        .add_modifier(Modifier::REVERSED)
+        .add_modifier(Modifier::BOLD)
+}